@@ -1,37 +1,14 @@
 #[macro_use]
 extern crate dom;
 extern crate jss;
-extern crate yoga;
 
 use dom::events::*;
 use dom::node::*;
-use dom::setup::*;
 use dom::traits::*;
 use dom::tree::*;
 use dom::types::*;
 
 use jss::types::*;
-use yoga::Direction;
-
-fn debug_layout<'a>(node: &mut DOMArenaRefMut<'a, BasicEvent>) {
-    println!(
-        "Node: {:#?}\n",
-        node.raw
-            .try_value()
-            .and_then(|value| Some(value.layout_node.get_layout()))
-    );
-
-    let mut next_child_id = node.first_child_id();
-
-    while let Some(child_id) = next_child_id {
-        {
-            let mut child_ref = node.get_mut(child_id);
-            debug_layout(&mut child_ref);
-        }
-
-        next_child_id = node.get(child_id).next_sibling_id();
-    }
-}
 
 fn main() {
     let container_style = StyleBuilder::default()
@@ -103,15 +80,10 @@ fn main() {
         fragment
     };
 
-    // Build build-layout
-    let mut document = tree.document_mut();
-    {
-        document.build_layout();
-        document
-            .value_mut()
-            .reflow_subtree(1024, 768, Direction::LTR);
-    }
+    tree.generate_layout_tree();
+    tree.resize(1024.0, 768.0);
 
-    // Iterator
-    debug_layout(&mut document);
+    let snapshot = tree.layout_snapshot();
+    println!("{}", snapshot);
+    println!("{}", snapshot.to_json());
 }
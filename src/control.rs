@@ -0,0 +1,331 @@
+use node::{DOMAttributeValue, DOMNode, DOMNodeId, DOMTagName};
+use setup::DOMArenaRefMut;
+use traits::{TDOMNode, TGenericEvent, TLayoutNode};
+use tree::DOMTree;
+use types::{KnownAttributeName, KnownElementName};
+
+pub type FormData = Vec<(String, String)>;
+
+// See https://html.spec.whatwg.org/multipage/input.html#attr-input-type
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum ControlKind {
+    Text,
+    Checkbox,
+    Radio,
+    Range,
+    Select,
+    Submit,
+    Reset,
+    Button,
+}
+
+// State changed by the user or by the application, attributes provide the defaults until then.
+// See https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-fe-dirty
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ControlState {
+    value: Option<String>,
+    checked: Option<bool>,
+    selected_index: Option<usize>,
+    disabled: Option<bool>,
+    caret: Option<usize>,
+    pending_change: bool,
+}
+
+impl ControlState {
+    // Returns whether the value was edited since the last change event.
+    pub fn take_pending_change(&mut self) -> bool {
+        ::std::mem::replace(&mut self.pending_change, false)
+    }
+
+    pub fn mark_pending_change(&mut self) {
+        self.pending_change = true;
+    }
+}
+
+impl<T, L> DOMNode<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub fn control_kind(&self) -> Option<ControlKind> {
+        use self::KnownElementName::*;
+        let name = match self.data.tag() {
+            Some(&DOMTagName::KnownName(name)) => name,
+            _ => return None,
+        };
+        let ty = self.attribute_string("type");
+        match name {
+            Input => Some(match ty.as_ref().map(|v| v.as_str()) {
+                Some("checkbox") => ControlKind::Checkbox,
+                Some("radio") => ControlKind::Radio,
+                Some("range") => ControlKind::Range,
+                Some("submit") => ControlKind::Submit,
+                Some("reset") => ControlKind::Reset,
+                Some("button") => ControlKind::Button,
+                _ => ControlKind::Text,
+            }),
+            Button => Some(match ty.as_ref().map(|v| v.as_str()) {
+                Some("reset") => ControlKind::Reset,
+                Some("button") => ControlKind::Button,
+                _ => ControlKind::Submit,
+            }),
+            Textarea | TextInput => Some(ControlKind::Text),
+            Select | Picker => Some(ControlKind::Select),
+            Switch => Some(ControlKind::Checkbox),
+            Slider => Some(ControlKind::Range),
+            _ => None,
+        }
+    }
+
+    // See https://html.spec.whatwg.org/multipage/input.html#concept-input-value-default-on
+    pub fn value(&self) -> String {
+        if let Some(ref value) = self.control.value {
+            return value.clone();
+        }
+        match (self.attribute_string("value"), self.control_kind()) {
+            (Some(value), _) => value,
+            (None, Some(ControlKind::Checkbox)) | (None, Some(ControlKind::Radio)) => "on".to_string(),
+            (None, Some(ControlKind::Range)) => {
+                let (min, max, _) = self.range_bounds();
+                (min + (max - min) / 2.0).to_string()
+            }
+            (None, _) => String::new(),
+        }
+    }
+
+    // Moves the caret to the end of the new value.
+    pub fn set_value<V: Into<String>>(&mut self, value: V) {
+        self.control.value = Some(value.into());
+        self.control.caret = None;
+    }
+
+    pub fn caret(&self) -> usize {
+        let length = self.value().chars().count();
+        self.control.caret.map_or(length, |caret| caret.min(length))
+    }
+
+    pub fn set_caret(&mut self, caret: usize) {
+        self.control.caret = Some(caret);
+    }
+
+    // Returns the (min, max, step) of range controls.
+    pub fn range_bounds(&self) -> (f64, f64, f64) {
+        let number = |name, default| {
+            self.attribute_string(name)
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        };
+        (number("min", 0.0), number("max", 100.0), number("step", 1.0))
+    }
+
+    pub fn checked(&self) -> bool {
+        self.control.checked.unwrap_or_else(|| self.has_boolean_attribute("checked"))
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.control.disabled.unwrap_or_else(|| self.has_boolean_attribute("disabled"))
+    }
+
+    pub fn attribute_string(&self, name: &str) -> Option<String> {
+        match self.data.attributes_ref()?.simple.get(name) {
+            Some(&DOMAttributeValue::Str(ref value)) => Some(value.as_ref().to_string()),
+            Some(&DOMAttributeValue::Number(value)) => Some(value.to_string()),
+            Some(&DOMAttributeValue::Char(value)) => Some(value.to_string()),
+            Some(&DOMAttributeValue::Boolean(value)) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+
+    // See https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#boolean-attributes
+    pub fn has_boolean_attribute(&self, name: &str) -> bool {
+        match self.data.attributes_ref().and_then(|v| v.simple.get(name)) {
+            Some(&DOMAttributeValue::Boolean(value)) => value,
+            Some(_) => true,
+            None => false,
+        }
+    }
+}
+
+// Selectors of descendants can depend on the checked and disabled states, so that they're restyled too.
+impl<'a, T, L> DOMArenaRefMut<'a, T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub fn set_checked(&mut self, checked: bool) {
+        if self.control.checked != Some(checked) {
+            self.control.checked = Some(checked);
+            self.mark_subtree_dirty();
+        }
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        if self.control.disabled != Some(disabled) {
+            self.control.disabled = Some(disabled);
+            self.mark_subtree_dirty();
+        }
+    }
+
+    pub fn reset_control(&mut self) {
+        if self.control != ControlState::default() {
+            self.control = ControlState::default();
+            self.mark_subtree_dirty();
+        }
+    }
+}
+
+impl<T, L> DOMTree<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub fn element_by_id(&self, element_id: &str) -> Option<DOMNodeId<T, L>> {
+        self.root().descendants_ids_iter().find(|&id| {
+            let attributes = self.get(id).data.attributes_ref();
+            match attributes.and_then(|v| v.common.get(&KnownAttributeName::Id)) {
+                Some(&DOMAttributeValue::Str(ref value)) => value.as_ref() == element_id,
+                _ => false,
+            }
+        })
+    }
+
+    // Only rendered and visible text is extracted, like with `innerText`.
+    // See https://html.spec.whatwg.org/multipage/dom.html#the-innertext-idl-attribute
+    pub fn text_content(&self, id: DOMNodeId<T, L>) -> String {
+        self.get(id)
+            .descendants_ids_iter()
+            .filter(|&id| self.is_visible(id))
+            .filter_map(|id| self.get(id).into_value().data.text())
+            .map(|text| text.as_ref())
+            .collect()
+    }
+
+    // See https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#form-owner
+    pub fn form_owner(&self, id: DOMNodeId<T, L>) -> Option<DOMNodeId<T, L>> {
+        let mut next_id = self.get(id).parent_id();
+        while let Some(ancestor_id) = next_id {
+            let ancestor = self.get(ancestor_id);
+            if ancestor.is_known(KnownElementName::Form) {
+                return Some(ancestor_id);
+            }
+            next_id = ancestor.parent_id();
+        }
+        None
+    }
+
+    // See https://html.spec.whatwg.org/multipage/forms.html#labeled-control
+    pub fn labeled_control(&self, label: DOMNodeId<T, L>) -> Option<DOMNodeId<T, L>> {
+        match self.get(label).attribute_string("for") {
+            Some(element_id) => self
+                .element_by_id(&element_id)
+                .filter(|&id| self.get(id).control_kind().is_some()),
+            None => self
+                .get(label)
+                .descendants_ids_iter()
+                .find(|&id| self.get(id).control_kind().is_some()),
+        }
+    }
+
+    pub fn options(&self, select: DOMNodeId<T, L>) -> Vec<DOMNodeId<T, L>> {
+        self.get(select)
+            .descendants_ids_iter()
+            .filter(|&id| self.get(id).is_known(KnownElementName::Option))
+            .collect()
+    }
+
+    // See https://html.spec.whatwg.org/multipage/form-elements.html#dom-select-selectedindex
+    pub fn selected_index(&self, select: DOMNodeId<T, L>) -> Option<usize> {
+        let options = self.options(select);
+        if let Some(index) = self.get(select).control.selected_index {
+            return Some(index).filter(|&index| index < options.len());
+        }
+        options
+            .iter()
+            .position(|&id| self.get(id).has_boolean_attribute("selected"))
+            .or_else(|| if options.is_empty() { None } else { Some(0) })
+    }
+
+    // Returns false when there's no option at that index.
+    pub fn set_selected_index(&mut self, select: DOMNodeId<T, L>, index: usize) -> bool {
+        if index >= self.options(select).len() {
+            return false;
+        }
+        self.get_mut(select).control.selected_index = Some(index);
+        true
+    }
+
+    // Select controls take the value of their selected option.
+    pub fn control_value(&self, id: DOMNodeId<T, L>) -> String {
+        let node = self.get(id);
+        if node.control_kind() != Some(ControlKind::Select) {
+            return node.value();
+        }
+        let option = self
+            .selected_index(id)
+            .and_then(|index| self.options(id).get(index).cloned());
+        match option {
+            Some(option) => self
+                .get(option)
+                .attribute_string("value")
+                .unwrap_or_else(|| self.text_content(option).trim().to_string()),
+            None => String::new(),
+        }
+    }
+
+    // Radio buttons sharing a name and a form owner.
+    // See https://html.spec.whatwg.org/multipage/input.html#radio-button-group
+    pub fn radio_group(&self, radio: DOMNodeId<T, L>) -> Vec<DOMNodeId<T, L>> {
+        let name = match self.get(radio).attribute_string("name") {
+            Some(name) => name,
+            None => return vec![radio],
+        };
+        let owner = self.form_owner(radio);
+        self.root()
+            .descendants_ids_iter()
+            .filter(|&id| {
+                let node = self.get(id);
+                node.control_kind() == Some(ControlKind::Radio)
+                    && node.attribute_string("name").as_ref() == Some(&name)
+                    && self.form_owner(id) == owner
+            })
+            .collect()
+    }
+
+    pub fn form_controls(&self, form: DOMNodeId<T, L>) -> Vec<DOMNodeId<T, L>> {
+        self.get(form)
+            .descendants_ids_iter()
+            .filter(|&id| self.get(id).control_kind().is_some() && self.form_owner(id) == Some(form))
+            .collect()
+    }
+
+    // Returns None when the node isn't a form.
+    // See https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#constructing-the-form-data-set
+    pub fn form_data(&self, form: DOMNodeId<T, L>) -> Option<FormData> {
+        if !self.get(form).is_known(KnownElementName::Form) {
+            return None;
+        }
+
+        let mut data = FormData::new();
+        for id in self.form_controls(form) {
+            let node = self.get(id);
+            let name = match node.attribute_string("name") {
+                Some(ref name) if !name.is_empty() && !node.is_disabled() => name.clone(),
+                _ => continue,
+            };
+            match node.control_kind() {
+                Some(ControlKind::Checkbox) | Some(ControlKind::Radio) if !node.checked() => continue,
+                Some(ControlKind::Submit) | Some(ControlKind::Reset) | Some(ControlKind::Button) => continue,
+                Some(ControlKind::Select) if self.selected_index(id).is_none() => continue,
+                _ => data.push((name, self.control_value(id))),
+            }
+        }
+        Some(data)
+    }
+
+    // See https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-form-reset
+    pub fn reset_form(&mut self, form: DOMNodeId<T, L>) {
+        for id in self.form_controls(form) {
+            self.get_mut(id).reset_control();
+        }
+    }
+}
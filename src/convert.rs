@@ -14,29 +14,31 @@ use jss::types::Style;
 use std::borrow::Cow;
 use std::rc::Rc;
 
-use types::{Closure, EventType, KnownAttributeName, KnownElementName, Prop};
+use types::{Atom, Closure, EventType, KnownAttributeName, KnownElementName, Prop};
 
 use node::{
     DOMAttribute, DOMAttributeName, DOMAttributeValue, DOMAttributes, DOMData, DOMNode,
     DOMNormalNode, DOMTagName, DOMText, DOMTextNode,
 };
 
-use traits::TGenericEvent;
+use traits::{TGenericEvent, TLayoutNode};
 
 use tree::DOMTree;
 
-impl<E> From<()> for DOMNode<E>
+impl<E, L> From<()> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(_: ()) -> Self {
         DOMNode::new(DOMData::Void)
     }
 }
 
-impl<E> From<Style> for DOMNode<E>
+impl<E, L> From<Style> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(style: Style) -> Self {
         let mut node = DOMNode::new(DOMData::Void);
@@ -45,9 +47,10 @@ where
     }
 }
 
-impl<E> From<&'static str> for DOMNode<E>
+impl<E, L> From<&'static str> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(text: &'static str) -> Self {
         DOMNode::new(DOMData::Text(DOMTextNode {
@@ -56,9 +59,10 @@ where
     }
 }
 
-impl<E> From<String> for DOMNode<E>
+impl<E, L> From<String> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(text: String) -> Self {
         DOMNode::new(DOMData::Text(DOMTextNode {
@@ -82,27 +86,34 @@ impl_text_node_from_stringifiable!(isize);
 impl_text_node_from_stringifiable!(usize);
 impl_text_node_from_stringifiable!(char);
 
-impl <E>Default for DOMAttributes<E>
-where E: TGenericEvent {
-    fn default() -> DOMAttributes<E> {
-        let namespaced: HashMap<(&'static str, &'static str), DOMAttributeValue<E>> = HashMap::default();
-        let common: HashMap<KnownAttributeName, DOMAttributeValue<E>> = HashMap::default();
-        let simple: HashMap<&'static str, DOMAttributeValue<E>> = HashMap::default();
+impl<E, L> Default for DOMAttributes<E, L>
+where
+    E: TGenericEvent,
+    L: TLayoutNode,
+{
+    fn default() -> DOMAttributes<E, L> {
+        let namespaced: HashMap<(&'static str, &'static str), DOMAttributeValue<E, L>> = HashMap::default();
+        let common: HashMap<KnownAttributeName, DOMAttributeValue<E, L>> = HashMap::default();
+        let simple: HashMap<&'static str, DOMAttributeValue<E, L>> = HashMap::default();
         let listeners: HashMap<EventType, Closure<E>> = HashMap::default();
+        let custom_listeners: HashMap<Atom, Closure<E>> = HashMap::default();
 
         DOMAttributes {
             namespaced,
             listeners,
+            custom_listeners,
             common,
             simple,
         }
     }
 }
 
-impl<E> From<Vec<DOMAttribute<E>>> for DOMAttributes<E>
-where E: TGenericEvent,
+impl<E, L> From<Vec<DOMAttribute<E, L>>> for DOMAttributes<E, L>
+where
+    E: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from(list: Vec<DOMAttribute<E>>) -> DOMAttributes<E> {
+    fn from(list: Vec<DOMAttribute<E, L>>) -> DOMAttributes<E, L> {
         let mut attributes = DOMAttributes::default();
         use self::DOMAttributeName::*;
         
@@ -123,6 +134,15 @@ where E: TGenericEvent,
                         false
                     }
                 },
+
+                CustomEvent(name) => {
+                    if let DOMAttributeValue::EventListener(listener) = value {
+                        attributes.custom_listeners.insert(name, listener).is_some();
+                        true
+                    } else {
+                        false
+                    }
+                },
             };
         }
 
@@ -130,9 +150,10 @@ where E: TGenericEvent,
     }
 }
 
-impl<E> From<DOMTagName> for DOMNode<E>
+impl<E, L> From<DOMTagName> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(tag: DOMTagName) -> Self {
         let attributes = DOMAttributes::default();
@@ -140,18 +161,20 @@ where
     }
 }
 
-impl<E> From<(DOMTagName, DOMAttributes<E>)> for DOMNode<E>
+impl<E, L> From<(DOMTagName, DOMAttributes<E, L>)> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from((tag, attributes): (DOMTagName, DOMAttributes<E>)) -> Self {
+    fn from((tag, attributes): (DOMTagName, DOMAttributes<E, L>)) -> Self {
         DOMNode::new(DOMData::Normal(DOMNormalNode { tag, attributes }))
     }
 }
 
-impl<E> From<(DOMTagName, Style)> for DOMNode<E>
+impl<E, L> From<(DOMTagName, Style)> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from((tag, style): (DOMTagName, Style)) -> Self {
         let mut node = DOMNode::from((tag, DOMAttributes::default()));
@@ -160,42 +183,46 @@ where
     }
 }
 
-impl<E> From<(DOMTagName, DOMAttributes<E>, Style)> for DOMNode<E>
+impl<E, L> From<(DOMTagName, DOMAttributes<E, L>, Style)> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from((tag, attributes, style): (DOMTagName, DOMAttributes<E>, Style)) -> Self {
+    fn from((tag, attributes, style): (DOMTagName, DOMAttributes<E, L>, Style)) -> Self {
         let mut node = DOMNode::from((tag, attributes));
         node.styles = style;
         node
     }
 }
 
-impl<E> From<(DOMTagName, Vec<DOMAttribute<E>>, Style)> for DOMNode<E>
+impl<E, L> From<(DOMTagName, Vec<DOMAttribute<E, L>>, Style)> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from((tag, attributes, style): (DOMTagName, Vec<DOMAttribute<E>>, Style)) -> Self {
+    fn from((tag, attributes, style): (DOMTagName, Vec<DOMAttribute<E, L>>, Style)) -> Self {
         let mut node = DOMNode::from((tag, DOMAttributes::from(attributes)));
         node.styles = style;
         node
     }
 }
 
-impl<E> From<(DOMTagName, Vec<DOMAttribute<E>>)> for DOMNode<E>
+impl<E, L> From<(DOMTagName, Vec<DOMAttribute<E, L>>)> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from((tag, attributes): (DOMTagName, Vec<DOMAttribute<E>>)) -> Self {
+    fn from((tag, attributes): (DOMTagName, Vec<DOMAttribute<E, L>>)) -> Self {
         DOMNode::from((tag, DOMAttributes::from(attributes)))
     }
 }
 
-impl<E> From<DOMTree<E>> for DOMNode<E>
+impl<E, L> From<DOMTree<E, L>> for DOMNode<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from(tree: DOMTree<E>) -> Self {
+    fn from(tree: DOMTree<E, L>) -> Self {
         DOMNode::new(DOMData::ShadowHost(tree))
     }
 }
@@ -230,11 +257,12 @@ impl From<(&'static str, &'static str)> for DOMTagName {
     }
 }
 
-impl<E> From<(DOMAttributeName, DOMAttributeValue<E>)> for DOMAttribute<E>
+impl<E, L> From<(DOMAttributeName, DOMAttributeValue<E, L>)> for DOMAttribute<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from((name, value): (DOMAttributeName, DOMAttributeValue<E>)) -> Self {
+    fn from((name, value): (DOMAttributeName, DOMAttributeValue<E, L>)) -> Self {
         DOMAttribute(name, value)
     }
 }
@@ -251,6 +279,12 @@ impl From<EventType> for DOMAttributeName {
     }
 }
 
+impl From<Atom> for DOMAttributeName {
+    fn from(name: Atom) -> Self {
+        DOMAttributeName::CustomEvent(name)
+    }
+}
+
 impl From<&'static str> for DOMAttributeName {
     fn from(name: &'static str) -> Self {
         DOMAttributeName::Simple(name)
@@ -263,18 +297,20 @@ impl From<(&'static str, &'static str)> for DOMAttributeName {
     }
 }
 
-impl<E> From<bool> for DOMAttributeValue<E>
+impl<E, L> From<bool> for DOMAttributeValue<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(value: bool) -> Self {
         DOMAttributeValue::Boolean(value)
     }
 }
 
-impl<E> From<f64> for DOMAttributeValue<E>
+impl<E, L> From<f64> for DOMAttributeValue<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(value: f64) -> Self {
         DOMAttributeValue::Number(value)
@@ -293,65 +329,72 @@ impl_number_attribute_from_countable!(f32);
 impl_number_attribute_from_countable!(isize);
 impl_number_attribute_from_countable!(usize);
 
-impl<E> From<char> for DOMAttributeValue<E>
+impl<E, L> From<char> for DOMAttributeValue<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(value: char) -> Self {
         DOMAttributeValue::Char(value)
     }
 }
 
-impl<E> From<&'static str> for DOMAttributeValue<E>
+impl<E, L> From<&'static str> for DOMAttributeValue<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(value: &'static str) -> Self {
         DOMAttributeValue::Str(DOMText::from(value))
     }
 }
 
-impl<E> From<String> for DOMAttributeValue<E>
+impl<E, L> From<String> for DOMAttributeValue<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(value: String) -> Self {
         DOMAttributeValue::Str(DOMText::from(value))
     }
 }
 
-impl<E> From<DOMText> for DOMAttributeValue<E>
+impl<E, L> From<DOMText> for DOMAttributeValue<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(value: DOMText) -> Self {
         DOMAttributeValue::Str(value)
     }
 }
 
-impl<E> From<Prop> for DOMAttributeValue<E>
+impl<E, L> From<Prop> for DOMAttributeValue<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(value: Prop) -> Self {
         DOMAttributeValue::Prop(value)
     }
 }
 
-impl<E> From<Closure<E>> for DOMAttributeValue<E>
+impl<E, L> From<Closure<E>> for DOMAttributeValue<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
     fn from(value: Closure<E>) -> Self {
         DOMAttributeValue::EventListener(value)
     }
 }
 
-impl<E> From<DOMNode<E>> for DOMAttributeValue<E>
+impl<E, L> From<DOMNode<E, L>> for DOMAttributeValue<E, L>
 where
     E: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from(value: DOMNode<E>) -> Self {
+    fn from(value: DOMNode<E, L>) -> Self {
         DOMAttributeValue::Node(value)
     }
 }
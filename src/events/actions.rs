@@ -0,0 +1,271 @@
+use hashbrown::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use events::{ancestors, forms, node_id, BasicEvent, EventTarget};
+use layout::LayoutNode;
+use node::DOMTagName;
+use traits::{TEvent, TLayoutNode, TMouseEvent};
+use tree::DOMTree;
+use types::{DOMNodeRawId, EventType, KeyCode, KnownElementName};
+
+// Returns false when the action didn't apply, so that the next element in the event path gets a chance.
+pub type DefaultAction<L = LayoutNode> = Rc<Fn(&mut DefaultActionContext<L>) -> bool>;
+
+// Pixels scrolled per line, for wheel events in line mode.
+const WHEEL_LINE_HEIGHT: f32 = 16.0;
+
+// Things only the embedder can do, like following links or showing native menus.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum DefaultActionRequest {
+    Navigate { target: DOMNodeRawId, href: String },
+    ContextMenu { target: DOMNodeRawId, page_x: u32, page_y: u32 },
+}
+
+// Behaviour which runs after an event was dispatched, unless a listener called `prevent_default`.
+// See https://dom.spec.whatwg.org/#concept-event-dispatch
+pub struct DefaultActions<L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    elements: HashMap<(KnownElementName, EventType), DefaultAction<L>>,
+    global: HashMap<EventType, DefaultAction<L>>,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub(crate) struct DefaultActionOutcome<L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    pub events: Vec<BasicEvent>,
+    pub focus: Option<Option<EventTarget<L>>>,
+    pub requests: Vec<DefaultActionRequest>,
+}
+
+pub struct DefaultActionContext<'a, L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    pub tree: &'a mut DOMTree<BasicEvent, L>,
+    pub event: &'a BasicEvent,
+    // The element in the event path whose action is running.
+    pub element: EventTarget<L>,
+    outcome: DefaultActionOutcome<L>,
+}
+
+impl<'a, L> DefaultActionContext<'a, L>
+where
+    L: TLayoutNode,
+{
+    pub fn target(&self) -> EventTarget<L> {
+        node_id(self.event.target())
+    }
+
+    // Fires an event right after the current one, its own default action runs as well.
+    pub fn fire(&mut self, event: BasicEvent) {
+        self.outcome.events.push(event);
+    }
+
+    pub fn focus(&mut self, target: Option<EventTarget<L>>) {
+        self.outcome.focus = Some(target);
+    }
+
+    pub fn request(&mut self, request: DefaultActionRequest) {
+        self.outcome.requests.push(request);
+    }
+}
+
+impl<L> Default for DefaultActions<L>
+where
+    L: TLayoutNode,
+{
+    fn default() -> Self {
+        use self::KnownElementName::*;
+        let mut actions = DefaultActions::empty();
+
+        actions.set(A, EventType::Click, follow_link);
+        actions.set(ScrollView, EventType::Wheel, scroll_on_wheel);
+        actions.set_global(EventType::MouseDown, focus_on_mouse_down);
+        actions.set_global(EventType::KeyDown, navigate_focus);
+        actions.set_global(EventType::ContextMenu, show_context_menu);
+
+        for &name in &[Input, Button, Switch] {
+            actions.set(name, EventType::Click, forms::activate);
+        }
+        for &name in &[Input, Textarea, TextInput, Select, Picker, Slider, Switch, Button] {
+            actions.set(name, EventType::KeyDown, forms::run_key_behavior);
+        }
+        actions.set(Label, EventType::Click, forms::forward_label_click);
+        actions.set(Form, EventType::Reset, forms::reset_form);
+
+        actions
+    }
+}
+
+impl<L> DefaultActions<L>
+where
+    L: TLayoutNode,
+{
+    pub fn empty() -> Self {
+        DefaultActions {
+            elements: HashMap::default(),
+            global: HashMap::default(),
+        }
+    }
+
+    pub fn set<F: 'static>(&mut self, name: KnownElementName, ty: EventType, action: F)
+    where
+        F: Fn(&mut DefaultActionContext<L>) -> bool,
+    {
+        self.elements.insert((name, ty), Rc::new(action));
+    }
+
+    pub fn remove(&mut self, name: KnownElementName, ty: EventType) -> bool {
+        self.elements.remove(&(name, ty)).is_some()
+    }
+
+    // Global actions run when no element in the event path handled the event.
+    pub fn set_global<F: 'static>(&mut self, ty: EventType, action: F)
+    where
+        F: Fn(&mut DefaultActionContext<L>) -> bool,
+    {
+        self.global.insert(ty, Rc::new(action));
+    }
+
+    pub fn remove_global(&mut self, ty: EventType) -> bool {
+        self.global.remove(&ty).is_some()
+    }
+
+    pub(crate) fn run(
+        &self,
+        tree: &mut DOMTree<BasicEvent, L>,
+        event: &BasicEvent,
+    ) -> DefaultActionOutcome<L> {
+        let ty = event.ty();
+        let target = node_id(event.target());
+        if event.default_prevented() || !tree.contains(target) {
+            return DefaultActionOutcome::default();
+        }
+
+        let path = if ty.bubbles() {
+            ancestors(tree, target)
+        } else {
+            vec![target]
+        };
+
+        let mut ctx = DefaultActionContext {
+            tree,
+            event,
+            element: target,
+            outcome: DefaultActionOutcome::default(),
+        };
+
+        let mut handled = false;
+        for id in path {
+            let action = match ctx.tree.get(id).data.tag() {
+                Some(&DOMTagName::KnownName(name)) => self.elements.get(&(name, ty)),
+                _ => None,
+            };
+            if let Some(action) = action {
+                ctx.element = id;
+                if action(&mut ctx) {
+                    handled = true;
+                    break;
+                }
+            }
+        }
+
+        if let (false, Some(action)) = (handled, self.global.get(&ty)) {
+            ctx.element = target;
+            action(&mut ctx);
+        }
+
+        ctx.outcome
+    }
+}
+
+// See https://html.spec.whatwg.org/multipage/links.html#following-hyperlinks-2
+fn follow_link<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>) -> bool {
+    let href = ctx.tree.get(ctx.element).attribute_string("href");
+    match href {
+        Some(href) => {
+            let target = ctx.event.target();
+            ctx.request(DefaultActionRequest::Navigate { target, href });
+            true
+        }
+        None => false,
+    }
+}
+
+// Scroll containers which can't move any further let the wheel event through to their ancestors.
+fn scroll_on_wheel<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>) -> bool {
+    let layout = ctx.tree.get(ctx.element).layout_node.get_layout();
+    let page = (layout.width(), layout.height());
+    let (dx, dy) = ctx.event.wheel().pixel_delta(WHEEL_LINE_HEIGHT, page);
+    ctx.tree.get_mut(ctx.element).scroll_by(dx, dy)
+}
+
+// See https://html.spec.whatwg.org/multipage/interaction.html#focusing-steps
+fn focus_on_mouse_down<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>) -> bool {
+    let target = ctx.target();
+    let focusable = ancestors(ctx.tree, target)
+        .into_iter()
+        .find(|&id| ctx.tree.get(id).is_focusable());
+    ctx.focus(focusable);
+    true
+}
+
+// Tab moves to the next node in the focus order, Shift+Tab to the previous one.
+// See https://html.spec.whatwg.org/multipage/interaction.html#sequential-focus-navigation
+fn navigate_focus<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>) -> bool {
+    let modifiers = *ctx.event.keyboard().modifiers();
+    if ctx.event.keyboard().code() != KeyCode::Tab || modifiers.ctrl || modifiers.alt || modifiers.meta {
+        return false;
+    }
+    let next = ctx.tree.next_focusable(ctx.tree.active_element(), modifiers.shift);
+    ctx.focus(next);
+    true
+}
+
+fn show_context_menu<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>) -> bool {
+    let request = DefaultActionRequest::ContextMenu {
+        target: ctx.event.target(),
+        page_x: ctx.event.page_x(),
+        page_y: ctx.event.page_y(),
+    };
+    ctx.request(request);
+    true
+}
+
+fn same_actions<K, L>(this: &HashMap<K, DefaultAction<L>>, that: &HashMap<K, DefaultAction<L>>) -> bool
+where
+    K: Eq + Hash,
+    L: TLayoutNode,
+{
+    this.len() == that.len()
+        && this
+            .iter()
+            .all(|(key, action)| that.get(key).map_or(false, |other| Rc::ptr_eq(action, other)))
+}
+
+impl<L> PartialEq for DefaultActions<L>
+where
+    L: TLayoutNode,
+{
+    fn eq(&self, other: &Self) -> bool {
+        same_actions(&self.elements, &other.elements) && same_actions(&self.global, &other.global)
+    }
+}
+
+impl<L> fmt::Debug for DefaultActions<L>
+where
+    L: TLayoutNode,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("DefaultActions")
+            .field("elements", &self.elements.keys().collect::<Vec<_>>())
+            .field("global", &self.global.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
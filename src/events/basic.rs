@@ -1,23 +1,32 @@
 use serde::{Deserialize, Serialize};
-use types::{DOMNodeRawId, EventType};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use types::{Atom, DOMNodeRawId, EventType, KeyCode, KeyModifier, KeyModifiers, KeyboardLayout};
 
-use traits::{TEvent, TGenericEvent, TKeyboardEvent, TMouseEvent, TUIEvent};
+use events::DataTransfer;
+use traits::{
+    TAnimationEvent, TCustomEvent, TDragEvent, TEvent, TGenericEvent, TKeyboardEvent, TMouseEvent, TPointerEvent,
+    TUIEvent, TWheelEvent,
+};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 pub struct MouseEvent {
-    target_mouse_code: u64,
+    button: MouseButton,
 
     client_x: u32,
     client_y: u32,
-    
+
     offset_x: u32,
     offset_y: u32,
 
     page_x: u32,
     page_y: u32,
+
+    timestamp: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum MouseButton {
     Main = 0, // Main button pressed, usually the left button or the un-initialized state
     Auxiliary = 1, // Auxiliary button pressed, usually the wheel button or the middle button (if present)
@@ -26,21 +35,346 @@ pub enum MouseButton {
     //Fifth = 4, // Fifth button, typically the Browser Forward button
 }
 
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PointerType {
+    Mouse,
+    Pen,
+    Touch,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PointerEvent {
+    pointer_id: u32,
+    pointer_type: PointerType,
+    pressure: f32,
+    width: f32,
+    height: f32,
+    is_primary: bool,
+}
+
+// See https://developer.mozilla.org/en-US/docs/Web/API/WheelEvent/deltaMode
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WheelDeltaMode {
+    Pixel = 0,
+    Line = 1,
+    Page = 2,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct WheelEvent {
+    delta_x: f64,
+    delta_y: f64,
+    delta_z: f64,
+    delta_mode: WheelDeltaMode,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 pub struct KeyboardEvent {
-    shift_pressed: bool,
-    ctrl_pressed: bool,
-    meta_pressed: bool,
-    alt_pressed: bool,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    layout: KeyboardLayout,
+    repeat: bool,
+
+    timestamp: u64,
+}
+
+// Data of transition and animation events.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AnimationEvent {
+    name: String,
+    elapsed_time: f32,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct BasicEvent {
     event_type: EventType,
     node_id: DOMNodeRawId,
+    related_node_id: Option<DOMNodeRawId>,
 
     keyboard: KeyboardEvent,
     mouse: MouseEvent,
+    pointer: PointerEvent,
+    wheel: WheelEvent,
+    data_transfer: Option<DataTransfer>,
+    animation: Option<AnimationEvent>,
+
+    custom_type: Option<Atom>,
+    detail: Option<Value>,
+
+    #[serde(skip)]
+    default_prevented: DefaultPrevented,
+}
+
+// Shared by every clone handed to listeners, so the manager can see it after dispatch.
+// Atomic, so that events can be handed to listeners on other threads.
+#[derive(Clone, Debug, Default)]
+struct DefaultPrevented(Arc<AtomicBool>);
+
+impl DefaultPrevented {
+    fn get(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    fn set(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+impl PartialEq for DefaultPrevented {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl Default for PointerType {
+    fn default() -> Self {
+        PointerType::Mouse
+    }
+}
+
+impl Default for PointerEvent {
+    fn default() -> Self {
+        PointerEvent::new(1, PointerType::Mouse, true)
+    }
+}
+
+impl PointerEvent {
+    pub fn new(pointer_id: u32, pointer_type: PointerType, is_primary: bool) -> Self {
+        PointerEvent {
+            pointer_id,
+            pointer_type,
+            pressure: 0.0,
+            width: 1.0,
+            height: 1.0,
+            is_primary,
+        }
+    }
+
+    pub fn with_pressure(mut self, pressure: f32) -> Self {
+        self.pressure = pressure;
+        self
+    }
+
+    pub fn with_contact_size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn pointer_id(&self) -> u32 {
+        self.pointer_id
+    }
+
+    pub fn pointer_type(&self) -> PointerType {
+        self.pointer_type
+    }
+
+    pub fn is_primary(&self) -> bool {
+        self.is_primary
+    }
+}
+
+impl Default for WheelDeltaMode {
+    fn default() -> Self {
+        WheelDeltaMode::Pixel
+    }
+}
+
+impl WheelEvent {
+    pub fn new(delta_x: f64, delta_y: f64, delta_mode: WheelDeltaMode) -> Self {
+        WheelEvent {
+            delta_x,
+            delta_y,
+            delta_z: 0.0,
+            delta_mode,
+        }
+    }
+
+    pub fn with_delta_z(mut self, delta_z: f64) -> Self {
+        self.delta_z = delta_z;
+        self
+    }
+
+    pub fn delta_mode(&self) -> WheelDeltaMode {
+        self.delta_mode
+    }
+
+    // Converts the deltas to pixels, given the line height and the size of a page.
+    pub fn pixel_delta(&self, line_height: f32, (page_width, page_height): (f32, f32)) -> (f32, f32) {
+        let (delta_x, delta_y) = (self.delta_x as f32, self.delta_y as f32);
+        match self.delta_mode {
+            WheelDeltaMode::Pixel => (delta_x, delta_y),
+            WheelDeltaMode::Line => (delta_x * line_height, delta_y * line_height),
+            WheelDeltaMode::Page => (delta_x * page_width, delta_y * page_height),
+        }
+    }
+}
+
+impl Default for MouseButton {
+    fn default() -> Self {
+        MouseButton::Main
+    }
+}
+
+impl MouseEvent {
+    pub fn new(button: MouseButton, (page_x, page_y): (u32, u32), timestamp: u64) -> Self {
+        MouseEvent {
+            button,
+            client_x: page_x,
+            client_y: page_y,
+            offset_x: 0,
+            offset_y: 0,
+            page_x,
+            page_y,
+            timestamp,
+        }
+    }
+
+    pub fn button(&self) -> MouseButton {
+        self.button
+    }
+
+    pub fn page_point(&self) -> (u32, u32) {
+        (self.page_x, self.page_y)
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn with_client_point(mut self, (client_x, client_y): (u32, u32)) -> Self {
+        self.client_x = client_x;
+        self.client_y = client_y;
+        self
+    }
+
+    pub fn with_offset_point(mut self, (offset_x, offset_y): (u32, u32)) -> Self {
+        self.offset_x = offset_x;
+        self.offset_y = offset_y;
+        self
+    }
+}
+
+impl KeyboardEvent {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers, repeat: bool) -> Self {
+        KeyboardEvent {
+            code,
+            modifiers,
+            layout: KeyboardLayout::default(),
+            repeat,
+            timestamp: 0,
+        }
+    }
+
+    pub fn with_layout(mut self, layout: KeyboardLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn code(&self) -> KeyCode {
+        self.code
+    }
+
+    pub fn key(&self) -> &'static str {
+        self.layout.key(self.code, &self.modifiers)
+    }
+
+    pub fn modifiers(&self) -> &KeyModifiers {
+        &self.modifiers
+    }
+}
+
+impl AnimationEvent {
+    pub fn new<N: Into<String>>(name: N, elapsed_time: f32) -> Self {
+        AnimationEvent {
+            name: name.into(),
+            elapsed_time,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn elapsed_time(&self) -> f32 {
+        self.elapsed_time
+    }
+}
+
+impl BasicEvent {
+    pub fn new(event_type: EventType, node_id: DOMNodeRawId, keyboard: KeyboardEvent, mouse: MouseEvent) -> Self {
+        BasicEvent {
+            event_type,
+            node_id,
+            related_node_id: None,
+            keyboard,
+            mouse,
+            pointer: PointerEvent::default(),
+            wheel: WheelEvent::default(),
+            data_transfer: None,
+            animation: None,
+            custom_type: None,
+            detail: None,
+            default_prevented: DefaultPrevented::default(),
+        }
+    }
+
+    pub fn custom<N: Into<Atom>>(name: N, node_id: DOMNodeRawId, detail: Value) -> Self {
+        let mut event = BasicEvent::new(EventType::Custom, node_id, KeyboardEvent::default(), MouseEvent::default());
+        event.custom_type = Some(name.into());
+        event.detail = Some(detail);
+        event
+    }
+
+    pub fn with_wheel(mut self, wheel: WheelEvent) -> Self {
+        self.wheel = wheel;
+        self
+    }
+
+    pub fn with_data_transfer(mut self, data_transfer: DataTransfer) -> Self {
+        self.data_transfer = Some(data_transfer);
+        self
+    }
+
+    pub fn with_animation(mut self, animation: AnimationEvent) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
+    pub fn with_pointer(mut self, pointer: PointerEvent) -> Self {
+        self.pointer = pointer;
+        self
+    }
+
+    pub fn with_related_target(mut self, related_node_id: Option<DOMNodeRawId>) -> Self {
+        self.related_node_id = related_node_id;
+        self
+    }
+
+    pub fn keyboard(&self) -> &KeyboardEvent {
+        &self.keyboard
+    }
+
+    pub fn mouse(&self) -> &MouseEvent {
+        &self.mouse
+    }
+
+    pub fn pointer(&self) -> &PointerEvent {
+        &self.pointer
+    }
+
+    pub fn wheel(&self) -> &WheelEvent {
+        &self.wheel
+    }
 }
 
 impl TGenericEvent for BasicEvent {}
@@ -53,43 +387,54 @@ impl TEvent for BasicEvent {
     fn ty(&self) -> EventType {
         self.event_type
     }
+
+    fn prevent_default(&self) {
+        if self.event_type.cancelable() {
+            self.default_prevented.set();
+        }
+    }
+
+    fn default_prevented(&self) -> bool {
+        self.default_prevented.get()
+    }
 }
 
 impl TUIEvent for BasicEvent {
     fn alt_key(&self) -> bool {
-        self.keyboard.alt_pressed
+        self.keyboard.modifiers.alt
     }
 
     fn ctrl_key(&self) -> bool {
-        self.keyboard.ctrl_pressed
+        self.keyboard.modifiers.ctrl
     }
 
     fn meta_key(&self) -> bool {
-        self.keyboard.meta_pressed
+        self.keyboard.modifiers.meta
     }
 
     fn shift_key(&self) -> bool {
-        self.keyboard.shift_pressed
+        self.keyboard.modifiers.shift
     }
 }
 
 impl TKeyboardEvent for BasicEvent {
-    type KeyCode = ();
+    type KeyCode = KeyCode;
+    type KeyModifier = KeyModifier;
 
     fn code(&self) -> Self::KeyCode {
-        unimplemented!()
+        self.keyboard.code
     }
 
     fn key(&self) -> &'static str {
-        unimplemented!()
+        self.keyboard.key()
     }
 
-    fn get_modifier_state(&self) -> bool {
-        unimplemented!()
+    fn get_modifier_state(&self, modifier: Self::KeyModifier) -> bool {
+        self.keyboard.modifiers.get(modifier)
     }
 
     fn repeat(&self) -> bool {
-        unimplemented!()
+        self.keyboard.repeat
     }
 }
 
@@ -97,30 +442,112 @@ impl TMouseEvent for BasicEvent {
     type MouseButton = MouseButton;
 
     fn button(&self) -> Self::MouseButton {
-        unimplemented!()
+        self.mouse.button
     }
 
     fn client_x(&self) -> u32 {
-        unimplemented!()
+        self.mouse.client_x
     }
 
     fn client_y(&self) -> u32 {
-        unimplemented!()
+        self.mouse.client_y
     }
 
     fn offset_x(&self) -> u32 {
-        unimplemented!()
+        self.mouse.offset_x
     }
 
     fn offset_y(&self) -> u32 {
-        unimplemented!()
+        self.mouse.offset_y
     }
 
     fn page_x(&self) -> u32 {
-        unimplemented!()
+        self.mouse.page_x
     }
 
     fn page_y(&self) -> u32 {
-        unimplemented!()
+        self.mouse.page_y
+    }
+
+    fn related_target(&self) -> Option<DOMNodeRawId> {
+        self.related_node_id
+    }
+}
+
+impl TPointerEvent for BasicEvent {
+    type PointerType = PointerType;
+
+    fn pointer_id(&self) -> u32 {
+        self.pointer.pointer_id
+    }
+
+    fn pointer_type(&self) -> Self::PointerType {
+        self.pointer.pointer_type
+    }
+
+    fn pressure(&self) -> f32 {
+        self.pointer.pressure
+    }
+
+    fn width(&self) -> f32 {
+        self.pointer.width
+    }
+
+    fn height(&self) -> f32 {
+        self.pointer.height
+    }
+
+    fn is_primary(&self) -> bool {
+        self.pointer.is_primary
+    }
+}
+
+impl TWheelEvent for BasicEvent {
+    type DeltaMode = WheelDeltaMode;
+
+    fn delta_x(&self) -> f64 {
+        self.wheel.delta_x
+    }
+
+    fn delta_y(&self) -> f64 {
+        self.wheel.delta_y
+    }
+
+    fn delta_z(&self) -> f64 {
+        self.wheel.delta_z
+    }
+
+    fn delta_mode(&self) -> Self::DeltaMode {
+        self.wheel.delta_mode
+    }
+}
+
+impl TDragEvent for BasicEvent {
+    type DataTransfer = DataTransfer;
+
+    fn data_transfer(&self) -> Option<&Self::DataTransfer> {
+        self.data_transfer.as_ref()
+    }
+}
+
+impl TCustomEvent for BasicEvent {
+    type Detail = Value;
+
+    fn custom_type(&self) -> Option<Atom> {
+        self.custom_type
+    }
+
+    fn detail(&self) -> Option<&Self::Detail> {
+        self.detail.as_ref()
+    }
+}
+
+impl TAnimationEvent for BasicEvent {
+    fn animation_name(&self) -> Option<&str> {
+        self.animation.as_ref().map(AnimationEvent::name)
+    }
+
+    fn elapsed_time(&self) -> f32 {
+        self.animation.as_ref().map_or(0.0, AnimationEvent::elapsed_time)
     }
 }
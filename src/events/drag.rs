@@ -0,0 +1,182 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use events::{BasicEvent, EventTarget};
+use layout::LayoutNode;
+use node::{DOMAttributeValue, DOMNode};
+use traits::TLayoutNode;
+use types::KnownAttributeName;
+
+// See https://html.spec.whatwg.org/multipage/dnd.html#dom-datatransfer-dropeffect
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DropEffect {
+    None,
+    Copy,
+    Move,
+    Link,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+struct DataTransferStore {
+    items: Vec<(String, String)>,
+    drop_effect: DropEffect,
+}
+
+// Shared by every event of a drag operation, so data set on drag start can be read on drop.
+// See https://html.spec.whatwg.org/multipage/dnd.html#the-datatransfer-interface
+#[derive(Clone, Debug, Default)]
+pub struct DataTransfer(Arc<Mutex<DataTransferStore>>);
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct DragState<L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    pub source: EventTarget<L>,
+    pub origin: (u32, u32),
+    pub started: bool,
+    pub immediate: Option<EventTarget<L>>,
+    pub current: Option<EventTarget<L>>,
+    pub data: DataTransfer,
+}
+
+impl Default for DropEffect {
+    fn default() -> Self {
+        DropEffect::None
+    }
+}
+
+impl DataTransfer {
+    fn store(&self) -> MutexGuard<DataTransferStore> {
+        self.0.lock().expect("Data transfer lock poisoned")
+    }
+
+    // Formats are ASCII case-insensitive.
+    pub fn set_data<F: AsRef<str>, D: Into<String>>(&self, format: F, data: D) {
+        let format = format.as_ref().to_ascii_lowercase();
+        let mut store = self.store();
+        store.items.retain(|&(ref v, _)| *v != format);
+        store.items.push((format, data.into()));
+    }
+
+    pub fn get_data<F: AsRef<str>>(&self, format: F) -> Option<String> {
+        let format = format.as_ref().to_ascii_lowercase();
+        let store = self.store();
+        store.items.iter().find(|&&(ref v, _)| *v == format).map(|v| v.1.clone())
+    }
+
+    pub fn clear_data(&self, format: Option<&str>) {
+        let mut store = self.store();
+        match format.map(|v| v.to_ascii_lowercase()) {
+            Some(format) => store.items.retain(|&(ref v, _)| *v != format),
+            None => store.items.clear(),
+        }
+    }
+
+    pub fn types(&self) -> Vec<String> {
+        self.store().items.iter().map(|v| v.0.clone()).collect()
+    }
+
+    pub fn drop_effect(&self) -> DropEffect {
+        self.store().drop_effect
+    }
+
+    pub fn set_drop_effect(&self, effect: DropEffect) {
+        self.store().drop_effect = effect;
+    }
+}
+
+// Transfers holding the same data are equal, without locking a transfer against itself.
+impl PartialEq for DataTransfer {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || *self.store() == *other.store()
+    }
+}
+
+impl Serialize for DataTransfer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.store().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DataTransfer {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        DataTransferStore::deserialize(deserializer).map(|v| DataTransfer(Arc::new(Mutex::new(v))))
+    }
+}
+
+impl<L> DragState<L>
+where
+    L: TLayoutNode,
+{
+    pub fn new(source: EventTarget<L>, origin: (u32, u32)) -> Self {
+        DragState {
+            source,
+            origin,
+            started: false,
+            immediate: None,
+            current: None,
+            data: DataTransfer::default(),
+        }
+    }
+}
+
+fn attribute_str<L>(node: &DOMNode<BasicEvent, L>, name: KnownAttributeName) -> Option<&str>
+where
+    L: TLayoutNode,
+{
+    match node.data.attributes_ref()?.common.get(&name) {
+        Some(&DOMAttributeValue::Str(ref value)) => Some(value.as_ref()),
+        _ => None,
+    }
+}
+
+// See https://html.spec.whatwg.org/multipage/dnd.html#the-draggable-attribute
+pub(crate) fn is_draggable<L: TLayoutNode>(node: &DOMNode<BasicEvent, L>) -> bool {
+    let attributes = match node.data.attributes_ref() {
+        Some(attributes) => attributes,
+        None => return false,
+    };
+    match attributes.common.get(&KnownAttributeName::Draggable) {
+        Some(&DOMAttributeValue::Boolean(value)) => value,
+        Some(&DOMAttributeValue::Str(ref value)) => value.as_ref().eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
+
+// Returns the operation of a dropzone accepting any of the dragged data types.
+// See https://www.w3.org/TR/2016/REC-html51-20161101/editing.html#the-dropzone-attribute
+pub(crate) fn dropzone_effect<L>(
+    node: &DOMNode<BasicEvent, L>,
+    data: &DataTransfer,
+) -> Option<DropEffect>
+where
+    L: TLayoutNode,
+{
+    let dropzone = attribute_str(node, KnownAttributeName::Dropzone)?.to_ascii_lowercase();
+    let types = data.types();
+
+    let mut effect = None;
+    let mut accepted = false;
+    for token in dropzone.split_whitespace() {
+        match token {
+            "copy" => effect = effect.or(Some(DropEffect::Copy)),
+            "move" => effect = effect.or(Some(DropEffect::Move)),
+            "link" => effect = effect.or(Some(DropEffect::Link)),
+            _ if token.starts_with("string:") => accepted |= types.iter().any(|v| *v == token["string:".len()..]),
+            _ => {}
+        }
+    }
+
+    if accepted {
+        Some(effect.unwrap_or(DropEffect::Copy))
+    } else {
+        None
+    }
+}
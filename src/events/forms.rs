@@ -0,0 +1,215 @@
+use control::ControlKind;
+use events::{ancestors, raw_id, BasicEvent, DefaultActionContext, EventTarget, KeyboardEvent, MouseEvent};
+use traits::{TDOMNode, TLayoutNode};
+use tree::DOMTree;
+use types::{EventType, KeyCode, KnownElementName};
+
+fn form_event<L: TLayoutNode>(ty: EventType, target: EventTarget<L>) -> BasicEvent {
+    BasicEvent::new(ty, raw_id(target), KeyboardEvent::default(), MouseEvent::default())
+}
+
+fn fire_value_events<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>, target: EventTarget<L>) {
+    ctx.fire(form_event(EventType::Input, target));
+    ctx.fire(form_event(EventType::Change, target));
+}
+
+// See https://html.spec.whatwg.org/multipage/input.html#event-input-change
+pub(crate) fn commit_change<L>(
+    tree: &mut DOMTree<BasicEvent, L>,
+    target: EventTarget<L>,
+) -> Option<BasicEvent>
+where
+    L: TLayoutNode,
+{
+    if tree.get_mut(target).control.take_pending_change() {
+        Some(form_event(EventType::Change, target))
+    } else {
+        None
+    }
+}
+
+// Text controls commit their pending change before the form is submitted.
+fn submit<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>, form: EventTarget<L>) {
+    for id in ctx.tree.form_controls(form) {
+        if let Some(event) = commit_change(ctx.tree, id) {
+            ctx.fire(event);
+        }
+    }
+    ctx.fire(form_event(EventType::Submit, form));
+}
+
+// See https://html.spec.whatwg.org/multipage/interaction.html#activation-behaviour
+pub(crate) fn activate<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>) -> bool {
+    let control = ctx.element;
+    if ctx.tree.get(control).is_disabled() {
+        return true;
+    }
+
+    let kind = ctx.tree.get(control).control_kind();
+    match kind {
+        Some(ControlKind::Checkbox) => {
+            let checked = ctx.tree.get(control).checked();
+            ctx.tree.get_mut(control).set_checked(!checked);
+            fire_value_events(ctx, control);
+        }
+        Some(ControlKind::Radio) if !ctx.tree.get(control).checked() => {
+            for id in ctx.tree.radio_group(control) {
+                ctx.tree.get_mut(id).set_checked(id == control);
+            }
+            fire_value_events(ctx, control);
+        }
+        Some(ControlKind::Submit) => {
+            if let Some(form) = ctx.tree.form_owner(control) {
+                submit(ctx, form);
+            }
+        }
+        Some(ControlKind::Reset) => {
+            if let Some(form) = ctx.tree.form_owner(control) {
+                ctx.fire(form_event(EventType::Reset, form));
+            }
+        }
+        _ => {}
+    }
+    true
+}
+
+// Clicks on a label are forwarded to its control, unless they already went through it.
+// See https://html.spec.whatwg.org/multipage/forms.html#the-label-element
+pub(crate) fn forward_label_click<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>) -> bool {
+    let path = ancestors(ctx.tree, ctx.target());
+    let control = ctx.tree.labeled_control(ctx.element).filter(|id| !path.contains(id));
+    if let Some(control) = control {
+        ctx.fire(form_event(EventType::Click, control));
+    }
+    true
+}
+
+// See https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-form-reset
+pub(crate) fn reset_form<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>) -> bool {
+    ctx.tree.reset_form(ctx.element);
+    true
+}
+
+pub(crate) fn run_key_behavior<L: TLayoutNode>(ctx: &mut DefaultActionContext<L>) -> bool {
+    let target = ctx.element;
+    let kind = match ctx.tree.get(target).control_kind() {
+        Some(kind) if !ctx.tree.get(target).is_disabled() => kind,
+        _ => return false,
+    };
+    let event = ctx.event;
+    let data = event.keyboard();
+    let modifiers = *data.modifiers();
+    if modifiers.ctrl || modifiers.alt || modifiers.meta {
+        return false;
+    }
+
+    match (kind, data.code()) {
+        (ControlKind::Text, KeyCode::Enter) | (ControlKind::Text, KeyCode::NumpadEnter)
+            if !ctx.tree.get(target).is_known(KnownElementName::Textarea) =>
+        {
+            // See https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#implicit-submission
+            if let Some(form) = ctx.tree.form_owner(target) {
+                submit(ctx, form);
+            }
+            true
+        }
+        (ControlKind::Text, _) => edit_text(ctx, target, data),
+        (ControlKind::Select, KeyCode::ArrowDown) | (ControlKind::Select, KeyCode::ArrowUp) => {
+            let index = match (ctx.tree.selected_index(target), data.code()) {
+                (Some(index), KeyCode::ArrowUp) if index > 0 => index - 1,
+                (Some(index), KeyCode::ArrowDown) => index + 1,
+                _ => return true,
+            };
+            if ctx.tree.set_selected_index(target, index) {
+                fire_value_events(ctx, target);
+            }
+            true
+        }
+        (ControlKind::Range, code) => {
+            let (min, max, step) = ctx.tree.get(target).range_bounds();
+            let current = ctx.tree.get(target).value().parse().unwrap_or(min);
+            let value: f64 = match code {
+                KeyCode::ArrowUp | KeyCode::ArrowRight => current + step,
+                KeyCode::ArrowDown | KeyCode::ArrowLeft => current - step,
+                _ => return false,
+            };
+            let value = value.max(min).min(max);
+            if (value - current).abs() > ::std::f64::EPSILON {
+                ctx.tree.get_mut(target).set_value(value.to_string());
+                fire_value_events(ctx, target);
+            }
+            true
+        }
+        (_, KeyCode::Space) => {
+            ctx.fire(form_event(EventType::Click, target));
+            true
+        }
+        _ => false,
+    }
+}
+
+// Caret positions are counted in chars.
+fn edit_text<L>(ctx: &mut DefaultActionContext<L>, target: EventTarget<L>, data: &KeyboardEvent) -> bool
+where
+    L: TLayoutNode,
+{
+    let (mut chars, mut caret) = {
+        let node = ctx.tree.get(target);
+        (node.value().chars().collect::<Vec<_>>(), node.caret())
+    };
+
+    let edited = match data.code() {
+        KeyCode::ArrowLeft => {
+            caret = caret.saturating_sub(1);
+            false
+        }
+        KeyCode::ArrowRight => {
+            caret = (caret + 1).min(chars.len());
+            false
+        }
+        KeyCode::Home | KeyCode::ArrowUp => {
+            caret = 0;
+            false
+        }
+        KeyCode::End | KeyCode::ArrowDown => {
+            caret = chars.len();
+            false
+        }
+        KeyCode::Backspace if caret > 0 => {
+            caret -= 1;
+            chars.remove(caret);
+            true
+        }
+        KeyCode::Delete if caret < chars.len() => {
+            chars.remove(caret);
+            true
+        }
+        KeyCode::Backspace | KeyCode::Delete => return true,
+        KeyCode::Enter | KeyCode::NumpadEnter => {
+            chars.insert(caret, '\n');
+            caret += 1;
+            true
+        }
+        _ if data.key().chars().count() == 1 => {
+            for value in data.key().chars() {
+                chars.insert(caret, value);
+                caret += 1;
+            }
+            true
+        }
+        _ => return false,
+    };
+
+    {
+        let mut node = ctx.tree.get_mut(target);
+        if edited {
+            node.set_value(chars.into_iter().collect::<String>());
+            node.control.mark_pending_change();
+        }
+        node.set_caret(caret);
+    }
+    if edited {
+        ctx.fire(form_event(EventType::Input, target));
+    }
+    true
+}
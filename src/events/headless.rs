@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use events::BasicEvent;
+use mutation::{DOMMutation, MutationError};
+use traits::{TGenericEvent, TLayoutNode};
+use tree::DOMTree;
+use types::{DOMNodeRawId, HeadlessClosureId};
+
+pub type HeadlessInvocation = (HeadlessClosureId, BasicEvent);
+
+// Events which hit headless listeners, waiting to be handled on the remote side.
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+pub struct HeadlessQueue {
+    invocations: VecDeque<HeadlessInvocation>,
+}
+
+// Render thread end of a headless channel.
+#[derive(Debug)]
+pub struct HeadlessBridge {
+    invocations: Sender<Vec<HeadlessInvocation>>,
+    mutations: Receiver<Vec<DOMMutation>>,
+}
+
+// Remote end of a headless channel, which runs the UI logic.
+#[derive(Debug)]
+pub struct HeadlessRemote {
+    invocations: Receiver<Vec<HeadlessInvocation>>,
+    mutations: Sender<Vec<DOMMutation>>,
+}
+
+pub fn headless_channel() -> (HeadlessBridge, HeadlessRemote) {
+    let (invocations_sender, invocations_receiver) = channel();
+    let (mutations_sender, mutations_receiver) = channel();
+
+    let bridge = HeadlessBridge {
+        invocations: invocations_sender,
+        mutations: mutations_receiver,
+    };
+    let remote = HeadlessRemote {
+        invocations: invocations_receiver,
+        mutations: mutations_sender,
+    };
+
+    (bridge, remote)
+}
+
+impl HeadlessQueue {
+    pub fn push(&mut self, closure: HeadlessClosureId, event: BasicEvent) {
+        self.invocations.push_back((closure, event));
+    }
+
+    pub fn len(&self) -> usize {
+        self.invocations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.invocations.is_empty()
+    }
+
+    pub fn drain(&mut self) -> Vec<HeadlessInvocation> {
+        self.invocations.drain(..).collect()
+    }
+}
+
+impl HeadlessBridge {
+    // Sends all queued invocations to the remote side, returns false if it hung up.
+    pub fn flush(&self, queue: &mut HeadlessQueue) -> bool {
+        if queue.is_empty() {
+            return true;
+        }
+        self.invocations.send(queue.drain()).is_ok()
+    }
+
+    // Applies every mutation batch received so far, in order.
+    pub fn apply_mutations<T, L>(&self, tree: &mut DOMTree<T, L>) -> Vec<Result<Option<DOMNodeRawId>, MutationError>>
+    where
+        T: TGenericEvent,
+        L: TLayoutNode,
+    {
+        self.mutations
+            .try_iter()
+            .flat_map(|batch| batch.into_iter())
+            .map(|mutation| tree.apply_mutation(mutation))
+            .collect()
+    }
+}
+
+impl HeadlessRemote {
+    // Blocks until the render thread sends invocations, returns None if it hung up.
+    pub fn recv(&self) -> Option<Vec<HeadlessInvocation>> {
+        self.invocations.recv().ok()
+    }
+
+    pub fn try_recv(&self) -> Option<Vec<HeadlessInvocation>> {
+        self.invocations.try_recv().ok()
+    }
+
+    pub fn send(&self, mutations: Vec<DOMMutation>) -> bool {
+        self.mutations.send(mutations).is_ok()
+    }
+}
@@ -0,0 +1,1080 @@
+use hashbrown::{HashMap, HashSet};
+use num_traits::{FromPrimitive, ToPrimitive};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::mem;
+
+use events::{
+    AnimationEvent, BasicEvent, ClickState, ClickThreshold, DefaultActionRequest, DefaultActions, DragState,
+    DropEffect, EventRecording, HeadlessQueue, HoverState, KeyboardEvent, MouseButton, MouseEvent, PointerEvent,
+    PointerState, PointerType, WheelEvent,
+};
+use events::{drag, forms};
+use layout::{LayoutNode, TextMeasure};
+use node::{DOMNode, DOMNodeId};
+use traits::{TCallback, TClientRect, TCustomEvent, TEvent, TEventManager, TLayoutNode};
+use tree::DOMTree;
+use types::{
+    Atom, Closure, DOMNodeRawId, Direction, ElementState, EventType, KeyCode, KeyModifiers, Viewport,
+    VirtualEventType,
+};
+
+pub type EventTarget<L = LayoutNode> = DOMNodeId<BasicEvent, L>;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum PendingInput {
+    Key(VirtualEventType, KeyboardEvent),
+    Mouse(VirtualEventType, MouseEvent),
+    Pointer(VirtualEventType, PointerEvent, MouseEvent),
+    Wheel(WheelEvent, MouseEvent),
+    Focus(Option<DOMNodeRawId>),
+    Viewport(Viewport),
+    // Advances transitions and animations to a time in milliseconds.
+    Tick(u64),
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct EventManager<L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    listeners: HashMap<(EventTarget<L>, EventType), Vec<Closure<BasicEvent>>>,
+    custom_listeners: HashMap<(EventTarget<L>, Atom), Vec<Closure<BasicEvent>>>,
+    pending: VecDeque<PendingInput>,
+    modifiers: KeyModifiers,
+    hover: HoverState<L>,
+    // The node the main button was pressed on, until it's released.
+    active: Option<EventTarget<L>>,
+    clicks: ClickState<L>,
+    pointers: HashMap<u32, PointerState<L>>,
+    drag: Option<DragState<L>>,
+    headless: HeadlessQueue,
+    recording: Option<EventRecording>,
+    default_actions: DefaultActions<L>,
+    requests: Vec<DefaultActionRequest>,
+    // The nodes given a hover, active or focus state by the last update.
+    stated: HashSet<EventTarget<L>>,
+}
+
+pub fn raw_id<L: TLayoutNode>(id: EventTarget<L>) -> DOMNodeRawId {
+    id.to_u64().expect("Invalid node id")
+}
+
+pub fn node_id<L: TLayoutNode>(raw_id: DOMNodeRawId) -> EventTarget<L> {
+    <EventTarget<L> as FromPrimitive>::from_u64(raw_id).expect("Invalid raw node id")
+}
+
+// Returns the node itself followed by all of its ancestors up to the root.
+pub fn ancestors<L>(tree: &DOMTree<BasicEvent, L>, id: EventTarget<L>) -> Vec<EventTarget<L>>
+where
+    L: TLayoutNode,
+{
+    let mut path = vec![id];
+    let mut next_id = tree.get(id).parent_id();
+    while let Some(parent_id) = next_id {
+        path.push(parent_id);
+        next_id = tree.get(parent_id).parent_id();
+    }
+    path
+}
+
+struct BoundaryEventTypes {
+    out: EventType,
+    leave: EventType,
+    over: EventType,
+    enter: EventType,
+}
+
+const MOUSE_BOUNDARY_EVENTS: BoundaryEventTypes = BoundaryEventTypes {
+    out: EventType::MouseOut,
+    leave: EventType::MouseLeave,
+    over: EventType::MouseOver,
+    enter: EventType::MouseEnter,
+};
+
+const POINTER_BOUNDARY_EVENTS: BoundaryEventTypes = BoundaryEventTypes {
+    out: EventType::PointerOut,
+    leave: EventType::PointerLeave,
+    over: EventType::PointerOver,
+    enter: EventType::PointerEnter,
+};
+
+// See https://www.w3.org/TR/uievents/#events-mouseevent-event-order
+fn push_boundary_events<L, F>(
+    tree: &DOMTree<BasicEvent, L>,
+    previous: Option<EventTarget<L>>,
+    target: Option<EventTarget<L>>,
+    types: &BoundaryEventTypes,
+    events: &mut Vec<BasicEvent>,
+    make_event: F,
+) where
+    L: TLayoutNode,
+    F: Fn(EventType, EventTarget<L>) -> BasicEvent,
+{
+    let previous_path = previous.map(|id| ancestors(tree, id)).unwrap_or_default();
+    let target_path = target.map(|id| ancestors(tree, id)).unwrap_or_default();
+
+    if let Some(previous) = previous {
+        let related = target.map(raw_id);
+        events.push(make_event(types.out, previous).with_related_target(related));
+
+        for &id in previous_path.iter().filter(|id| !target_path.contains(id)) {
+            events.push(make_event(types.leave, id).with_related_target(related));
+        }
+    }
+
+    if let Some(target) = target {
+        let related = previous.map(raw_id);
+        events.push(make_event(types.over, target).with_related_target(related));
+
+        for &id in target_path.iter().rev().filter(|id| !previous_path.contains(id)) {
+            events.push(make_event(types.enter, id).with_related_target(related));
+        }
+    }
+}
+
+// Headless listeners can't run in process, so their events are queued for the remote side.
+fn invoke(listener: &Closure<BasicEvent>, event: &BasicEvent, headless: &mut HeadlessQueue) {
+    if let Err(event) = listener.call(event.clone()) {
+        if let Closure::Headless { f } = *listener {
+            headless.push(f, event);
+        }
+    }
+}
+
+impl<L> EventManager<L>
+where
+    L: TLayoutNode,
+{
+    pub fn with_click_threshold(threshold: ClickThreshold) -> Self {
+        let mut manager = EventManager::default();
+        manager.set_click_threshold(threshold);
+        manager
+    }
+
+    pub fn set_click_threshold(&mut self, threshold: ClickThreshold) {
+        self.clicks.threshold = threshold;
+    }
+
+    pub fn hovered(&self) -> Option<EventTarget<L>> {
+        self.hover.target
+    }
+
+    // Events which reached headless listeners since the queue was last drained.
+    pub fn headless_queue(&mut self) -> &mut HeadlessQueue {
+        &mut self.headless
+    }
+
+    // Queues a focus change, fired together with the next batch of events.
+    pub fn focus(&mut self, target: Option<EventTarget<L>>) {
+        self.receive_input(PendingInput::Focus(target.map(raw_id)));
+    }
+
+    // Queues a viewport change, so that recordings reproduce it in order with the other inputs.
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.receive_input(PendingInput::Viewport(viewport));
+    }
+
+    // Queues a clock tick, so that recordings reproduce animations at the same times.
+    pub fn tick(&mut self, now: u64) {
+        self.receive_input(PendingInput::Tick(now));
+    }
+
+    pub fn receive_input(&mut self, input: PendingInput) {
+        if let Some(ref mut recording) = self.recording {
+            recording.record(&input);
+        }
+        self.pending.push_back(input);
+    }
+
+    // See https://www.w3.org/TR/pointerevents/#setting-pointer-capture
+    pub fn set_pointer_capture(&mut self, target: EventTarget<L>, pointer_id: u32) -> bool {
+        match self.pointers.get_mut(&pointer_id) {
+            Some(ref mut state) if state.pressed => {
+                state.pending_capture = Some(target);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn release_pointer_capture(&mut self, target: EventTarget<L>, pointer_id: u32) -> bool {
+        match self.pointers.get_mut(&pointer_id) {
+            Some(ref mut state) if state.pending_capture == Some(target) => {
+                state.pending_capture = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn has_pointer_capture(&self, target: EventTarget<L>, pointer_id: u32) -> bool {
+        self.pointers
+            .get(&pointer_id)
+            .map_or(false, |state| state.pending_capture == Some(target))
+    }
+
+    pub fn add_custom_event_listener<N, F>(&mut self, id: EventTarget<L>, name: N, listener: F)
+    where
+        N: Into<Atom>,
+        F: Into<Closure<BasicEvent>>,
+    {
+        let key = (id, name.into());
+        self.custom_listeners.entry(key).or_insert_with(Vec::new).push(listener.into());
+    }
+
+    pub fn remove_custom_event_listener<N, F>(&mut self, id: EventTarget<L>, name: N, listener: F)
+    where
+        N: Into<Atom>,
+        F: Into<Closure<BasicEvent>>,
+    {
+        let listener = listener.into();
+        if let Some(listeners) = self.custom_listeners.get_mut(&(id, name.into())) {
+            listeners.retain(|v| *v != listener);
+        }
+    }
+
+    // Dispatches synchronously through the same bubbling path as built-in events.
+    // Returns false when a listener called `prevent_default`.
+    // The detail is JSON rather than a `Prop`, so that custom events can be serialized into recordings.
+    // See https://dom.spec.whatwg.org/#dom-eventtarget-dispatchevent
+    pub fn dispatch_custom_event<N>(
+        &mut self,
+        tree: &DOMTree<BasicEvent, L>,
+        id: EventTarget<L>,
+        name: N,
+        detail: Value,
+    ) -> bool
+    where
+        N: Into<Atom>,
+    {
+        let event = BasicEvent::custom(name, raw_id(id), detail);
+        self.dispatch(tree, &event);
+        !event.default_prevented()
+    }
+
+    pub fn default_actions(&self) -> &DefaultActions<L> {
+        &self.default_actions
+    }
+
+    // Embedders can override or remove the built-in default actions here.
+    pub fn default_actions_mut(&mut self) -> &mut DefaultActions<L> {
+        &mut self.default_actions
+    }
+
+    // Requests made by default actions since the last call, like following a link.
+    pub fn take_requests(&mut self) -> Vec<DefaultActionRequest> {
+        mem::replace(&mut self.requests, vec![])
+    }
+
+    pub fn start_recording(&mut self) {
+        self.recording = Some(EventRecording::default());
+    }
+
+    pub fn stop_recording(&mut self) -> Option<EventRecording> {
+        self.recording.take()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    // Nodes removed since the last broadcast can't stay hovered, pressed, captured or focused.
+    fn forget_disconnected(&mut self, tree: &mut DOMTree<BasicEvent, L>) {
+        if tree.active_element().map_or(false, |id| !tree.is_connected(id)) {
+            tree.blur();
+        }
+
+        let tree = &*tree;
+        let connected = |id: EventTarget<L>| tree.is_connected(id);
+        self.hover.target = self.hover.target.filter(|&id| connected(id));
+        self.active = self.active.filter(|&id| connected(id));
+        self.clicks.retain(&connected);
+        for state in self.pointers.values_mut() {
+            state.retain(&connected);
+        }
+        if let Some(ref mut drag) = self.drag {
+            drag.immediate = drag.immediate.filter(|&id| connected(id));
+            drag.current = drag.current.filter(|&id| connected(id));
+        }
+    }
+
+    // Hover and active states apply to the ancestors too. Only the nodes which had one of these
+    // states and the ones getting one are visited.
+    // See https://drafts.csswg.org/selectors/#the-hover-pseudo
+    fn update_element_states(&mut self, tree: &mut DOMTree<BasicEvent, L>) {
+        let (hovered, active, focused, focus_within) = {
+            let path = |id: Option<EventTarget<L>>| {
+                id.filter(|&id| tree.contains(id))
+                    .map(|id| ancestors(tree, id))
+                    .unwrap_or_default()
+            };
+            let focused = tree.active_element();
+            (path(self.hover.target), path(self.active), focused, path(focused))
+        };
+
+        let stated = hovered
+            .iter()
+            .chain(&active)
+            .chain(&focus_within)
+            .cloned()
+            .collect::<HashSet<_>>();
+        let previous = mem::replace(&mut self.stated, stated);
+        let ids = previous
+            .into_iter()
+            .filter(|&id| tree.contains(id))
+            .chain(self.stated.iter().cloned())
+            .collect::<HashSet<_>>();
+        for id in ids {
+            let state = ElementState {
+                hovered: hovered.contains(&id),
+                active: active.contains(&id),
+                focused: focused == Some(id),
+                focus_within: focus_within.contains(&id),
+                ..ElementState::default()
+            };
+            tree.set_element_state(id, state);
+        }
+    }
+
+    fn run_default_action(
+        &mut self,
+        tree: &mut DOMTree<BasicEvent, L>,
+        event: &BasicEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        let outcome = self.default_actions.run(tree, event);
+        events.extend(outcome.events);
+        if let Some(target) = outcome.focus {
+            self.move_focus(tree, target, events);
+        }
+        self.requests.extend(outcome.requests);
+    }
+
+    fn resolve_key_input(
+        &mut self,
+        tree: &mut DOMTree<BasicEvent, L>,
+        ty: VirtualEventType,
+        data: KeyboardEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        self.modifiers = *data.modifiers();
+
+        // Keyboard events go to the focused element, or to the document when nothing is focused.
+        let target = tree.active_element().unwrap_or_else(|| {
+            let root = tree.root();
+            root.first_child_id().unwrap_or_else(|| root.id())
+        });
+
+        if ty == VirtualEventType::KeyDown && data.code() == KeyCode::Escape {
+            self.cancel_drag(tree, &MouseEvent::default(), events);
+        }
+
+        events.push(BasicEvent::new(EventType::from(ty), raw_id(target), data, MouseEvent::default()));
+    }
+
+    // See https://www.w3.org/TR/uievents/#events-focusevent-event-order
+    fn move_focus(
+        &mut self,
+        tree: &mut DOMTree<BasicEvent, L>,
+        target: Option<EventTarget<L>>,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        let previous = tree.active_element();
+        tree.focus(target);
+        let target = tree.active_element();
+        if previous == target {
+            return;
+        }
+
+        if let Some(previous) = previous {
+            let related = target.map(raw_id);
+            events.extend(forms::commit_change(tree, previous));
+            events.push(self.focus_event(EventType::Blur, previous, related));
+            events.push(self.focus_event(EventType::FocusOut, previous, related));
+        }
+
+        if let Some(target) = target {
+            let related = previous.map(raw_id);
+            events.push(self.focus_event(EventType::Focus, target, related));
+            events.push(self.focus_event(EventType::FocusIn, target, related));
+        }
+    }
+
+    fn focus_event(&self, ty: EventType, target: EventTarget<L>, related: Option<DOMNodeRawId>) -> BasicEvent {
+        let keyboard = KeyboardEvent::new(KeyCode::Unidentified, self.modifiers, false);
+        BasicEvent::new(ty, raw_id(target), keyboard, MouseEvent::default()).with_related_target(related)
+    }
+
+    fn resolve_mouse_input(
+        &mut self,
+        tree: &mut DOMTree<BasicEvent, L>,
+        ty: VirtualEventType,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        if ty == VirtualEventType::MouseUp && data.button() == MouseButton::Main {
+            self.active = None;
+        }
+
+        // Mouse events are replaced by drag events while dragging.
+        if self.resolve_drag_input(tree, ty, data, events) {
+            return;
+        }
+
+        let target = tree.hit_test(data.page_point());
+
+        if ty == VirtualEventType::MouseMove {
+            self.update_hover(tree, target, data, events);
+        }
+        if ty == VirtualEventType::MouseDown && data.button() == MouseButton::Main {
+            self.active = target;
+        }
+
+        let target = match target {
+            Some(target) => target,
+            None => {
+                if ty == VirtualEventType::MouseUp {
+                    self.clicks.cancel();
+                }
+                return;
+            }
+        };
+
+        events.push(self.mouse_event(tree, EventType::from(ty), target, data));
+
+        match ty {
+            VirtualEventType::MouseDown => self.clicks.press(data.button(), target),
+            VirtualEventType::MouseUp => self.synthesize_clicks(tree, target, data, events),
+            _ => {}
+        }
+    }
+
+    fn update_hover(
+        &mut self,
+        tree: &DOMTree<BasicEvent, L>,
+        target: Option<EventTarget<L>>,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        let previous = mem::replace(&mut self.hover.target, target);
+        if previous == target {
+            return;
+        }
+
+        push_boundary_events(tree, previous, target, &MOUSE_BOUNDARY_EVENTS, events, |ty, id| {
+            self.mouse_event(tree, ty, id, data)
+        });
+    }
+
+    // See https://www.w3.org/TR/pointerevents/
+    fn resolve_pointer_input(
+        &mut self,
+        tree: &mut DOMTree<BasicEvent, L>,
+        ty: VirtualEventType,
+        pointer: &PointerEvent,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        let pointer_id = pointer.pointer_id();
+        let is_touch = pointer.pointer_type() == PointerType::Touch;
+
+        self.pointers.entry(pointer_id).or_insert_with(PointerState::default);
+        self.apply_pending_capture(tree, pointer, data, events);
+
+        // Captured pointers are retargeted to the capturing node instead of being hit tested.
+        let capture = self.pointers.get(&pointer_id).and_then(|v| v.capture);
+        let target = capture.or_else(|| tree.hit_test(data.page_point()));
+
+        if ty == VirtualEventType::PointerDown || ty == VirtualEventType::PointerMove {
+            self.update_pointer_hover(tree, pointer, target, data, events);
+        }
+
+        if let Some(target) = target {
+            events.push(self.pointer_event(tree, EventType::from(ty), target, pointer, data));
+        }
+
+        let touch_target = match ty {
+            VirtualEventType::PointerDown => {
+                let state = self.pointer_state(pointer_id);
+                state.pressed = true;
+                state.touch_target = target;
+                target.map(|id| (EventType::TouchStart, id))
+            }
+            VirtualEventType::PointerMove => {
+                let state = self.pointer_state(pointer_id);
+                state.touch_target.map(|id| (EventType::TouchMove, id))
+            }
+            VirtualEventType::PointerUp | VirtualEventType::PointerCancel => {
+                let state = self.pointer_state(pointer_id);
+                let touch_type = if ty == VirtualEventType::PointerUp {
+                    EventType::TouchEnd
+                } else {
+                    EventType::TouchCancel
+                };
+                state.pressed = false;
+                state.pending_capture = None;
+                state.touch_target.take().map(|id| (touch_type, id))
+            }
+            _ => None,
+        };
+
+        if let (true, Some((touch_type, id))) = (is_touch, touch_target) {
+            events.push(self.pointer_event(tree, touch_type, id, pointer, data));
+        }
+
+        if ty == VirtualEventType::PointerUp || ty == VirtualEventType::PointerCancel {
+            // Capture is implicitly released, and touch contacts leave the surface entirely.
+            self.apply_pending_capture(tree, pointer, data, events);
+            if is_touch || ty == VirtualEventType::PointerCancel {
+                self.update_pointer_hover(tree, pointer, None, data, events);
+                self.pointers.remove(&pointer_id);
+            }
+        }
+
+        // Compatibility mouse events are derived from the primary pointer only.
+        if pointer.is_primary() {
+            let mouse_type = match ty {
+                VirtualEventType::PointerDown => Some(VirtualEventType::MouseDown),
+                VirtualEventType::PointerMove => Some(VirtualEventType::MouseMove),
+                VirtualEventType::PointerUp => Some(VirtualEventType::MouseUp),
+                _ => None,
+            };
+            if let Some(mouse_type) = mouse_type {
+                self.resolve_mouse_input(tree, mouse_type, data, events);
+            }
+        }
+    }
+
+    fn pointer_state(&mut self, pointer_id: u32) -> &mut PointerState<L> {
+        self.pointers.entry(pointer_id).or_insert_with(PointerState::default)
+    }
+
+    fn apply_pending_capture(
+        &mut self,
+        tree: &DOMTree<BasicEvent, L>,
+        pointer: &PointerEvent,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        let change = self.pointer_state(pointer.pointer_id()).apply_pending_capture();
+        if let Some((lost, got)) = change {
+            if let Some(lost) = lost {
+                events.push(self.pointer_event(tree, EventType::LostPointerCapture, lost, pointer, data));
+            }
+            if let Some(got) = got {
+                events.push(self.pointer_event(tree, EventType::GotPointerCapture, got, pointer, data));
+            }
+        }
+    }
+
+    fn update_pointer_hover(
+        &mut self,
+        tree: &DOMTree<BasicEvent, L>,
+        pointer: &PointerEvent,
+        target: Option<EventTarget<L>>,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        let previous = mem::replace(&mut self.pointer_state(pointer.pointer_id()).hover, target);
+        if previous == target {
+            return;
+        }
+
+        push_boundary_events(tree, previous, target, &POINTER_BOUNDARY_EVENTS, events, |ty, id| {
+            self.pointer_event(tree, ty, id, pointer, data)
+        });
+    }
+
+    // See https://www.w3.org/TR/uievents/#events-wheelevents
+    fn resolve_wheel_input(
+        &mut self,
+        tree: &mut DOMTree<BasicEvent, L>,
+        wheel: &WheelEvent,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        let target = match tree.hit_test(data.page_point()) {
+            Some(target) => target,
+            None => return,
+        };
+
+        let event = self.mouse_event(tree, EventType::Wheel, target, data).with_wheel(wheel.clone());
+        events.push(event);
+    }
+
+    // See https://drafts.csswg.org/cssom-view/#scrolling-events
+    // Fired at the document, as there's no window.
+    // See https://drafts.csswg.org/cssom-view/#run-the-resize-steps
+    fn collect_resize_event(&self, tree: &mut DOMTree<BasicEvent, L>, events: &mut Vec<BasicEvent>) {
+        if tree.take_resized() {
+            let root = tree.root();
+            let target = root.first_child_id().unwrap_or_else(|| root.id());
+            let (keyboard, mouse) = (KeyboardEvent::default(), MouseEvent::default());
+            events.push(BasicEvent::new(EventType::Resize, raw_id(target), keyboard, mouse));
+        }
+    }
+
+    // Transitions and animations which ended since the last broadcast, in the order they ended.
+    // See https://drafts.csswg.org/css-transitions/#transition-events
+    fn collect_animation_events(&self, tree: &mut DOMTree<BasicEvent, L>, events: &mut Vec<BasicEvent>) {
+        for finished in tree.take_finished_animations() {
+            if !tree.contains(finished.id) {
+                continue;
+            }
+            let (keyboard, mouse) = (KeyboardEvent::default(), MouseEvent::default());
+            let animation = AnimationEvent::new(finished.name, finished.elapsed_time);
+            let event = BasicEvent::new(finished.event_type, raw_id(finished.id), keyboard, mouse);
+            events.push(event.with_animation(animation));
+        }
+    }
+
+    fn collect_scroll_events(&self, tree: &mut DOMTree<BasicEvent, L>, events: &mut Vec<BasicEvent>) {
+        for id in tree.take_scrolled_ids() {
+            let (keyboard, mouse) = (KeyboardEvent::default(), MouseEvent::default());
+            events.push(BasicEvent::new(EventType::Scroll, raw_id(id), keyboard, mouse));
+        }
+    }
+
+    // See https://html.spec.whatwg.org/multipage/dnd.html#drag-and-drop-processing-model
+    fn resolve_drag_input(
+        &mut self,
+        tree: &DOMTree<BasicEvent, L>,
+        ty: VirtualEventType,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) -> bool {
+        match ty {
+            VirtualEventType::MouseDown if data.button() == MouseButton::Main => {
+                let source = tree.hit_test(data.page_point()).and_then(|target| {
+                    ancestors(tree, target)
+                        .into_iter()
+                        .find(|&id| drag::is_draggable(&tree.get(id)))
+                });
+                self.drag = source.map(|source| DragState::new(source, data.page_point()));
+                false
+            }
+            VirtualEventType::MouseMove => {
+                let (source, origin, started) = match self.drag {
+                    Some(ref state) => (state.source, state.origin, state.started),
+                    None => return false,
+                };
+
+                if started {
+                    events.push(self.drag_event(tree, EventType::Drag, source, data));
+                    return true;
+                }
+
+                // Drags only start once the pointer moved further than a click would allow.
+                let (x, y) = data.page_point();
+                let (dx, dy) = (i64::from(x) - i64::from(origin.0), i64::from(y) - i64::from(origin.1));
+                let distance = i64::from(self.clicks.threshold.distance);
+                if dx * dx + dy * dy <= distance * distance {
+                    return false;
+                }
+
+                if let Some(ref mut state) = self.drag {
+                    state.started = true;
+                }
+                self.clicks.cancel();
+                events.push(self.drag_event(tree, EventType::DragStart, source, data));
+                true
+            }
+            VirtualEventType::MouseUp => {
+                let current = match self.drag {
+                    Some(ref state) if state.started => state.current,
+                    Some(_) => {
+                        self.drag = None;
+                        return false;
+                    }
+                    None => return false,
+                };
+
+                self.clicks.cancel();
+                match current {
+                    Some(current) if self.drop_effect() != DropEffect::None => {
+                        events.push(self.drag_event(tree, EventType::Drop, current, data));
+                    }
+                    _ => self.cancel_drag(tree, data, events),
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Fires the events which depend on whether the previous drag event was canceled.
+    fn after_drag_event(&mut self, tree: &DOMTree<BasicEvent, L>, event: &BasicEvent, events: &mut Vec<BasicEvent>) {
+        let (source, immediate, current) = match self.drag {
+            Some(ref state) => (state.source, state.immediate, state.current),
+            None => return,
+        };
+        let data = event.mouse();
+        let prevented = event.default_prevented();
+
+        match event.ty() {
+            EventType::DragStart if prevented => self.drag = None,
+            EventType::DragStart => events.push(self.drag_event(tree, EventType::Drag, source, data)),
+            EventType::Drag if prevented => self.cancel_drag(tree, data, events),
+            EventType::Drag => {
+                let target = tree.hit_test(data.page_point());
+                if target == immediate {
+                    if let Some(current) = current {
+                        self.push_drag_over(tree, current, data, events);
+                    }
+                    return;
+                }
+
+                if let Some(ref mut state) = self.drag {
+                    state.immediate = target;
+                }
+                match target {
+                    Some(target) => events.push(self.drag_event(tree, EventType::DragEnter, target, data)),
+                    None => self.update_drop_target(tree, None, data, events),
+                }
+            }
+            EventType::DragEnter => {
+                // Elements become drop targets by canceling drag enter, or through their dropzone attribute.
+                let target = node_id(event.target());
+                let next = if prevented {
+                    Some(target)
+                } else {
+                    let transfer = self.drag.as_ref().map(|v| v.data.clone()).unwrap_or_default();
+                    ancestors(tree, target)
+                        .into_iter()
+                        .find(|&id| drag::dropzone_effect(&tree.get(id), &transfer).is_some())
+                };
+                self.update_drop_target(tree, next, data, events);
+            }
+            EventType::DragOver => {
+                let effect = if prevented {
+                    self.drop_effect()
+                } else {
+                    self.dropzone_effect(tree, node_id(event.target()))
+                };
+                self.set_drop_effect(effect);
+            }
+            EventType::Drop => {
+                if !prevented {
+                    let effect = self.dropzone_effect(tree, node_id(event.target()));
+                    self.set_drop_effect(effect);
+                }
+                events.push(self.drag_event(tree, EventType::DragEnd, source, data));
+            }
+            EventType::DragEnd => self.drag = None,
+            _ => {}
+        }
+    }
+
+    fn update_drop_target(
+        &mut self,
+        tree: &DOMTree<BasicEvent, L>,
+        next: Option<EventTarget<L>>,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        let previous = match self.drag {
+            Some(ref mut state) => mem::replace(&mut state.current, next),
+            None => return,
+        };
+
+        if let Some(previous) = previous.filter(|&v| Some(v) != next) {
+            events.push(self.drag_event(tree, EventType::DragLeave, previous, data));
+        }
+        match next {
+            Some(next) => self.push_drag_over(tree, next, data, events),
+            None => self.set_drop_effect(DropEffect::None),
+        }
+    }
+
+    // Listeners canceling drag over without picking an effect get a copy.
+    fn push_drag_over(
+        &mut self,
+        tree: &DOMTree<BasicEvent, L>,
+        target: EventTarget<L>,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        self.set_drop_effect(DropEffect::Copy);
+        events.push(self.drag_event(tree, EventType::DragOver, target, data));
+    }
+
+    fn cancel_drag(&mut self, tree: &DOMTree<BasicEvent, L>, data: &MouseEvent, events: &mut Vec<BasicEvent>) {
+        let (source, current) = match self.drag {
+            Some(ref state) if state.started => (state.source, state.current),
+            _ => return,
+        };
+
+        self.set_drop_effect(DropEffect::None);
+        if let Some(current) = current {
+            events.push(self.drag_event(tree, EventType::DragLeave, current, data));
+        }
+        events.push(self.drag_event(tree, EventType::DragEnd, source, data));
+    }
+
+    fn drop_effect(&self) -> DropEffect {
+        self.drag.as_ref().map_or(DropEffect::None, |v| v.data.drop_effect())
+    }
+
+    fn set_drop_effect(&self, effect: DropEffect) {
+        if let Some(ref state) = self.drag {
+            state.data.set_drop_effect(effect);
+        }
+    }
+
+    fn dropzone_effect(&self, tree: &DOMTree<BasicEvent, L>, target: EventTarget<L>) -> DropEffect {
+        match self.drag {
+            Some(ref state) => drag::dropzone_effect(&tree.get(target), &state.data).unwrap_or(DropEffect::None),
+            None => DropEffect::None,
+        }
+    }
+
+    fn drag_event(
+        &self,
+        tree: &DOMTree<BasicEvent, L>,
+        ty: EventType,
+        target: EventTarget<L>,
+        data: &MouseEvent,
+    ) -> BasicEvent {
+        let transfer = self.drag.as_ref().map(|v| v.data.clone()).unwrap_or_default();
+        self.mouse_event(tree, ty, target, data).with_data_transfer(transfer)
+    }
+
+    fn synthesize_clicks(
+        &mut self,
+        tree: &DOMTree<BasicEvent, L>,
+        target: EventTarget<L>,
+        data: &MouseEvent,
+        events: &mut Vec<BasicEvent>,
+    ) {
+        let press = match self.clicks.release(data.button()) {
+            Some(press) => press,
+            None => return,
+        };
+
+        // Clicks are fired on the nearest common ancestor of the press and release targets.
+        let press_path = ancestors(tree, press.target);
+        let common = ancestors(tree, target).into_iter().find(|id| press_path.contains(id));
+        let common = match common {
+            Some(common) => common,
+            None => return,
+        };
+
+        match data.button() {
+            MouseButton::Main => {
+                events.push(self.mouse_event(tree, EventType::Click, common, data));
+                if self.clicks.register_click(common, data) {
+                    events.push(self.mouse_event(tree, EventType::DblClick, common, data));
+                }
+            }
+            MouseButton::Auxiliary => {
+                events.push(self.mouse_event(tree, EventType::AuxClick, common, data));
+            }
+            MouseButton::Secondary => {
+                events.push(self.mouse_event(tree, EventType::AuxClick, common, data));
+                events.push(self.mouse_event(tree, EventType::ContextMenu, common, data));
+            }
+        }
+    }
+
+    fn mouse_event(
+        &self,
+        tree: &DOMTree<BasicEvent, L>,
+        ty: EventType,
+        target: EventTarget<L>,
+        data: &MouseEvent,
+    ) -> BasicEvent {
+        let rect = tree.client_rect(target);
+        let point = data.page_point();
+        let data = data
+            .clone()
+            .with_client_point(rect.client_from_page(point))
+            .with_offset_point(rect.offset_from_page(point));
+        let keyboard = KeyboardEvent::new(KeyCode::Unidentified, self.modifiers, false);
+        BasicEvent::new(ty, raw_id(target), keyboard, data)
+    }
+
+    fn pointer_event(
+        &self,
+        tree: &DOMTree<BasicEvent, L>,
+        ty: EventType,
+        target: EventTarget<L>,
+        pointer: &PointerEvent,
+        data: &MouseEvent,
+    ) -> BasicEvent {
+        self.mouse_event(tree, ty, target, data).with_pointer(pointer.clone())
+    }
+
+    fn dispatch(&mut self, tree: &DOMTree<BasicEvent, L>, event: &BasicEvent) {
+        let ty = event.ty();
+        let target = node_id(event.target());
+        let path = if ty.bubbles() {
+            ancestors(tree, target)
+        } else {
+            vec![target]
+        };
+
+        let custom_type = event.custom_type();
+        for id in path {
+            let attributes = tree.get(id).into_value().data.attributes_ref();
+            let (listener, listeners) = match custom_type {
+                Some(name) => (
+                    attributes.and_then(|v| v.custom_listeners.get(&name)),
+                    self.custom_listeners.get(&(id, name)),
+                ),
+                None => (
+                    attributes.and_then(|v| v.listeners.get(&ty)),
+                    self.listeners.get(&(id, ty)),
+                ),
+            };
+
+            if let Some(listener) = listener {
+                invoke(listener, event, &mut self.headless);
+            }
+            if let Some(listeners) = listeners {
+                for listener in listeners {
+                    invoke(listener, event, &mut self.headless);
+                }
+            }
+        }
+    }
+}
+
+impl<L> EventManager<L>
+where
+    L: TLayoutNode,
+    L::TextMeasureMetadata: From<TextMeasure>,
+    L::ReflowDirection: From<Direction>,
+{
+    // Dispatches all pending events, returning them in the order they were fired.
+    pub fn broadcast_and_collect_events(&mut self, tree: &mut DOMTree<BasicEvent, L>) -> Vec<BasicEvent> {
+        self.process_pending(tree, |manager, tree, event| manager.dispatch(tree, event))
+    }
+
+    // Inputs are resolved one at a time, and each event is handled before its default action runs,
+    // so that the events caused by a default action are fired before the remaining ones.
+    fn process_pending<F>(&mut self, tree: &mut DOMTree<BasicEvent, L>, mut handle: F) -> Vec<BasicEvent>
+    where
+        F: FnMut(&mut EventManager<L>, &mut DOMTree<BasicEvent, L>, &BasicEvent),
+    {
+        let mut fired = vec![];
+        self.forget_disconnected(tree);
+
+        // The viewport, scroll offsets and animations may have changed since the last broadcast.
+        let mut changed = vec![];
+        self.collect_resize_event(tree, &mut changed);
+        self.collect_scroll_events(tree, &mut changed);
+        self.collect_animation_events(tree, &mut changed);
+        let mut queue: VecDeque<BasicEvent> = changed.into_iter().collect();
+
+        loop {
+            let event = match queue.pop_front() {
+                Some(event) => event,
+                None => match self.pending.pop_front() {
+                    Some(input) => {
+                        queue.extend(self.resolve_input(tree, input));
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+
+            handle(self, tree, &event);
+
+            let mut caused = vec![];
+            self.after_drag_event(tree, &event, &mut caused);
+            self.run_default_action(tree, &event, &mut caused);
+            self.collect_scroll_events(tree, &mut caused);
+            for event in caused.into_iter().rev() {
+                queue.push_front(event);
+            }
+
+            fired.push(event);
+        }
+
+        self.update_element_states(tree);
+        fired
+    }
+
+    fn resolve_input(&mut self, tree: &mut DOMTree<BasicEvent, L>, input: PendingInput) -> Vec<BasicEvent> {
+        let mut events = vec![];
+        match input {
+            PendingInput::Key(ty, data) => self.resolve_key_input(tree, ty, data, &mut events),
+            PendingInput::Mouse(ty, data) => self.resolve_mouse_input(tree, ty, &data, &mut events),
+            PendingInput::Pointer(ty, pointer, data) => {
+                self.resolve_pointer_input(tree, ty, &pointer, &data, &mut events)
+            }
+            PendingInput::Wheel(wheel, data) => self.resolve_wheel_input(tree, &wheel, &data, &mut events),
+            PendingInput::Focus(target) => self.move_focus(tree, target.map(node_id), &mut events),
+            PendingInput::Viewport(viewport) => {
+                tree.set_viewport(viewport);
+                self.collect_resize_event(tree, &mut events);
+            }
+            PendingInput::Tick(now) => {
+                tree.tick(now);
+                self.collect_animation_events(tree, &mut events);
+            }
+        }
+        events
+    }
+}
+
+impl<L> TEventManager for EventManager<L>
+where
+    L: TLayoutNode,
+    L::TextMeasureMetadata: From<TextMeasure>,
+    L::ReflowDirection: From<Direction>,
+{
+    type Target = DOMNode<BasicEvent, L>;
+    type Tree = DOMTree<BasicEvent, L>;
+    type KeyCode = KeyCode;
+    type MouseButton = MouseButton;
+    type KeyEventData = KeyboardEvent;
+    type MouseEventData = MouseEvent;
+    type PointerEventData = (PointerEvent, MouseEvent);
+    type WheelEventData = (WheelEvent, MouseEvent);
+
+    fn add_event_listener<F>(&mut self, id: EventTarget<L>, ty: EventType, listener: F)
+    where
+        F: Into<Closure<BasicEvent>>,
+    {
+        self.listeners.entry((id, ty)).or_insert_with(Vec::new).push(listener.into());
+    }
+
+    fn remove_event_listener<F>(&mut self, id: EventTarget<L>, ty: EventType, listener: F)
+    where
+        F: Into<Closure<BasicEvent>>,
+    {
+        let listener = listener.into();
+        if let Some(listeners) = self.listeners.get_mut(&(id, ty)) {
+            listeners.retain(|v| *v != listener);
+        }
+    }
+
+    fn receive_key_event(&mut self, ty: VirtualEventType, data: KeyboardEvent) {
+        self.receive_input(PendingInput::Key(ty, data));
+    }
+
+    fn receive_mouse_event(&mut self, ty: VirtualEventType, data: MouseEvent) {
+        self.receive_input(PendingInput::Mouse(ty, data));
+    }
+
+    fn receive_pointer_event(&mut self, ty: VirtualEventType, (pointer, data): (PointerEvent, MouseEvent)) {
+        self.receive_input(PendingInput::Pointer(ty, pointer, data));
+    }
+
+    fn receive_wheel_event(&mut self, (wheel, data): (WheelEvent, MouseEvent)) {
+        self.receive_input(PendingInput::Wheel(wheel, data));
+    }
+
+    fn broadcast_events(&mut self, tree: &mut DOMTree<BasicEvent, L>) {
+        self.broadcast_and_collect_events(tree);
+    }
+
+    fn intercept_events<F>(&mut self, tree: &mut DOMTree<BasicEvent, L>, mut f: F)
+    where
+        F: FnMut(BasicEvent),
+    {
+        self.process_pending(tree, |_, _, event| f(event.clone()));
+    }
+}
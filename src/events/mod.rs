@@ -1,3 +1,18 @@
+mod actions;
 mod basic;
+mod drag;
+mod forms;
+mod headless;
+mod manager;
+mod mouse;
+mod pointer;
+mod recording;
 
+pub use self::actions::*;
 pub use self::basic::*;
+pub use self::drag::*;
+pub use self::headless::*;
+pub use self::manager::*;
+pub use self::mouse::*;
+pub use self::pointer::*;
+pub use self::recording::*;
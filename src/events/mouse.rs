@@ -0,0 +1,126 @@
+use events::{BasicEvent, MouseButton, MouseEvent};
+use layout::LayoutNode;
+use node::DOMNodeId;
+use traits::TLayoutNode;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ClickThreshold {
+    // Maximum delay between two clicks in milliseconds
+    pub time: u64,
+    // Maximum distance between two clicks in pixels
+    pub distance: u32,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub(crate) struct HoverState<L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    pub target: Option<DOMNodeId<BasicEvent, L>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Press<L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    pub button: MouseButton,
+    pub target: DOMNodeId<BasicEvent, L>,
+}
+
+#[derive(Debug, PartialEq)]
+struct LastClick<L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    target: DOMNodeId<BasicEvent, L>,
+    point: (u32, u32),
+    timestamp: u64,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub(crate) struct ClickState<L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    pub threshold: ClickThreshold,
+    press: Option<Press<L>>,
+    last_click: Option<LastClick<L>>,
+}
+
+impl Default for ClickThreshold {
+    fn default() -> Self {
+        ClickThreshold {
+            time: 500,
+            distance: 4,
+        }
+    }
+}
+
+impl ClickThreshold {
+    fn contains(&self, (x1, y1): (u32, u32), (x2, y2): (u32, u32), elapsed: u64) -> bool {
+        let dx = (i64::from(x1) - i64::from(x2)).abs();
+        let dy = (i64::from(y1) - i64::from(y2)).abs();
+        let distance = i64::from(self.distance);
+        elapsed <= self.time && dx <= distance && dy <= distance
+    }
+}
+
+impl<L> ClickState<L>
+where
+    L: TLayoutNode,
+{
+    pub fn press(&mut self, button: MouseButton, target: DOMNodeId<BasicEvent, L>) {
+        self.press = Some(Press { button, target });
+    }
+
+    pub fn release(&mut self, button: MouseButton) -> Option<Press<L>> {
+        match self.press.take() {
+            Some(press) if press.button == button => Some(press),
+            _ => None,
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.press = None;
+    }
+
+    // Forgets presses and clicks on nodes for which `keep` returns false.
+    pub fn retain<F>(&mut self, keep: F)
+    where
+        F: Fn(DOMNodeId<BasicEvent, L>) -> bool,
+    {
+        if self.press.as_ref().map_or(false, |press| !keep(press.target)) {
+            self.press = None;
+        }
+        if self.last_click.as_ref().map_or(false, |click| !keep(click.target)) {
+            self.last_click = None;
+        }
+    }
+
+    // Returns true when this click completes a double click.
+    pub fn register_click(&mut self, target: DOMNodeId<BasicEvent, L>, data: &MouseEvent) -> bool {
+        let point = data.page_point();
+        let timestamp = data.timestamp();
+
+        let is_double = match self.last_click {
+            Some(ref last) => {
+                let elapsed = timestamp.saturating_sub(last.timestamp);
+                last.target == target && self.threshold.contains(last.point, point, elapsed)
+            }
+            None => false,
+        };
+
+        self.last_click = if is_double {
+            None
+        } else {
+            Some(LastClick {
+                target,
+                point,
+                timestamp,
+            })
+        };
+
+        is_double
+    }
+}
@@ -0,0 +1,46 @@
+use events::BasicEvent;
+use layout::LayoutNode;
+use node::DOMNodeId;
+use traits::TLayoutNode;
+
+// See https://www.w3.org/TR/pointerevents/#pointer-capture
+#[derive(Debug, PartialEq, Default)]
+pub(crate) struct PointerState<L = LayoutNode>
+where
+    L: TLayoutNode,
+{
+    pub hover: Option<DOMNodeId<BasicEvent, L>>,
+    pub capture: Option<DOMNodeId<BasicEvent, L>>,
+    pub pending_capture: Option<DOMNodeId<BasicEvent, L>>,
+    pub touch_target: Option<DOMNodeId<BasicEvent, L>>,
+    pub pressed: bool,
+}
+
+impl<L> PointerState<L>
+where
+    L: TLayoutNode,
+{
+    // Forgets the targets for which `keep` returns false.
+    pub fn retain<F>(&mut self, keep: F)
+    where
+        F: Fn(DOMNodeId<BasicEvent, L>) -> bool,
+    {
+        self.hover = self.hover.filter(|&id| keep(id));
+        self.capture = self.capture.filter(|&id| keep(id));
+        self.pending_capture = self.pending_capture.filter(|&id| keep(id));
+        self.touch_target = self.touch_target.filter(|&id| keep(id));
+    }
+
+    // Applies a pending capture change, returning the (lost, got) capture targets.
+    #[cfg_attr(feature = "cargo-clippy", allow(type_complexity))]
+    pub fn apply_pending_capture(
+        &mut self,
+    ) -> Option<(Option<DOMNodeId<BasicEvent, L>>, Option<DOMNodeId<BasicEvent, L>>)> {
+        if self.capture == self.pending_capture {
+            return None;
+        }
+        let lost = self.capture;
+        self.capture = self.pending_capture;
+        Some((lost, self.capture))
+    }
+}
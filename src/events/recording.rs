@@ -0,0 +1,79 @@
+use events::{BasicEvent, EventManager, PendingInput};
+use layout::TextMeasure;
+use traits::TLayoutNode;
+use tree::DOMTree;
+use types::Direction;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub timestamp: u64,
+    pub input: PendingInput,
+}
+
+// Inputs received by an event manager, in the order they arrived.
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+pub struct EventRecording {
+    inputs: Vec<RecordedInput>,
+}
+
+impl PendingInput {
+    pub fn timestamp(&self) -> Option<u64> {
+        match self {
+            &PendingInput::Key(_, ref data) => Some(data.timestamp()),
+            &PendingInput::Mouse(_, ref data) => Some(data.timestamp()),
+            &PendingInput::Pointer(_, _, ref data) => Some(data.timestamp()),
+            &PendingInput::Wheel(_, ref data) => Some(data.timestamp()),
+            &PendingInput::Tick(now) => Some(now),
+            &PendingInput::Focus(_) | &PendingInput::Viewport(_) => None,
+        }
+    }
+}
+
+impl EventRecording {
+    pub fn record(&mut self, input: &PendingInput) {
+        // Inputs without their own timestamp inherit the one of the previous input.
+        let timestamp = input
+            .timestamp()
+            .or_else(|| self.inputs.last().map(|v| v.timestamp))
+            .unwrap_or(0);
+
+        self.inputs.push(RecordedInput {
+            timestamp,
+            input: input.clone(),
+        });
+    }
+
+    pub fn inputs(&self) -> &[RecordedInput] {
+        &self.inputs
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    // Feeds every input to the manager one at a time, calling `after_each` with the events it fired.
+    // The tree is laid out after each input, as an embedder would before the next frame.
+    // Hover, focus and click state live in the manager, so replays should start from a fresh one.
+    pub fn replay<L, F>(
+        &self,
+        manager: &mut EventManager<L>,
+        tree: &mut DOMTree<BasicEvent, L>,
+        mut after_each: F,
+    ) where
+        L: TLayoutNode,
+        L::TextMeasureMetadata: From<TextMeasure>,
+        L::ReflowDirection: From<Direction>,
+        F: FnMut(&RecordedInput, &[BasicEvent], &DOMTree<BasicEvent, L>),
+    {
+        for recorded in &self.inputs {
+            manager.receive_input(recorded.input.clone());
+            let events = manager.broadcast_and_collect_events(tree);
+            tree.layout_viewport();
+            after_each(recorded, &events, tree);
+        }
+    }
+}
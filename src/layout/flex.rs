@@ -0,0 +1,1011 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use layout::TextMeasure;
+use traits::TLayoutNode;
+use types::{
+    Align, ClientEdges, Direction, Display, FlexDirection, FlexStyle, Justify, Layout, PositionType, StyleUnit,
+    Wrap,
+};
+
+// Indices into edge values, specific edges win over the shorthands when resolving.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Edge {
+    Left,
+    Top,
+    Right,
+    Bottom,
+    Start,
+    End,
+    Horizontal,
+    Vertical,
+    All,
+}
+
+type EdgeValues = [StyleUnit; 9];
+
+// Border-box sizes and the owner's inner size, which percentages resolve against.
+type SizeKey = ((Option<f32>, Option<f32>), (Option<f32>, Option<f32>));
+
+#[derive(Debug, PartialEq, Clone)]
+struct NodeStyle {
+    direction: FlexDirection,
+    wrap: Wrap,
+    justify: Justify,
+    align_items: Align,
+    align_self: Align,
+    align_content: Align,
+    position: PositionType,
+    display: Display,
+    flex: Option<f32>,
+    grow: Option<f32>,
+    shrink: Option<f32>,
+    basis: StyleUnit,
+    aspect_ratio: Option<f32>,
+    width: StyleUnit,
+    height: StyleUnit,
+    min_width: StyleUnit,
+    min_height: StyleUnit,
+    max_width: StyleUnit,
+    max_height: StyleUnit,
+    margin: EdgeValues,
+    padding: EdgeValues,
+    border: EdgeValues,
+    offset: EdgeValues,
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+struct ComputedLayout {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+    width: f32,
+    height: f32,
+    padding: ClientEdges,
+    border: ClientEdges,
+    margin: ClientEdges,
+}
+
+#[derive(Debug)]
+struct FlexNode {
+    style: NodeStyle,
+    children: Vec<Rc<RefCell<FlexNode>>>,
+    parent: Weak<RefCell<FlexNode>>,
+    measure: Option<TextMeasure>,
+    layout: ComputedLayout,
+    cache: Vec<(SizeKey, (f32, f32))>,
+}
+
+// Pure Rust layout of the flexbox subset jss emits. Defaults follow yoga rather than the web
+// (column direction, no shrinking), so that both backends lay out the same trees the same way.
+// See https://www.w3.org/TR/css-flexbox-1/#layout-algorithm
+#[derive(Debug)]
+pub struct FlexLayoutNode {
+    inner: Rc<RefCell<FlexNode>>,
+    tainted: bool,
+    tainted_descendants: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Axis {
+    row: bool,
+    reverse: bool,
+    wrap_reverse: bool,
+}
+
+#[derive(Debug)]
+struct Item {
+    node: Rc<RefCell<FlexNode>>,
+    margin: ClientEdges,
+    align: Align,
+    stretch: bool,
+    grow: f32,
+    shrink: f32,
+    base: f32,
+    main: f32,
+    cross: f32,
+    min: (Option<f32>, Option<f32>),
+    max: (Option<f32>, Option<f32>),
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Line {
+    start: usize,
+    end: usize,
+    cross: f32,
+}
+
+impl Default for NodeStyle {
+    fn default() -> Self {
+        NodeStyle {
+            direction: FlexDirection::Column,
+            wrap: Wrap::NoWrap,
+            justify: Justify::FlexStart,
+            align_items: Align::Stretch,
+            align_self: Align::Auto,
+            align_content: Align::FlexStart,
+            position: PositionType::Relative,
+            display: Display::Flex,
+            flex: None,
+            grow: None,
+            shrink: None,
+            basis: StyleUnit::Auto,
+            aspect_ratio: None,
+            width: StyleUnit::Auto,
+            height: StyleUnit::Auto,
+            min_width: StyleUnit::UndefinedValue,
+            min_height: StyleUnit::UndefinedValue,
+            max_width: StyleUnit::UndefinedValue,
+            max_height: StyleUnit::UndefinedValue,
+            margin: [StyleUnit::UndefinedValue; 9],
+            padding: [StyleUnit::UndefinedValue; 9],
+            border: [StyleUnit::UndefinedValue; 9],
+            offset: [StyleUnit::UndefinedValue; 9],
+        }
+    }
+}
+
+impl NodeStyle {
+    fn apply(&mut self, style: &FlexStyle) {
+        match *style {
+            FlexStyle::AlignContent(value) => self.align_content = value,
+            FlexStyle::AlignItems(value) => self.align_items = value,
+            FlexStyle::AlignSelf(value) => self.align_self = value,
+            FlexStyle::AspectRatio(value) => self.aspect_ratio = defined(value),
+            FlexStyle::BorderBottom(value) => self.border[Edge::Bottom as usize] = border(value),
+            FlexStyle::BorderEnd(value) => self.border[Edge::End as usize] = border(value),
+            FlexStyle::BorderLeft(value) => self.border[Edge::Left as usize] = border(value),
+            FlexStyle::BorderRight(value) => self.border[Edge::Right as usize] = border(value),
+            FlexStyle::BorderStart(value) => self.border[Edge::Start as usize] = border(value),
+            FlexStyle::BorderTop(value) => self.border[Edge::Top as usize] = border(value),
+            FlexStyle::Bottom(value) => self.offset[Edge::Bottom as usize] = value,
+            FlexStyle::Display(value) => self.display = value,
+            FlexStyle::End(value) => self.offset[Edge::End as usize] = value,
+            FlexStyle::Flex(value) => self.flex = defined(value),
+            FlexStyle::FlexBasis(value) => self.basis = value,
+            FlexStyle::FlexDirection(value) => self.direction = value,
+            FlexStyle::FlexGrow(value) => self.grow = defined(value),
+            FlexStyle::FlexShrink(value) => self.shrink = defined(value),
+            FlexStyle::FlexWrap(value) => self.wrap = value,
+            FlexStyle::Height(value) => self.height = value,
+            FlexStyle::JustifyContent(value) => self.justify = value,
+            FlexStyle::Left(value) => self.offset[Edge::Left as usize] = value,
+            FlexStyle::Margin(value) => self.margin[Edge::All as usize] = value,
+            FlexStyle::MarginBottom(value) => self.margin[Edge::Bottom as usize] = value,
+            FlexStyle::MarginEnd(value) => self.margin[Edge::End as usize] = value,
+            FlexStyle::MarginHorizontal(value) => self.margin[Edge::Horizontal as usize] = value,
+            FlexStyle::MarginLeft(value) => self.margin[Edge::Left as usize] = value,
+            FlexStyle::MarginRight(value) => self.margin[Edge::Right as usize] = value,
+            FlexStyle::MarginStart(value) => self.margin[Edge::Start as usize] = value,
+            FlexStyle::MarginTop(value) => self.margin[Edge::Top as usize] = value,
+            FlexStyle::MarginVertical(value) => self.margin[Edge::Vertical as usize] = value,
+            FlexStyle::MaxHeight(value) => self.max_height = value,
+            FlexStyle::MaxWidth(value) => self.max_width = value,
+            FlexStyle::MinHeight(value) => self.min_height = value,
+            FlexStyle::MinWidth(value) => self.min_width = value,
+            FlexStyle::Padding(value) => self.padding[Edge::All as usize] = value,
+            FlexStyle::PaddingBottom(value) => self.padding[Edge::Bottom as usize] = value,
+            FlexStyle::PaddingEnd(value) => self.padding[Edge::End as usize] = value,
+            FlexStyle::PaddingHorizontal(value) => self.padding[Edge::Horizontal as usize] = value,
+            FlexStyle::PaddingLeft(value) => self.padding[Edge::Left as usize] = value,
+            FlexStyle::PaddingRight(value) => self.padding[Edge::Right as usize] = value,
+            FlexStyle::PaddingStart(value) => self.padding[Edge::Start as usize] = value,
+            FlexStyle::PaddingTop(value) => self.padding[Edge::Top as usize] = value,
+            FlexStyle::PaddingVertical(value) => self.padding[Edge::Vertical as usize] = value,
+            FlexStyle::Position(value) => self.position = value,
+            FlexStyle::Right(value) => self.offset[Edge::Right as usize] = value,
+            FlexStyle::Start(value) => self.offset[Edge::Start as usize] = value,
+            FlexStyle::Top(value) => self.offset[Edge::Top as usize] = value,
+            FlexStyle::Width(value) => self.width = value,
+            // Nothing is clipped or scrolled here, overflowing children are laid out the same either way.
+            FlexStyle::Overflow(_) => {}
+        }
+    }
+
+    // See https://yogalayout.com/docs/flex
+    fn flex_grow(&self) -> f32 {
+        self.grow
+            .or_else(|| self.flex.filter(|&flex| flex > 0.0))
+            .unwrap_or(0.0)
+    }
+
+    fn flex_shrink(&self) -> f32 {
+        self.shrink
+            .or_else(|| self.flex.filter(|&flex| flex < 0.0).map(|flex| -flex))
+            .unwrap_or(0.0)
+    }
+
+    fn flex_basis(&self, base: Option<f32>) -> Option<f32> {
+        match (self.basis, self.flex) {
+            (StyleUnit::Point(_), _) | (StyleUnit::Percent(_), _) => resolve(self.basis, base),
+            (_, Some(flex)) if flex > 0.0 => Some(0.0),
+            _ => None,
+        }
+    }
+
+    // Both sizes resolved against the owner, the aspect ratio fills in a missing one.
+    fn size(&self, owner: (Option<f32>, Option<f32>)) -> (Option<f32>, Option<f32>) {
+        let width = resolve(self.width, owner.0);
+        let height = resolve(self.height, owner.1);
+        match (width, height, self.aspect_ratio) {
+            (Some(width), None, Some(ratio)) if ratio > 0.0 => (Some(width), Some(width / ratio)),
+            (None, Some(height), Some(ratio)) => (Some(height * ratio), Some(height)),
+            _ => (width, height),
+        }
+    }
+
+    fn min_size(&self, owner: (Option<f32>, Option<f32>)) -> (Option<f32>, Option<f32>) {
+        (resolve(self.min_width, owner.0), resolve(self.min_height, owner.1))
+    }
+
+    fn max_size(&self, owner: (Option<f32>, Option<f32>)) -> (Option<f32>, Option<f32>) {
+        (resolve(self.max_width, owner.0), resolve(self.max_height, owner.1))
+    }
+}
+
+impl FlexNode {
+    fn new() -> Self {
+        FlexNode {
+            style: NodeStyle::default(),
+            children: vec![],
+            parent: Weak::new(),
+            measure: None,
+            layout: ComputedLayout::default(),
+            cache: vec![],
+        }
+    }
+}
+
+impl Axis {
+    fn new(flex_direction: FlexDirection, wrap: Wrap, direction: Direction) -> Self {
+        let rtl = is_rtl(direction);
+        let (row, reverse) = match flex_direction {
+            FlexDirection::Row => (true, rtl),
+            FlexDirection::RowReverse => (true, !rtl),
+            FlexDirection::Column => (false, false),
+            FlexDirection::ColumnReverse => (false, true),
+        };
+        let wrap_reverse = match wrap {
+            Wrap::WrapReverse => true,
+            Wrap::NoWrap | Wrap::Wrap => false,
+        };
+        Axis {
+            row,
+            reverse,
+            wrap_reverse,
+        }
+    }
+
+    fn main<V>(&self, (width, height): (V, V)) -> V {
+        if self.row {
+            width
+        } else {
+            height
+        }
+    }
+
+    fn cross<V>(&self, (width, height): (V, V)) -> V {
+        if self.row {
+            height
+        } else {
+            width
+        }
+    }
+
+    fn size<V>(&self, main: V, cross: V) -> (V, V) {
+        if self.row {
+            (main, cross)
+        } else {
+            (cross, main)
+        }
+    }
+
+    // Edges on the logical start and end of the main axis.
+    fn main_edges(&self, edges: &ClientEdges) -> (f32, f32) {
+        let (start, end) = if self.row {
+            (edges.left, edges.right)
+        } else {
+            (edges.top, edges.bottom)
+        };
+        if self.reverse {
+            (end, start)
+        } else {
+            (start, end)
+        }
+    }
+
+    fn cross_edges(&self, edges: &ClientEdges) -> (f32, f32) {
+        let (start, end) = if self.row {
+            (edges.top, edges.bottom)
+        } else {
+            (edges.left, edges.right)
+        };
+        if self.wrap_reverse {
+            (end, start)
+        } else {
+            (start, end)
+        }
+    }
+}
+
+impl Item {
+    fn outer_main(&self, axis: Axis) -> f32 {
+        let (start, end) = axis.main_edges(&self.margin);
+        self.main + start + end
+    }
+
+    fn outer_cross(&self, axis: Axis) -> f32 {
+        let (start, end) = axis.cross_edges(&self.margin);
+        self.cross + start + end
+    }
+
+    fn clamp_main(&self, axis: Axis, value: f32) -> f32 {
+        clamp(value, axis.main(self.min), axis.main(self.max))
+    }
+
+    fn clamp_cross(&self, axis: Axis, value: f32) -> f32 {
+        clamp(value, axis.cross(self.min), axis.cross(self.max))
+    }
+}
+
+fn is_rtl(direction: Direction) -> bool {
+    match direction {
+        Direction::RTL => true,
+        Direction::LTR | Direction::Inherit => false,
+    }
+}
+
+// Numbers are undefined when NaN, as with yoga.
+fn defined(value: f32) -> Option<f32> {
+    if value.is_nan() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn border(value: f32) -> StyleUnit {
+    defined(value).map_or(StyleUnit::UndefinedValue, StyleUnit::Point)
+}
+
+fn resolve(unit: StyleUnit, base: Option<f32>) -> Option<f32> {
+    match unit {
+        StyleUnit::Point(value) => Some(value),
+        StyleUnit::Percent(value) => base.map(|base| base * value / 100.0),
+        StyleUnit::UndefinedValue | StyleUnit::Auto => None,
+    }
+}
+
+fn clamp(value: f32, min: Option<f32>, max: Option<f32>) -> f32 {
+    let value = max.map_or(value, |max| value.min(max));
+    min.map_or(value, |min| value.max(min))
+}
+
+fn edge_value(values: &EdgeValues, edge: Edge, direction: Direction) -> StyleUnit {
+    use self::Edge::*;
+    let (start, end) = if is_rtl(direction) { (End, Start) } else { (Start, End) };
+    let candidates = match edge {
+        Left => [start, Left, Horizontal, All],
+        Right => [end, Right, Horizontal, All],
+        Top => [Top, Vertical, All, All],
+        Bottom => [Bottom, Vertical, All, All],
+        _ => [edge, All, All, All],
+    };
+    candidates
+        .iter()
+        .map(|&edge| values[edge as usize])
+        .find(|&value| value != StyleUnit::UndefinedValue)
+        .unwrap_or(StyleUnit::UndefinedValue)
+}
+
+// Percentages of every edge resolve against the owner width.
+fn resolve_edges(values: &EdgeValues, owner_width: Option<f32>, direction: Direction) -> ClientEdges {
+    let edge = |edge| resolve(edge_value(values, edge, direction), owner_width).unwrap_or(0.0);
+    ClientEdges {
+        top: edge(Edge::Top),
+        right: edge(Edge::Right),
+        bottom: edge(Edge::Bottom),
+        left: edge(Edge::Left),
+    }
+}
+
+fn add_edges(a: &ClientEdges, b: &ClientEdges) -> ClientEdges {
+    ClientEdges {
+        top: a.top + b.top,
+        right: a.right + b.right,
+        bottom: a.bottom + b.bottom,
+        left: a.left + b.left,
+    }
+}
+
+fn horizontal(edges: &ClientEdges) -> f32 {
+    edges.left + edges.right
+}
+
+fn vertical(edges: &ClientEdges) -> f32 {
+    edges.top + edges.bottom
+}
+
+// Relative offsets, where left and top win over right and bottom.
+fn relative_offset(style: &NodeStyle, owner: (Option<f32>, Option<f32>), direction: Direction) -> (f32, f32) {
+    let offset = |edge, base| resolve(edge_value(&style.offset, edge, direction), base);
+    let dx = offset(Edge::Left, owner.0)
+        .or_else(|| offset(Edge::Right, owner.0).map(|right| -right))
+        .unwrap_or(0.0);
+    let dy = offset(Edge::Top, owner.1)
+        .or_else(|| offset(Edge::Bottom, owner.1).map(|bottom| -bottom))
+        .unwrap_or(0.0);
+    (dx, dy)
+}
+
+// See https://www.w3.org/TR/css-flexbox-1/#justify-content-property
+fn justify_content(justify: Justify, free: f32, count: usize) -> (f32, f32) {
+    match justify {
+        Justify::FlexEnd => (free, 0.0),
+        Justify::Center => (free / 2.0, 0.0),
+        Justify::SpaceBetween if free > 0.0 && count > 1 => (0.0, free / (count - 1) as f32),
+        Justify::SpaceAround if free > 0.0 && count > 0 => (free / count as f32 / 2.0, free / count as f32),
+        _ => (0.0, 0.0),
+    }
+}
+
+// See https://www.w3.org/TR/css-flexbox-1/#align-content-property
+fn align_content(align: Align, free: f32, count: usize) -> (f32, f32) {
+    match align {
+        Align::FlexEnd => (free, 0.0),
+        Align::Center => (free / 2.0, 0.0),
+        Align::SpaceBetween if free > 0.0 && count > 1 => (0.0, free / (count - 1) as f32),
+        Align::SpaceAround if free > 0.0 && count > 0 => (free / count as f32 / 2.0, free / count as f32),
+        _ => (0.0, 0.0),
+    }
+}
+
+fn clear_cache(node: &mut FlexNode) {
+    node.cache.clear();
+    for child in &node.children {
+        clear_cache(&mut child.borrow_mut());
+    }
+}
+
+fn hide(node: &mut FlexNode) {
+    node.layout = ComputedLayout::default();
+    for child in &node.children {
+        hide(&mut child.borrow_mut());
+    }
+}
+
+// Pixel snapping happens in absolute coordinates, so that adjacent nodes don't leave gaps.
+fn round_layout(node: &mut FlexNode, origin: (f32, f32)) {
+    let layout = node.layout;
+    let (left, top) = (origin.0 + layout.left, origin.1 + layout.top);
+    node.layout.left = left.round() - origin.0.round();
+    node.layout.top = top.round() - origin.1.round();
+    node.layout.width = (left + layout.width).round() - left.round();
+    node.layout.height = (top + layout.height).round() - top.round();
+
+    for child in &node.children {
+        round_layout(&mut child.borrow_mut(), (left, top));
+    }
+}
+
+// Returns the border-box size of the node, sizes which aren't given are computed from the content.
+// Measuring passes are cached, only the final pass positions the children.
+fn compute(
+    node: &mut FlexNode,
+    size: (Option<f32>, Option<f32>),
+    owner: (Option<f32>, Option<f32>),
+    direction: Direction,
+    perform: bool,
+) -> (f32, f32) {
+    let key = (size, owner);
+    if !perform {
+        if let Some(&(_, result)) = node.cache.iter().find(|entry| entry.0 == key) {
+            return result;
+        }
+    }
+
+    let result = compute_uncached(node, size, owner, direction, perform);
+    node.cache.push((key, result));
+    result
+}
+
+fn compute_uncached(
+    node: &mut FlexNode,
+    size: (Option<f32>, Option<f32>),
+    owner: (Option<f32>, Option<f32>),
+    direction: Direction,
+    perform: bool,
+) -> (f32, f32) {
+    let style = node.style.clone();
+    let padding = resolve_edges(&style.padding, owner.0, direction);
+    let border = resolve_edges(&style.border, owner.0, direction);
+    let inset = add_edges(&padding, &border);
+    let (min, max) = (style.min_size(owner), style.max_size(owner));
+    if perform {
+        node.layout.padding = padding;
+        node.layout.border = border;
+    }
+
+    let fit = |content: (f32, f32)| {
+        let width = size.0.unwrap_or_else(|| clamp(content.0 + horizontal(&inset), min.0, max.0));
+        let height = size.1.unwrap_or_else(|| clamp(content.1 + vertical(&inset), min.1, max.1));
+        (width.max(horizontal(&inset)), height.max(vertical(&inset)))
+    };
+
+    if let Some(ref measure) = node.measure {
+        let max_width = size
+            .0
+            .or(max.0)
+            .map(|width| (width - horizontal(&inset)).max(0.0));
+        let metrics = measure.measure(max_width);
+        return fit((metrics.width, metrics.height));
+    }
+
+    let axis = Axis::new(style.direction, style.wrap, direction);
+    let wrap = match style.wrap {
+        Wrap::NoWrap => false,
+        Wrap::Wrap | Wrap::WrapReverse => true,
+    };
+    let inner = (
+        size.0.map(|width| (width - horizontal(&inset)).max(0.0)),
+        size.1.map(|height| (height - vertical(&inset)).max(0.0)),
+    );
+    let (inner_main, inner_cross) = (axis.main(inner), axis.cross(inner));
+
+    let mut items = vec![];
+    let mut absolute = vec![];
+    for child in &node.children {
+        let (display, position) = {
+            let child = child.borrow();
+            (child.style.display, child.style.position)
+        };
+        match (display, position) {
+            (Display::None, _) => {
+                if perform {
+                    hide(&mut child.borrow_mut());
+                }
+            }
+            (_, PositionType::Absolute) => absolute.push(child.clone()),
+            (_, PositionType::Relative) => {
+                let single_line_cross = if wrap { None } else { inner_cross };
+                let item = flex_item(child, &style, axis, inner, single_line_cross, direction);
+                items.push(item);
+            }
+        }
+    }
+
+    // Collect items into lines, a line only breaks when the main size is known.
+    // See https://www.w3.org/TR/css-flexbox-1/#algo-line-break
+    let mut lines = vec![];
+    let mut start = 0;
+    let mut used = 0.0;
+    for (index, item) in items.iter().enumerate() {
+        let outer = item.outer_main(axis);
+        let overflows = inner_main.map_or(false, |inner_main| used + outer > inner_main);
+        if wrap && overflows && index > start {
+            lines.push(Line { start, end: index, cross: 0.0 });
+            start = index;
+            used = 0.0;
+        }
+        used += outer;
+    }
+    lines.push(Line { start, end: items.len(), cross: 0.0 });
+
+    // See https://www.w3.org/TR/css-flexbox-1/#resolve-flexible-lengths
+    if let Some(inner_main) = inner_main {
+        for line in &lines {
+            let line_items = &mut items[line.start..line.end];
+            let free = inner_main - line_items.iter().map(|item| item.outer_main(axis)).sum::<f32>();
+            if free > 0.0 {
+                let total = line_items.iter().map(|item| item.grow).sum::<f32>();
+                if total > 0.0 {
+                    for item in line_items.iter_mut() {
+                        item.main = item.clamp_main(axis, item.main + free * item.grow / total);
+                    }
+                }
+            } else if free < 0.0 {
+                let total = line_items.iter().map(|item| item.shrink * item.base).sum::<f32>();
+                if total > 0.0 {
+                    for item in line_items.iter_mut() {
+                        let main = item.main + free * item.shrink * item.base / total;
+                        item.main = item.clamp_main(axis, main).max(0.0);
+                    }
+                }
+            }
+        }
+    }
+
+    // Hypothetical cross sizes, now that the main sizes are known.
+    for item in &mut items {
+        if item.stretch && !wrap && inner_cross.is_some() {
+            continue;
+        }
+        let cross_size = {
+            let mut child = item.node.borrow_mut();
+            let child_size = child.style.size(inner);
+            match axis.cross(child_size) {
+                Some(cross) => cross,
+                None => axis.cross(compute(&mut child, axis.size(Some(item.main), None), inner, direction, false)),
+            }
+        };
+        item.cross = item.clamp_cross(axis, cross_size);
+    }
+    for line in &mut lines {
+        line.cross = items[line.start..line.end]
+            .iter()
+            .map(|item| item.outer_cross(axis))
+            .fold(0.0, f32::max);
+    }
+
+    let main_content = lines
+        .iter()
+        .map(|line| items[line.start..line.end].iter().map(|item| item.outer_main(axis)).sum::<f32>())
+        .fold(0.0, f32::max);
+    let cross_content = lines.iter().map(|line| line.cross).sum::<f32>();
+    let (width, height) = fit(axis.size(main_content, cross_content));
+    let final_inner = (width - horizontal(&inset), height - vertical(&inset));
+    let (final_main, final_cross) = (axis.main(final_inner), axis.cross(final_inner));
+
+    // See https://www.w3.org/TR/css-flexbox-1/#algo-line-align
+    let free_cross = final_cross - cross_content;
+    let (mut cross_cursor, line_gap) = if !wrap {
+        lines[0].cross = final_cross;
+        (0.0, 0.0)
+    } else if let (Align::Stretch, true) = (style.align_content, free_cross > 0.0) {
+        let extra = free_cross / lines.len() as f32;
+        for line in &mut lines {
+            line.cross += extra;
+        }
+        (0.0, 0.0)
+    } else {
+        align_content(style.align_content, free_cross, lines.len())
+    };
+
+    for line in &lines {
+        for item in &mut items[line.start..line.end] {
+            if item.stretch {
+                let (start, end) = axis.cross_edges(&item.margin);
+                item.cross = item.clamp_cross(axis, line.cross - start - end).max(0.0);
+            }
+        }
+    }
+
+    if !perform {
+        return (width, height);
+    }
+
+    let (main_start, _) = axis.main_edges(&inset);
+    let (cross_start, _) = axis.cross_edges(&inset);
+    let (main_total, cross_total) = (axis.main((width, height)), axis.cross((width, height)));
+    for line in &lines {
+        let line_items = &items[line.start..line.end];
+        let free = final_main - line_items.iter().map(|item| item.outer_main(axis)).sum::<f32>();
+        let (mut main_cursor, gap) = justify_content(style.justify, free, line_items.len());
+
+        for item in line_items {
+            let (margin_main_start, _) = axis.main_edges(&item.margin);
+            let (margin_cross_start, margin_cross_end) = axis.cross_edges(&item.margin);
+
+            let logical_main = main_start + main_cursor + margin_main_start;
+            let main_position = if axis.reverse {
+                main_total - logical_main - item.main
+            } else {
+                logical_main
+            };
+            main_cursor += item.outer_main(axis) + gap;
+
+            let offset_in_line = match item.align {
+                Align::FlexEnd => line.cross - item.cross - margin_cross_end,
+                Align::Center => margin_cross_start + (line.cross - item.outer_cross(axis)) / 2.0,
+                _ => margin_cross_start,
+            };
+            let logical_cross = cross_start + cross_cursor + offset_in_line;
+            let cross_position = if axis.wrap_reverse {
+                cross_total - logical_cross - item.cross
+            } else {
+                logical_cross
+            };
+
+            let owner = (Some(final_inner.0), Some(final_inner.1));
+            let (left, top) = axis.size(main_position, cross_position);
+            let child_size = axis.size(item.main, item.cross);
+            place(&item.node, (left, top), child_size, item.margin, owner, (width, height), direction);
+        }
+
+        cross_cursor += line.cross + line_gap;
+    }
+
+    // Absolutely positioned children are placed against the padding box.
+    // See https://www.w3.org/TR/css-flexbox-1/#abspos-items
+    let container = (width - horizontal(&border), height - vertical(&border));
+    let owner = (Some(container.0), Some(container.1));
+    for child in &absolute {
+        let (margin, offsets, child_size) = {
+            let mut child = child.borrow_mut();
+            let child_style = child.style.clone();
+            let margin = resolve_edges(&child_style.margin, owner.0, direction);
+            let offset = |edge, base| resolve(edge_value(&child_style.offset, edge, direction), base);
+            let offsets = (
+                offset(Edge::Left, owner.0),
+                offset(Edge::Top, owner.1),
+                offset(Edge::Right, owner.0),
+                offset(Edge::Bottom, owner.1),
+            );
+
+            let (mut child_width, mut child_height) = child_style.size(owner);
+            if let (Some(left), Some(right)) = (offsets.0, offsets.2) {
+                child_width = child_width.or(Some(container.0 - left - right - horizontal(&margin)));
+            }
+            if let (Some(top), Some(bottom)) = (offsets.1, offsets.3) {
+                child_height = child_height.or(Some(container.1 - top - bottom - vertical(&margin)));
+            }
+
+            let measured = if child_width.is_none() || child_height.is_none() {
+                compute(&mut child, (child_width, child_height), owner, direction, false)
+            } else {
+                (0.0, 0.0)
+            };
+            let (min, max) = (child_style.min_size(owner), child_style.max_size(owner));
+            let child_size = (
+                clamp(child_width.unwrap_or(measured.0), min.0, max.0).max(0.0),
+                clamp(child_height.unwrap_or(measured.1), min.1, max.1).max(0.0),
+            );
+            (margin, offsets, child_size)
+        };
+
+        let left = match offsets {
+            (Some(left), _, _, _) => border.left + left + margin.left,
+            (None, _, Some(right), _) => width - border.right - right - child_size.0 - margin.right,
+            _ => inset.left + margin.left,
+        };
+        let top = match offsets {
+            (_, Some(top), _, _) => border.top + top + margin.top,
+            (_, None, _, Some(bottom)) => height - border.bottom - bottom - child_size.1 - margin.bottom,
+            _ => inset.top + margin.top,
+        };
+
+        let mut child = child.borrow_mut();
+        compute(&mut child, (Some(child_size.0), Some(child_size.1)), owner, direction, true);
+        child.layout.left = left;
+        child.layout.top = top;
+        child.layout.right = width - left - child_size.0;
+        child.layout.bottom = height - top - child_size.1;
+        child.layout.width = child_size.0;
+        child.layout.height = child_size.1;
+        child.layout.margin = margin;
+    }
+
+    (width, height)
+}
+
+fn flex_item(
+    node: &Rc<RefCell<FlexNode>>,
+    style: &NodeStyle,
+    axis: Axis,
+    inner: (Option<f32>, Option<f32>),
+    single_line_cross: Option<f32>,
+    direction: Direction,
+) -> Item {
+    let mut child = node.borrow_mut();
+    let child_style = child.style.clone();
+    let margin = resolve_edges(&child_style.margin, inner.0, direction);
+    let child_size = child_style.size(inner);
+
+    let align = match child_style.align_self {
+        Align::Auto => style.align_items,
+        align => align,
+    };
+    let stretch = match align {
+        Align::Stretch => axis.cross(child_size).is_none(),
+        _ => false,
+    };
+
+    let mut item = Item {
+        node: node.clone(),
+        margin,
+        align,
+        stretch,
+        grow: child_style.flex_grow(),
+        shrink: child_style.flex_shrink(),
+        base: 0.0,
+        main: 0.0,
+        cross: 0.0,
+        min: child_style.min_size(inner),
+        max: child_style.max_size(inner),
+    };
+
+    // Items stretched in a single line with a definite cross size already know their cross size.
+    let (margin_start, margin_end) = axis.cross_edges(&margin);
+    let cross = axis.cross(child_size).or_else(|| {
+        if stretch {
+            single_line_cross.map(|cross| item.clamp_cross(axis, cross - margin_start - margin_end).max(0.0))
+        } else {
+            None
+        }
+    });
+    if let Some(cross) = cross {
+        item.cross = cross;
+    }
+
+    // See https://www.w3.org/TR/css-flexbox-1/#algo-main-item
+    let base = child_style
+        .flex_basis(axis.main(inner))
+        .or_else(|| axis.main(child_size))
+        .unwrap_or_else(|| axis.main(compute(&mut child, axis.size(None, cross), inner, direction, false)));
+    item.base = base;
+    item.main = item.clamp_main(axis, base);
+    item
+}
+
+fn place(
+    node: &Rc<RefCell<FlexNode>>,
+    position: (f32, f32),
+    size: (f32, f32),
+    margin: ClientEdges,
+    owner: (Option<f32>, Option<f32>),
+    container: (f32, f32),
+    direction: Direction,
+) {
+    let mut child = node.borrow_mut();
+    compute(&mut child, (Some(size.0), Some(size.1)), owner, direction, true);
+
+    let (dx, dy) = relative_offset(&child.style, owner, direction);
+    let (left, top) = (position.0 + dx, position.1 + dy);
+    child.layout.left = left;
+    child.layout.top = top;
+    child.layout.right = container.0 - left - size.0;
+    child.layout.bottom = container.1 - top - size.1;
+    child.layout.width = size.0;
+    child.layout.height = size.1;
+    child.layout.margin = margin;
+}
+
+impl Default for FlexLayoutNode {
+    fn default() -> Self {
+        FlexLayoutNode {
+            inner: Rc::new(RefCell::new(FlexNode::new())),
+            tainted: true,
+            tainted_descendants: false,
+        }
+    }
+}
+
+impl PartialEq for FlexLayoutNode {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+// Like yoga nodes, dropped nodes leave their parent.
+impl Drop for FlexLayoutNode {
+    fn drop(&mut self) {
+        let parent = self.inner.borrow().parent.upgrade();
+        if let Some(parent) = parent {
+            parent.borrow_mut().children.retain(|child| !Rc::ptr_eq(child, &self.inner));
+        }
+    }
+}
+
+impl TLayoutNode for FlexLayoutNode {
+    type TextMeasureMetadata = TextMeasure;
+    type ReflowDirection = Direction;
+
+    fn is_tainted(&self) -> bool {
+        self.tainted
+    }
+
+    fn has_tainted_descendants(&self) -> bool {
+        self.tainted_descendants
+    }
+
+    fn taint(&mut self) {
+        self.tainted = true;
+    }
+
+    fn taint_descendants(&mut self) {
+        self.tainted_descendants = true;
+    }
+
+    fn untaint(&mut self) {
+        self.tainted = false;
+        self.tainted_descendants = false;
+    }
+
+    // Children already attached elsewhere are moved.
+    fn insert_child(&mut self, child: &mut Self, index: u32) {
+        let previous_parent = child.inner.borrow().parent.upgrade();
+        if let Some(previous_parent) = previous_parent {
+            previous_parent
+                .borrow_mut()
+                .children
+                .retain(|node| !Rc::ptr_eq(node, &child.inner));
+        }
+
+        child.inner.borrow_mut().parent = Rc::downgrade(&self.inner);
+        let mut node = self.inner.borrow_mut();
+        let index = (index as usize).min(node.children.len());
+        node.children.insert(index, child.inner.clone());
+    }
+
+    fn append_child(&mut self, child: &mut Self) {
+        let count = self.child_count();
+        self.insert_child(child, count);
+    }
+
+    fn remove_child(&mut self, child: &mut Self) {
+        let mut node = self.inner.borrow_mut();
+        let count = node.children.len();
+        node.children.retain(|node| !Rc::ptr_eq(node, &child.inner));
+        if node.children.len() != count {
+            child.inner.borrow_mut().parent = Weak::new();
+        }
+    }
+
+    // Like yoga, nodes without a size of their own fill the available space.
+    fn reflow_subtree(&mut self, width: u32, height: u32, direction: Self::ReflowDirection) {
+        let mut node = self.inner.borrow_mut();
+        clear_cache(&mut node);
+
+        let owner = (Some(width as f32), Some(height as f32));
+        let style = node.style.clone();
+        let margin = resolve_edges(&style.margin, owner.0, direction);
+        let (min, max) = (style.min_size(owner), style.max_size(owner));
+        let (own_width, own_height) = style.size(owner);
+        let size = (
+            clamp(own_width.unwrap_or(width as f32 - horizontal(&margin)), min.0, max.0).max(0.0),
+            clamp(own_height.unwrap_or(height as f32 - vertical(&margin)), min.1, max.1).max(0.0),
+        );
+
+        compute(&mut node, (Some(size.0), Some(size.1)), owner, direction, true);
+
+        let (dx, dy) = relative_offset(&style, owner, direction);
+        node.layout.left = margin.left + dx;
+        node.layout.top = margin.top + dy;
+        node.layout.right = width as f32 - node.layout.left - size.0;
+        node.layout.bottom = height as f32 - node.layout.top - size.1;
+        node.layout.width = size.0;
+        node.layout.height = size.1;
+        node.layout.margin = margin;
+        round_layout(&mut node, (0.0, 0.0));
+    }
+
+    fn child_count(&self) -> u32 {
+        self.inner.borrow().children.len() as u32
+    }
+
+    fn get_layout(&self) -> Layout {
+        let layout = self.inner.borrow().layout;
+        Layout::new(layout.left, layout.right, layout.top, layout.bottom, layout.width, layout.height)
+    }
+
+    fn get_padding(&self) -> ClientEdges {
+        self.inner.borrow().layout.padding
+    }
+
+    fn get_border(&self) -> ClientEdges {
+        self.inner.borrow().layout.border
+    }
+
+    fn get_margin(&self) -> ClientEdges {
+        self.inner.borrow().layout.margin
+    }
+
+    fn reset(&mut self) {
+        let mut node = self.inner.borrow_mut();
+        node.style = NodeStyle::default();
+        node.measure = None;
+        node.layout = ComputedLayout::default();
+    }
+
+    fn apply_styles(&mut self, styles: &[FlexStyle]) {
+        let mut node = self.inner.borrow_mut();
+        for style in styles {
+            node.style.apply(style);
+        }
+    }
+
+    fn set_text_measure(&mut self, measure: Option<TextMeasure>) {
+        self.inner.borrow_mut().measure = measure;
+    }
+}
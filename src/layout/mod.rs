@@ -0,0 +1,108 @@
+use std::rc::Rc;
+use traits::TTextMeasurer;
+use types::{TextFont, TextMetrics};
+
+mod flex;
+#[cfg(feature = "yoga-layout")]
+mod yoga;
+
+pub use self::flex::FlexLayoutNode;
+#[cfg(feature = "yoga-layout")]
+pub use self::yoga::YogaLayoutNode;
+
+// Yoga stays the default backend, the pure Rust one is picked with the `flex-layout` feature,
+// or when building without `yoga-layout`.
+#[cfg(all(feature = "yoga-layout", not(feature = "flex-layout")))]
+pub type LayoutNode = YogaLayoutNode;
+#[cfg(any(not(feature = "yoga-layout"), feature = "flex-layout"))]
+pub type LayoutNode = FlexLayoutNode;
+
+// Installed by the tree on its text nodes.
+#[derive(Debug, Clone)]
+pub struct TextMeasurement {
+    pub measurer: Rc<TTextMeasurer>,
+    pub font: TextFont,
+}
+
+// Text of a node along with how to measure it, read by the layout backends.
+#[derive(Debug, Clone)]
+pub struct TextMeasure {
+    pub measurement: TextMeasurement,
+    pub text: String,
+}
+
+// Every char advances by the same amount, so that text layout is predictable in headless tests.
+// All values are relative to the font size.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct MonospaceTextMeasurer {
+    pub advance: f32,
+    pub line_height: f32,
+    pub ascent: f32,
+}
+
+impl PartialEq for TextMeasurement {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.measurer, &other.measurer) && self.font == other.font
+    }
+}
+
+impl TextMeasure {
+    pub fn measure(&self, max_width: Option<f32>) -> TextMetrics {
+        let measurement = &self.measurement;
+        measurement.measurer.measure(&self.text, &measurement.font, max_width)
+    }
+}
+
+impl Default for MonospaceTextMeasurer {
+    fn default() -> Self {
+        MonospaceTextMeasurer {
+            advance: 0.6,
+            line_height: 1.2,
+            ascent: 0.8,
+        }
+    }
+}
+
+impl TTextMeasurer for MonospaceTextMeasurer {
+    // Whitespace collapses like with `white-space: normal`, except for line breaks which are kept.
+    fn measure(&self, text: &str, font: &TextFont, max_width: Option<f32>) -> TextMetrics {
+        if text.trim().is_empty() {
+            return TextMetrics::default();
+        }
+
+        let advance = self.advance * font.size;
+        let line_height = font.line_height.unwrap_or(self.line_height * font.size);
+        let max_columns = max_width.map(|width| ((width / advance).floor() as usize).max(1));
+
+        let lines = text
+            .split('\n')
+            .flat_map(|paragraph| wrap_columns(paragraph, max_columns))
+            .collect::<Vec<_>>();
+        let columns = lines.iter().cloned().max().unwrap_or(0);
+
+        TextMetrics {
+            width: columns as f32 * advance,
+            height: lines.len() as f32 * line_height,
+            baseline: (line_height - font.size) / 2.0 + self.ascent * font.size,
+        }
+    }
+}
+
+// Returns the length of every line in chars, wrapping greedily between words.
+fn wrap_columns(paragraph: &str, max_columns: Option<usize>) -> Vec<usize> {
+    let mut lines = vec![];
+    let mut current = 0;
+    for word in paragraph.split_whitespace() {
+        let length = word.chars().count();
+        let extended = if current == 0 { length } else { current + 1 + length };
+        match max_columns {
+            Some(max_columns) if current > 0 && extended > max_columns => {
+                lines.push(current);
+                current = length;
+            }
+            _ => current = extended,
+        }
+    }
+    lines.push(current);
+    lines
+}
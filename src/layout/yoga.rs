@@ -0,0 +1,150 @@
+use layout::TextMeasure;
+use traits::TLayoutNode;
+use types::{ClientEdges, Direction, FlexStyle, Layout};
+use yoga::{Context, MeasureMode, Node, NodeRef, Size};
+
+// Yoga tracks which nodes need a reflow on its own, the taint flags track which styles need re-applying.
+#[derive(Debug, PartialEq)]
+pub struct YogaLayoutNode {
+    pub node: Node,
+    tainted: bool,
+    tainted_descendants: bool,
+}
+
+impl Default for YogaLayoutNode {
+    fn default() -> Self {
+        Self {
+            node: Node::new(),
+            tainted: true,
+            tainted_descendants: false,
+        }
+    }
+}
+
+impl TLayoutNode for YogaLayoutNode {
+    type TextMeasureMetadata = TextMeasure;
+    type ReflowDirection = Direction;
+
+    fn is_tainted(&self) -> bool {
+        self.tainted
+    }
+
+    fn has_tainted_descendants(&self) -> bool {
+        self.tainted_descendants
+    }
+
+    fn taint(&mut self) {
+        self.tainted = true;
+    }
+
+    fn taint_descendants(&mut self) {
+        self.tainted_descendants = true;
+    }
+
+    fn untaint(&mut self) {
+        self.tainted = false;
+        self.tainted_descendants = false;
+    }
+
+    fn insert_child(&mut self, child: &mut Self, index: u32) {
+        self.node.insert_child(&mut child.node, index);
+    }
+
+    fn append_child(&mut self, child: &mut Self) {
+        let count = self.node.child_count();
+        self.insert_child(child, count);
+    }
+
+    fn remove_child(&mut self, child: &mut Self) {
+        self.node.remove_child(&mut child.node);
+    }
+
+    fn reflow_subtree(&mut self, width: u32, height: u32, direction: Self::ReflowDirection) {
+        self.node.calculate_layout(width as f32, height as f32, direction.into());
+    }
+
+    fn child_count(&self) -> u32 {
+        self.node.child_count()
+    }
+
+    fn get_layout(&self) -> Layout {
+        self.node.get_layout().into()
+    }
+
+    fn get_padding(&self) -> ClientEdges {
+        ClientEdges {
+            top: self.node.get_layout_padding_top(),
+            right: self.node.get_layout_padding_right(),
+            bottom: self.node.get_layout_padding_bottom(),
+            left: self.node.get_layout_padding_left(),
+        }
+    }
+
+    fn get_border(&self) -> ClientEdges {
+        ClientEdges {
+            top: self.node.get_layout_border_top(),
+            right: self.node.get_layout_border_right(),
+            bottom: self.node.get_layout_border_bottom(),
+            left: self.node.get_layout_border_left(),
+        }
+    }
+
+    fn get_margin(&self) -> ClientEdges {
+        ClientEdges {
+            top: self.node.get_layout_margin_top(),
+            right: self.node.get_layout_margin_right(),
+            bottom: self.node.get_layout_margin_bottom(),
+            left: self.node.get_layout_margin_left(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.node.reset()
+    }
+
+    fn apply_styles(&mut self, styles: &[FlexStyle]) {
+        for style in styles {
+            self.node.apply_style(&(*style).into());
+        }
+    }
+
+    fn set_text_measure(&mut self, measure: Option<TextMeasure>) {
+        match measure {
+            Some(measure) => {
+                self.node.set_context(Some(Context::new(measure)));
+                self.node.set_measure_func(Some(measure_text));
+                self.node.set_baseline_func(Some(text_baseline));
+                self.node.mark_dirty();
+            }
+            None => {
+                self.node.set_measure_func(None);
+                self.node.set_baseline_func(None);
+                self.node.set_context(None);
+            }
+        }
+    }
+}
+
+fn text_measure(node_ref: &NodeRef) -> Option<&TextMeasure> {
+    Node::get_context(node_ref).and_then(|context| context.downcast_ref::<TextMeasure>())
+}
+
+// See https://yogalayout.com/docs/api/c#measure-function
+extern "C" fn measure_text(node_ref: NodeRef, width: f32, width_mode: MeasureMode, _: f32, _: MeasureMode) -> Size {
+    let max_width = match width_mode {
+        MeasureMode::Undefined => None,
+        MeasureMode::Exactly | MeasureMode::AtMost => Some(width),
+    };
+    let metrics = text_measure(&node_ref).map(|v| v.measure(max_width)).unwrap_or_default();
+    Size {
+        width: match width_mode {
+            MeasureMode::Exactly => width,
+            MeasureMode::Undefined | MeasureMode::AtMost => metrics.width,
+        },
+        height: metrics.height,
+    }
+}
+
+extern "C" fn text_baseline(node_ref: NodeRef, width: f32, _: f32) -> f32 {
+    text_measure(&node_ref).map_or(0.0, |v| v.measure(Some(width)).baseline)
+}
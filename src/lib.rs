@@ -14,18 +14,23 @@ extern crate num_traits;
 extern crate rsx_tree;
 extern crate self_tokenize_trait;
 extern crate serde;
+extern crate serde_json;
 extern crate yoga;
 extern crate hashbrown;
 
 #[macro_use]
 pub mod macros;
 
+pub mod control;
 pub mod convert;
 pub mod events;
 pub mod layout;
+pub mod mutation;
 pub mod node;
 pub mod node_data;
 pub mod setup;
+pub mod snapshot;
+pub mod style;
 pub mod traits;
 pub mod tree;
 pub mod types;
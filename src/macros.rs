@@ -104,9 +104,10 @@ macro_rules! fragment {
 
 macro_rules! impl_text_node_from_stringifiable {
     ($src: ty) => {
-        impl<E> From<$src> for DOMNode<E>
+        impl<E, L> From<$src> for DOMNode<E, L>
         where
             E: TGenericEvent,
+            L: TLayoutNode,
         {
             fn from(value: $src) -> Self {
                 DOMNode::new(DOMData::Text(DOMTextNode {
@@ -119,9 +120,10 @@ macro_rules! impl_text_node_from_stringifiable {
 
 macro_rules! impl_number_attribute_from_countable {
     ($src: ty) => {
-        impl<E> From<$src> for DOMAttributeValue<E>
+        impl<E, L> From<$src> for DOMAttributeValue<E, L>
         where
             E: TGenericEvent,
+            L: TLayoutNode,
         {
             #[cfg_attr(feature = "cargo-clippy", allow(cast_lossless))]
             fn from(value: $src) -> Self {
@@ -0,0 +1,212 @@
+use num_traits::{FromPrimitive, ToPrimitive};
+
+use node::{DOMAttributeValue, DOMAttributes, DOMData, DOMNode, DOMNodeId, DOMTagName, DOMText};
+use traits::{TDOMNode, TGenericEvent, TLayoutNode};
+use tree::DOMTree;
+use types::{Atom, Closure, DOMNodeRawId, EventType, HeadlessClosureId, KnownAttributeName, KnownElementName};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MutationTagName {
+    Known(KnownElementName),
+    Simple(Atom),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MutationAttributeName {
+    Known(KnownAttributeName),
+    Simple(Atom),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MutationValue {
+    Boolean(bool),
+    Number(f64),
+    Char(char),
+    Str(String),
+}
+
+// Serializable DOM changes, so that trees can be edited from outside of the render thread.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DOMMutation {
+    SetAttribute {
+        node: DOMNodeRawId,
+        name: MutationAttributeName,
+        value: MutationValue,
+    },
+    RemoveAttribute {
+        node: DOMNodeRawId,
+        name: MutationAttributeName,
+    },
+    AddEventListener {
+        node: DOMNodeRawId,
+        ty: EventType,
+        closure: HeadlessClosureId,
+    },
+    RemoveEventListener {
+        node: DOMNodeRawId,
+        ty: EventType,
+    },
+    AddCustomEventListener {
+        node: DOMNodeRawId,
+        name: Atom,
+        closure: HeadlessClosureId,
+    },
+    RemoveCustomEventListener {
+        node: DOMNodeRawId,
+        name: Atom,
+    },
+    SetText {
+        node: DOMNodeRawId,
+        text: String,
+    },
+    AppendElement {
+        parent: DOMNodeRawId,
+        tag: MutationTagName,
+    },
+    AppendText {
+        parent: DOMNodeRawId,
+        text: String,
+    },
+    Remove {
+        node: DOMNodeRawId,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MutationError {
+    UnknownNode(DOMNodeRawId),
+    NotAnElement(DOMNodeRawId),
+    NotATextNode(DOMNodeRawId),
+}
+
+impl From<MutationTagName> for DOMTagName {
+    fn from(name: MutationTagName) -> Self {
+        match name {
+            MutationTagName::Known(name) => DOMTagName::KnownName(name),
+            MutationTagName::Simple(name) => DOMTagName::Simple(name.as_str()),
+        }
+    }
+}
+
+impl<E, L> From<MutationValue> for DOMAttributeValue<E, L>
+where
+    E: TGenericEvent,
+    L: TLayoutNode,
+{
+    fn from(value: MutationValue) -> Self {
+        match value {
+            MutationValue::Boolean(value) => DOMAttributeValue::from(value),
+            MutationValue::Number(value) => DOMAttributeValue::from(value),
+            MutationValue::Char(value) => DOMAttributeValue::from(value),
+            MutationValue::Str(value) => DOMAttributeValue::from(value),
+        }
+    }
+}
+
+impl<T, L> DOMTree<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    // Applies a mutation, returning the raw id of the node it created, if any.
+    pub fn apply_mutation(&mut self, mutation: DOMMutation) -> Result<Option<DOMNodeRawId>, MutationError> {
+        match mutation {
+            DOMMutation::SetAttribute { node, name, value } => {
+                let attributes = self.mutation_attributes(node)?;
+                let value = DOMAttributeValue::from(value);
+                match name {
+                    MutationAttributeName::Known(name) => attributes.common.insert(name, value),
+                    MutationAttributeName::Simple(name) => attributes.simple.insert(name.as_str(), value),
+                };
+                self.mark_mutation_dirty(node);
+                Ok(None)
+            }
+            DOMMutation::RemoveAttribute { node, name } => {
+                let attributes = self.mutation_attributes(node)?;
+                match name {
+                    MutationAttributeName::Known(name) => attributes.common.remove(&name),
+                    MutationAttributeName::Simple(name) => attributes.simple.remove(name.as_str()),
+                };
+                self.mark_mutation_dirty(node);
+                Ok(None)
+            }
+            DOMMutation::AddEventListener { node, ty, closure } => {
+                let attributes = self.mutation_attributes(node)?;
+                attributes.listeners.insert(ty, Closure::headless(closure));
+                Ok(None)
+            }
+            DOMMutation::RemoveEventListener { node, ty } => {
+                let attributes = self.mutation_attributes(node)?;
+                attributes.listeners.remove(&ty);
+                Ok(None)
+            }
+            DOMMutation::AddCustomEventListener { node, name, closure } => {
+                let attributes = self.mutation_attributes(node)?;
+                attributes.custom_listeners.insert(name, Closure::headless(closure));
+                Ok(None)
+            }
+            DOMMutation::RemoveCustomEventListener { node, name } => {
+                let attributes = self.mutation_attributes(node)?;
+                attributes.custom_listeners.remove(&name);
+                Ok(None)
+            }
+            DOMMutation::SetText { node, text } => {
+                let id = self.mutation_target(node)?;
+                match self.get_mut(id).into_value().data {
+                    DOMData::Text(ref mut text_node) => text_node.content = DOMText::from(text),
+                    _ => return Err(MutationError::NotATextNode(node)),
+                }
+                self.get_mut(id).mark_dirty();
+                Ok(None)
+            }
+            DOMMutation::AppendElement { parent, tag } => {
+                let node = DOMNode::from(DOMTagName::from(tag));
+                self.append_mutation_node(parent, node)
+            }
+            DOMMutation::AppendText { parent, text } => {
+                let node = DOMNode::from(text);
+                self.append_mutation_node(parent, node)
+            }
+            DOMMutation::Remove { node } => {
+                let id = self.mutation_target(node)?;
+                self.get_mut(id).detach();
+                // See https://html.spec.whatwg.org/multipage/interaction.html#focus-fixup-rule
+                if self.active_element().map_or(false, |id| !self.is_connected(id)) {
+                    self.blur();
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn mutation_target(&self, raw_id: DOMNodeRawId) -> Result<DOMNodeId<T, L>, MutationError> {
+        <DOMNodeId<T, L> as FromPrimitive>::from_u64(raw_id)
+            .filter(|&id| self.contains(id))
+            .ok_or(MutationError::UnknownNode(raw_id))
+    }
+
+    // Selectors of descendants can depend on attributes of their ancestors.
+    fn mark_mutation_dirty(&mut self, raw_id: DOMNodeRawId) {
+        if let Ok(id) = self.mutation_target(raw_id) {
+            self.get_mut(id).mark_subtree_dirty();
+        }
+    }
+
+    fn mutation_attributes(&mut self, raw_id: DOMNodeRawId) -> Result<&mut DOMAttributes<T, L>, MutationError> {
+        let id = self.mutation_target(raw_id)?;
+        self.get_mut(id)
+            .into_value()
+            .data
+            .attributes_mut()
+            .ok_or(MutationError::NotAnElement(raw_id))
+    }
+
+    fn append_mutation_node(&mut self, parent: DOMNodeRawId, node: DOMNode<T, L>) -> Result<Option<DOMNodeRawId>, MutationError> {
+        let parent_id = self.mutation_target(parent)?;
+        if !self.get(parent_id).is_normal() {
+            return Err(MutationError::NotAnElement(parent));
+        }
+        let id = self.get_mut(parent_id).append(node).id();
+        Ok(id.to_u64())
+    }
+}
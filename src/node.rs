@@ -17,40 +17,71 @@ use std::borrow::{Borrow, Cow};
 use rsx_tree::types::Id;
 use layout::LayoutNode;
 use std::cmp::Ordering;
-use yoga::Direction;
+use std::mem;
 use tree::DOMTree;
 use std::rc::Rc;
 
 use jss::traits::TStyleCollect;
 use traits::{TDOMNode, TDOMText, TGenericEvent, TLayoutNode};
-use types::{Closure, EventType, KnownAttributeName, KnownElementName, Prop};
+use types::{
+    Atom, Closure, Display, ElementState, EventType, FlexStyle, KnownAttributeName, KnownElementName, Layout, Prop,
+    ScrollState, Visibility,
+};
 use util::is_event_listener;
+use control::{ControlKind, ControlState};
+use style::Declarations;
 
-pub type DOMNodeId<T> = Id<DOMNode<T>>;
-pub type DOMNodeIdPair<T> = (DOMNodeId<T>, DOMNodeId<T>);
+pub type DOMNodeId<T, L = LayoutNode> = Id<DOMNode<T, L>>;
+pub type DOMNodeIdPair<T, L = LayoutNode> = (DOMNodeId<T, L>, DOMNodeId<T, L>);
 
-pub type DOMNodeSiblingIds<T> = (Option<DOMNodeId<T>>, Option<DOMNodeId<T>>);
-pub type DOMNodeEdgeIds<T> = (Option<DOMNodeId<T>>, Option<DOMNodeId<T>>);
+pub type DOMNodeSiblingIds<T, L = LayoutNode> = (Option<DOMNodeId<T, L>>, Option<DOMNodeId<T, L>>);
+pub type DOMNodeEdgeIds<T, L = LayoutNode> = (Option<DOMNodeId<T, L>>, Option<DOMNodeId<T, L>>);
 // pub type DOMAttributes<T> = Vec<DOMAttribute<T>>;
-pub type DOMChildren<T> = Vec<DOMNodeId<T>>;
+pub type DOMChildren<T, L = LayoutNode> = Vec<DOMNodeId<T, L>>;
 
+// The layout backend can be swapped, e.g. for a no-op one in trees which are never laid out.
 #[derive(Debug, PartialEq)]
-pub struct DOMNode<T>
+pub struct DOMNode<T, L = LayoutNode>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    pub layout_node: LayoutNode,
-    pub data: DOMData<T>,
+    pub layout_node: L,
+    pub data: DOMData<T, L>,
     pub styles: Style,
+    pub scroll: ScrollState,
+    pub control: ControlState,
+    // Inherited from the parent when None.
+    pub visibility: Option<Visibility>,
+    pub(crate) state: ElementState,
+    // Overrides the dimensions styles are resolved against, the tree sets the viewport on its root.
+    pub(crate) containing_block: Option<Layout>,
+    // Size of the viewport, which viewport units are resolved against. Kept up to date by the tree.
+    pub(crate) viewport: Layout,
+    // What the stylesheets and the inline style cascade onto the node.
+    pub(crate) declarations: Declarations,
+    // The cascaded declarations along with inherited ones, keywords resolved.
+    pub(crate) computed_declarations: Declarations,
+    // Values of running transitions and animations, applied over the cascaded declarations.
+    pub(crate) animated_declarations: Declarations,
+    // Built from the cascaded and the animated declarations, merged over `styles` property by property.
+    pub(crate) cascaded_styles: Option<Style>,
+    pub(crate) animated_styles: Option<Style>,
+    // The computed layout styles of jss, converted for the layout backends.
+    pub(crate) flex_styles: Vec<FlexStyle>,
+    // Mirror of the yoga tree, so that it can be reconciled with the DOM tree.
+    pub(crate) layout_parent: Option<DOMNodeId<T, L>>,
+    pub(crate) layout_children: DOMChildren<T, L>,
 }
 
 #[derive(Debug, PartialEq)]
-pub enum DOMData<T>
+pub enum DOMData<T, L = LayoutNode>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    Normal(DOMNormalNode<T>),
-    ShadowHost(DOMTree<T>),
+    Normal(DOMNormalNode<T, L>),
+    ShadowHost(DOMTree<T, L>),
     Text(DOMTextNode),
     Void,
 }
@@ -67,11 +98,12 @@ pub struct DOMTextNode {
 }
 
 #[derive(Debug, PartialEq)]
-pub struct DOMNormalNode<T>
+pub struct DOMNormalNode<T, L = LayoutNode>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    pub attributes: DOMAttributes<T>,
+    pub attributes: DOMAttributes<T, L>,
     pub tag: DOMTagName,
 }
 
@@ -83,14 +115,22 @@ pub enum DOMTagName {
 }
 
 #[derive(Debug, PartialEq)]
-pub struct DOMAttribute<T: TGenericEvent>(pub DOMAttributeName, pub DOMAttributeValue<T>);
+pub struct DOMAttribute<T, L = LayoutNode>(pub DOMAttributeName, pub DOMAttributeValue<T, L>)
+where
+    T: TGenericEvent,
+    L: TLayoutNode;
 
 #[derive(Debug, PartialEq)]
-pub struct DOMAttributes<T: TGenericEvent> {
-    pub namespaced: HashMap<(&'static str, &'static str), DOMAttributeValue<T>>,
-    pub common: HashMap<KnownAttributeName, DOMAttributeValue<T>>,
-    pub simple: HashMap<&'static str, DOMAttributeValue<T>>,
+pub struct DOMAttributes<T, L = LayoutNode>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub namespaced: HashMap<(&'static str, &'static str), DOMAttributeValue<T, L>>,
+    pub common: HashMap<KnownAttributeName, DOMAttributeValue<T, L>>,
+    pub simple: HashMap<&'static str, DOMAttributeValue<T, L>>,
     pub listeners: HashMap<EventType, Closure<T>>,
+    pub custom_listeners: HashMap<Atom, Closure<T>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -98,13 +138,15 @@ pub enum DOMAttributeName {
     NamedspacedName(&'static str, &'static str),
     KnownName(KnownAttributeName),
     EventType(EventType),
+    CustomEvent(Atom),
     Simple(&'static str),
 }
 
 #[derive(Debug, PartialEq)]
-pub enum DOMAttributeValue<T>
+pub enum DOMAttributeValue<T, L = LayoutNode>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     Boolean(bool),
     Number(f64),
@@ -113,34 +155,68 @@ where
     Styles(Style),
     Prop(Prop),
     EventListener(Closure<T>),
-    Node(DOMNode<T>),
+    Node(DOMNode<T, L>),
 }
 
-impl<T> Default for DOMNode<T>
+impl<T, L> Default for DOMNode<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     fn default() -> Self {
         DOMNode::from(DOMTagName::from(KnownElementName::Fragment))
     }
 }
 
-impl<T> DOMNode<T>
+impl<T, L> DOMNode<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    pub fn new(data: DOMData<T>) -> Self {
-        let layout_node = LayoutNode::default();
+    pub fn new(data: DOMData<T, L>) -> Self {
+        let layout_node = L::default();
         let styles = Style::default();
 
         DOMNode {
             styles,
             layout_node,
             data,
+            scroll: ScrollState::default(),
+            control: ControlState::default(),
+            visibility: None,
+            state: ElementState::default(),
+            containing_block: None,
+            viewport: Layout::default(),
+            declarations: Declarations::new(),
+            computed_declarations: Declarations::new(),
+            animated_declarations: Declarations::new(),
+            cascaded_styles: None,
+            animated_styles: None,
+            flex_styles: vec![],
+            layout_parent: None,
+            layout_children: DOMChildren::new(),
+        }
+    }
+
+    pub fn declarations(&self) -> &Declarations {
+        &self.declarations
+    }
+
+    pub fn computed_declarations(&self) -> &Declarations {
+        &self.computed_declarations
+    }
+
+    // The checked and disabled states are taken from the control, the event manager sets the others.
+    pub fn element_state(&self) -> ElementState {
+        let kind = self.control_kind();
+        ElementState {
+            checked: (kind == Some(ControlKind::Checkbox) || kind == Some(ControlKind::Radio)) && self.checked(),
+            disabled: kind.is_some() && self.is_disabled(),
+            ..self.state
         }
     }
 
-    pub fn shadow_dom(self) -> DOMTree<T> {
+    pub fn shadow_dom(self) -> DOMTree<T, L> {
         match self.data {
             DOMData::ShadowHost(tree) => tree,
             DOMData::Void | DOMData::Text(_) | DOMData::Normal(_) => DOMTree::default(),
@@ -148,16 +224,17 @@ where
     }
 }
 
-impl<T> TDOMNode for DOMNode<T>
+impl<T, L> TDOMNode for DOMNode<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    type LayoutNode = LayoutNode;
-    type Id = DOMNodeId<T>;
-    type Data = DOMData<T>;
+    type LayoutNode = L;
+    type Id = DOMNodeId<T, L>;
+    type Data = DOMData<T, L>;
     type Event = T;
 
-    fn data(&self) -> &DOMData<T> {
+    fn data(&self) -> &DOMData<T, L> {
         &self.data
     }
 
@@ -181,39 +258,88 @@ where
         self.data.is_known(name).is_some()
     }
 
-    fn layout_node(&self) -> &LayoutNode {
+    fn layout_node(&self) -> &L {
         &self.layout_node
     }
 
-    fn reflow_subtree(&mut self, width: u32, height: u32, direction: Direction) {
+    fn reflow_subtree(&mut self, width: u32, height: u32, direction: L::ReflowDirection) {
         self.layout_node.reflow_subtree(width, height, direction);
     }
 }
 
-impl<T> DOMNode<T>
+impl<T, L> DOMNode<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     pub fn apply_measurement_metadata_to_layout(&mut self) {
         // use self::KnownElementName::*;
 
         // preset dimensions for calculate
-        let layout = self.layout_node.get_layout();
-
-        // Set current node dimensions to style context
-        self.styles
-            .context
-            .set_dimension(DimensionType::Parent, Some(layout.clone()));
+        let layout = match self.containing_block {
+            Some(ref layout) => layout.clone(),
+            None => self.layout_node.get_layout(),
+        };
+        let viewport = self.viewport;
+
+        // Inline jss styles win over the stylesheets, animations over both.
+        // See https://drafts.csswg.org/css-cascade/#cascade-origin
+        let mut flex_styles = computed_flex_styles(&mut self.styles, &layout, &viewport);
+        if let Some(ref mut styles) = self.cascaded_styles {
+            merge_flex_styles(&mut flex_styles, computed_flex_styles(styles, &layout, &viewport));
+        }
+        if let Some(attributes) = self.data.attributes_mut() {
+            if let Some(&mut DOMAttributeValue::Styles(ref mut styles)) =
+                attributes.common.get_mut(&KnownAttributeName::Style)
+            {
+                merge_flex_styles(&mut flex_styles, computed_flex_styles(styles, &layout, &viewport));
+            }
+        }
+        if let Some(ref mut styles) = self.animated_styles {
+            merge_flex_styles(&mut flex_styles, computed_flex_styles(styles, &layout, &viewport));
+        }
+        let previous = mem::replace(&mut self.flex_styles, flex_styles);
+        let display = if self.is_display_none() {
+            Display::None
+        } else {
+            Display::Flex
+        };
+        // Always set last, so that a node shown again doesn't keep its previous display.
+        self.flex_styles.push(FlexStyle::Display(display));
+
+        // Layout nodes only ever get styles added, so that properties which aren't styled anymore
+        // are set back to their initial values first.
+        let mut styles = previous
+            .iter()
+            .filter(|&style| !self.flex_styles.iter().any(|v| v.is_same_property(style)))
+            .map(FlexStyle::initial)
+            .collect::<Vec<_>>();
+        styles.extend(self.flex_styles.iter().cloned());
+        self.layout_node.apply_styles(&styles);
+    }
 
-        let layout_node = &mut self.layout_node;
-        self.styles.calculate_layout();
-        self.styles.calculate_appearance();
+    // See https://html.spec.whatwg.org/multipage/interaction.html#the-hidden-attribute
+    pub fn is_hidden(&self) -> bool {
+        let attributes = self.data.attributes_ref();
+        match attributes.and_then(|v| v.common.get(&KnownAttributeName::Hidden)) {
+            Some(&DOMAttributeValue::Boolean(value)) => value,
+            Some(_) => true,
+            None => false,
+        }
+    }
 
-        // println!("Computed: {:?}", &self.styles.computed.layout);
-        layout_node.node.apply_styles(&self.styles.computed.layout);
+    // Hidden elements aren't rendered, as with the user agent stylesheet.
+    // See https://html.spec.whatwg.org/multipage/rendering.html#hidden-elements
+    pub fn is_display_none(&self) -> bool {
+        // The last declaration wins.
+        let mut display = self.flex_styles.iter().rev().filter_map(|style| match *style {
+            FlexStyle::Display(display) => Some(display),
+            _ => None,
+        });
+        self.is_hidden() || display.next() == Some(Display::None)
     }
 
-    pub fn append_to_layout_node(&mut self, parent: &mut DOMNode<T>) {
+    pub fn append_to_layout_node(&mut self, parent: &mut DOMNode<T, L>) {
         let parent = &mut parent.layout_node;
         let child = &mut self.layout_node;
         let count = parent.child_count();
@@ -221,13 +347,119 @@ where
         parent.insert_child(child, count);
     }
 
-    pub fn remove_from_layout_node(&mut self, parent: &mut DOMNode<T>) {
+    pub fn insert_into_layout_node(&mut self, parent: &mut DOMNode<T, L>, index: u32) {
+        let parent = &mut parent.layout_node;
+        let child = &mut self.layout_node;
+        parent.insert_child(child, index);
+    }
+
+    pub fn remove_from_layout_node(&mut self, parent: &mut DOMNode<T, L>) {
         let parent = &mut parent.layout_node;
         let child = &mut self.layout_node;
         parent.remove_child(child);
     }
 }
 
+// Set current node and viewport dimensions to style context, jss computes yoga's copies of the layout types.
+fn computed_flex_styles(styles: &mut Style, layout: &Layout, viewport: &Layout) -> Vec<FlexStyle> {
+    styles.context.set_dimension(DimensionType::Parent, Some((*layout).into()));
+    styles.context.set_dimension(DimensionType::Viewport, Some((*viewport).into()));
+
+    styles.calculate_layout();
+    styles.calculate_appearance();
+    styles.computed.layout.iter().cloned().map(FlexStyle::from).collect()
+}
+
+// The overriding styles replace the ones setting the same properties, the others are kept.
+fn merge_flex_styles(styles: &mut Vec<FlexStyle>, overrides: Vec<FlexStyle>) {
+    styles.retain(|style| !overrides.iter().any(|v| v.is_same_property(style)));
+    styles.extend(overrides);
+}
+
+impl<T, L> DOMNode<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    // See https://html.spec.whatwg.org/multipage/interaction.html#attr-tabindex
+    pub fn tab_index(&self) -> Option<i32> {
+        let attributes = self.data.attributes_ref()?;
+        match attributes.common.get(&KnownAttributeName::Tabindex) {
+            Some(&DOMAttributeValue::Number(value)) => Some(value as i32),
+            Some(&DOMAttributeValue::Str(ref value)) => value.as_ref().trim().parse().ok(),
+            _ if self.is_focusable_by_default() => Some(0),
+            _ => None,
+        }
+    }
+
+    // Disabled form controls can't be focused, whatever their tabindex.
+    // See https://html.spec.whatwg.org/multipage/interaction.html#focusable-area
+    pub fn is_focusable(&self) -> bool {
+        self.tab_index().is_some() && !(self.control_kind().is_some() && self.is_disabled())
+    }
+
+    pub fn is_scroll_container(&self) -> bool {
+        self.is_known(KnownElementName::ScrollView)
+    }
+
+    fn is_focusable_by_default(&self) -> bool {
+        use self::KnownElementName::*;
+        let attributes = match self.data.attributes_ref() {
+            Some(attributes) => attributes,
+            None => return false,
+        };
+        match self.data.tag() {
+            Some(&DOMTagName::KnownName(A)) => attributes.simple.contains_key("href"),
+            Some(&DOMTagName::KnownName(name)) => match name {
+                Button | Input | Select | Textarea | TextInput | Switch | Slider | Picker => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl<T, L> DOMNode<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    // Names are expected in lowercase, known elements go by their name in the table.
+    pub fn has_local_name(&self, name: &str) -> bool {
+        match self.data.tag() {
+            Some(&DOMTagName::KnownName(known)) => known.as_str() == name,
+            Some(&DOMTagName::Simple(tag)) | Some(&DOMTagName::NamedspacedName(_, tag)) => {
+                tag.eq_ignore_ascii_case(name)
+            }
+            None => false,
+        }
+    }
+
+    // Known attributes are looked up by their name in the table.
+    pub fn attribute(&self, name: &str) -> Option<&DOMAttributeValue<T, L>> {
+        let attributes = self.data.attributes_ref()?;
+        attributes.simple.get(name).or_else(|| {
+            let known = name.parse::<KnownAttributeName>().ok()?;
+            attributes.common.get(&known)
+        })
+    }
+
+    pub fn element_id(&self) -> Option<&str> {
+        match self.data.attributes_ref()?.common.get(&KnownAttributeName::Id)? {
+            &DOMAttributeValue::Str(ref id) => Some(id.as_ref()),
+            _ => None,
+        }
+    }
+
+    pub fn class_list(&self) -> Vec<&str> {
+        let attributes = self.data.attributes_ref();
+        match attributes.and_then(|v| v.common.get(&KnownAttributeName::Class)) {
+            Some(&DOMAttributeValue::Str(ref class)) => class.as_ref().split_whitespace().collect(),
+            _ => vec![],
+        }
+    }
+}
+
 impl TDOMText for DOMText {}
 
 impl PartialEq for DOMText {
@@ -258,9 +490,10 @@ impl AsRef<str> for DOMText {
 }
 
 #[allow(dead_code)]
-impl<T> DOMData<T>
+impl<T, L> DOMData<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     pub fn text(&self) -> Option<&DOMText> {
         match self {
@@ -283,7 +516,7 @@ where
         }
     }
 
-    pub fn is_shadow_host(&self) -> Option<&DOMTree<T>> {
+    pub fn is_shadow_host(&self) -> Option<&DOMTree<T, L>> {
         match self {
             &DOMData::ShadowHost(ref value) => Some(value),
             _ => None,
@@ -297,14 +530,14 @@ where
         }
     }
 
-    pub fn is_normal(&self) -> Option<&DOMNormalNode<T>> {
+    pub fn is_normal(&self) -> Option<&DOMNormalNode<T, L>> {
         match self {
             &DOMData::Normal(ref value) => Some(value),
             _ => None,
         }
     }
 
-    pub fn is_known(&self, name: KnownElementName) -> Option<&DOMNormalNode<T>> {
+    pub fn is_known(&self, name: KnownElementName) -> Option<&DOMNormalNode<T, L>> {
         match self {
             &DOMData::Normal(ref value) if value.tag == DOMTagName::KnownName(name) => Some(value),
             _ => None,
@@ -321,8 +554,18 @@ where
         }
     }
 
+    pub fn drop_custom_event_listeners(&mut self) -> Option<Drain<Atom, Closure<T>>> {
+        match self {
+            &mut DOMData::Void | &mut DOMData::ShadowHost(_) | &mut DOMData::Text(_) => None,
+            &mut DOMData::Normal(DOMNormalNode { ref mut attributes, .. }) => {
+                let cleaned = attributes.custom_listeners.drain();
+                Some(cleaned)
+            }
+        }
+    }
+
     #[cfg_attr(rustfmt, rustfmt_skip)]
-    pub fn attributes_ref(&self) -> Option<&DOMAttributes<T>> {
+    pub fn attributes_ref(&self) -> Option<&DOMAttributes<T, L>> {
         match self {
             &DOMData::Void | &DOMData::ShadowHost(_) | &DOMData::Text(_) => None,
             &DOMData::Normal(DOMNormalNode { ref attributes, .. }) => Some(attributes)
@@ -330,7 +573,7 @@ where
     }
 
     #[cfg_attr(rustfmt, rustfmt_skip)]
-    pub fn attributes_mut(&mut self) -> Option<&mut DOMAttributes<T>> {
+    pub fn attributes_mut(&mut self) -> Option<&mut DOMAttributes<T, L>> {
         match self {
             &mut DOMData::Void | &mut DOMData::ShadowHost(_) | &mut DOMData::Text(_) => None,
             &mut DOMData::Normal(DOMNormalNode { ref mut attributes, .. }) => Some(attributes)
@@ -12,114 +12,158 @@ specific language governing permissions and limitations under the License.
 use rsx_tree::types::Ref;
 use std::ops::Deref;
 
+use layout::LayoutNode;
 use node::{DOMNode, DOMNodeEdgeIds, DOMNodeId, DOMNodeSiblingIds};
-use traits::TGenericEvent;
+use traits::{TGenericEvent, TLayoutNode};
+use types::{ClientPosition, ClientSize};
 
 #[derive(Debug, PartialEq)]
-pub struct DOMArenaRef<'a, T: 'a>
+pub struct DOMArenaRef<'a, T: 'a, L: 'a = LayoutNode>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    raw: Ref<'a, DOMNode<T>>,
+    raw: Ref<'a, DOMNode<T, L>>,
 }
 
-impl<'a, T> From<Ref<'a, DOMNode<T>>> for DOMArenaRef<'a, T>
+impl<'a, T, L> From<Ref<'a, DOMNode<T, L>>> for DOMArenaRef<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from(raw: Ref<'a, DOMNode<T>>) -> Self {
+    fn from(raw: Ref<'a, DOMNode<T, L>>) -> Self {
         DOMArenaRef { raw }
     }
 }
 
-impl<'a, T> Deref for DOMArenaRef<'a, T>
+impl<'a, T, L> Deref for DOMArenaRef<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    type Target = DOMNode<T>;
+    type Target = DOMNode<T, L>;
 
     fn deref(&self) -> &Self::Target {
         self.value()
     }
 }
 
-impl<'a, T> DOMArenaRef<'a, T>
+impl<'a, T, L> DOMArenaRef<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    pub(crate) fn value(&self) -> &'a DOMNode<T> {
+    pub(crate) fn value(&self) -> &'a DOMNode<T, L> {
         self.raw.try_value().expect("Node deallocated")
     }
 
-    pub(crate) fn into_value(self) -> &'a DOMNode<T> {
+    pub(crate) fn into_value(self) -> &'a DOMNode<T, L> {
         self.raw.try_into_value().expect("Node deallocated")
     }
 
-    pub fn get(&self, id: DOMNodeId<T>) -> DOMArenaRef<'a, T> {
+    pub fn get(&self, id: DOMNodeId<T, L>) -> DOMArenaRef<'a, T, L> {
         DOMArenaRef::from(self.raw.tree().get(id))
     }
 
-    pub fn id(&self) -> DOMNodeId<T> {
+    pub fn id(&self) -> DOMNodeId<T, L> {
         self.raw.id()
     }
 
-    pub fn parent_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn parent_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.parent_id()
     }
 
-    pub fn parent(&self) -> Option<DOMArenaRef<'a, T>> {
+    pub fn parent(&self) -> Option<DOMArenaRef<'a, T, L>> {
         self.raw.parent().map(DOMArenaRef::from)
     }
 
-    pub fn prev_sibling_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn prev_sibling_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.prev_sibling_id()
     }
 
-    pub fn prev_sibling(&self) -> Option<DOMArenaRef<'a, T>> {
+    pub fn prev_sibling(&self) -> Option<DOMArenaRef<'a, T, L>> {
         self.raw.prev_sibling().map(DOMArenaRef::from)
     }
 
-    pub fn next_sibling_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn next_sibling_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.next_sibling_id()
     }
 
-    pub fn next_sibling(&self) -> Option<DOMArenaRef<'a, T>> {
+    pub fn next_sibling(&self) -> Option<DOMArenaRef<'a, T, L>> {
         self.raw.next_sibling().map(DOMArenaRef::from)
     }
 
-    pub fn first_child_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn first_child_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.first_child_id()
     }
 
-    pub fn first_child(&self) -> Option<DOMArenaRef<'a, T>> {
+    pub fn first_child(&self) -> Option<DOMArenaRef<'a, T, L>> {
         self.raw.first_child().map(DOMArenaRef::from)
     }
 
-    pub fn last_child_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn last_child_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.last_child_id()
     }
 
-    pub fn last_child(&self) -> Option<DOMArenaRef<'a, T>> {
+    pub fn last_child(&self) -> Option<DOMArenaRef<'a, T, L>> {
         self.raw.last_child().map(DOMArenaRef::from)
     }
 
-    pub fn sibling_ids(&self) -> DOMNodeSiblingIds<T> {
+    pub fn sibling_ids(&self) -> DOMNodeSiblingIds<T, L> {
         self.raw.sibling_ids()
     }
 
-    pub fn edge_ids(&self) -> DOMNodeEdgeIds<T> {
+    pub fn edge_ids(&self) -> DOMNodeEdgeIds<T, L> {
         self.raw.edge_ids()
     }
 
-    pub fn children_iter(&self) -> impl Iterator<Item = &'a DOMNode<T>> {
+    pub fn children_ids_iter(&self) -> impl Iterator<Item = DOMNodeId<T, L>> + 'a {
+        self.raw.children_ids_iter()
+    }
+
+    pub fn descendants_ids_iter(&self) -> impl Iterator<Item = DOMNodeId<T, L>> + 'a {
+        self.raw.descendants_ids_iter()
+    }
+
+    pub fn children_iter(&self) -> impl Iterator<Item = &'a DOMNode<T, L>> {
         self.raw.children_values_iter()
     }
 
-    pub fn descendants_iter(&self) -> impl Iterator<Item = &'a DOMNode<T>> {
+    pub fn descendants_iter(&self) -> impl Iterator<Item = &'a DOMNode<T, L>> {
         self.raw.descendants_values_iter()
     }
 
-    pub fn traverse_iter(&self) -> impl Iterator<Item = &'a DOMNode<T>> {
+    pub fn traverse_iter(&self) -> impl Iterator<Item = &'a DOMNode<T, L>> {
         self.raw.traverse_values_iter()
     }
 }
+
+impl<'a, T, L> DOMArenaRef<'a, T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    // See https://drafts.csswg.org/cssom-view/#scrolling-area
+    pub fn scroll_size(&self) -> ClientSize {
+        let layout = self.layout_node.get_layout();
+        let mut size = ClientSize {
+            width: layout.width(),
+            height: layout.height(),
+        };
+        for child in self.children_iter() {
+            let child_layout = child.layout_node.get_layout();
+            size.width = size.width.max(child_layout.left() + child_layout.width());
+            size.height = size.height.max(child_layout.top() + child_layout.height());
+        }
+        size
+    }
+
+    pub fn max_scroll_offset(&self) -> ClientPosition {
+        let layout = self.layout_node.get_layout();
+        let size = self.scroll_size();
+        ClientPosition {
+            left: (size.width - layout.width()).max(0.0),
+            top: (size.height - layout.height()).max(0.0),
+        }
+    }
+}
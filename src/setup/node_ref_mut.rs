@@ -9,185 +9,296 @@ CONDITIONS OF ANY KIND, either express or implied. See the License for the
 specific language governing permissions and limitations under the License.
 */
 
+use jss::types::Style;
 use rsx_tree::types::{Ref, RefMut};
+use std::mem;
 use std::ops::{Deref, DerefMut};
 
-use node::{DOMNode, DOMNodeEdgeIds, DOMNodeId, DOMNodeIdPair, DOMNodeSiblingIds};
+use layout::LayoutNode;
+use node::{DOMAttributeValue, DOMNode, DOMNodeEdgeIds, DOMNodeId, DOMNodeIdPair, DOMNodeSiblingIds};
 use setup::{DOMArenaRef, DOMArenaRefMutPair};
-use traits::TGenericEvent;
+use traits::{TGenericEvent, TLayoutNode};
+use types::{ClientPosition, KnownAttributeName};
 use tree::DOMTree;
 
 #[derive(Debug, PartialEq)]
-pub struct DOMArenaRefMut<'a, T: 'a>
+pub struct DOMArenaRefMut<'a, T: 'a, L: 'a = LayoutNode>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    pub raw: RefMut<'a, DOMNode<T>>,
+    pub raw: RefMut<'a, DOMNode<T, L>>,
 }
 
-impl<'a, T> From<RefMut<'a, DOMNode<T>>> for DOMArenaRefMut<'a, T>
+impl<'a, T, L> From<RefMut<'a, DOMNode<T, L>>> for DOMArenaRefMut<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from(raw: RefMut<'a, DOMNode<T>>) -> Self {
+    fn from(raw: RefMut<'a, DOMNode<T, L>>) -> Self {
         DOMArenaRefMut { raw }
     }
 }
 
-impl<'a, T> Into<DOMArenaRef<'a, T>> for DOMArenaRefMut<'a, T>
+impl<'a, T, L> Into<DOMArenaRef<'a, T, L>> for DOMArenaRefMut<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn into(self) -> DOMArenaRef<'a, T> {
-        DOMArenaRef::from(Into::<Ref<DOMNode<T>>>::into(self.raw))
+    fn into(self) -> DOMArenaRef<'a, T, L> {
+        DOMArenaRef::from(Into::<Ref<DOMNode<T, L>>>::into(self.raw))
     }
 }
 
-impl<'a, T> Deref for DOMArenaRefMut<'a, T>
+impl<'a, T, L> Deref for DOMArenaRefMut<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    type Target = DOMNode<T>;
+    type Target = DOMNode<T, L>;
 
     fn deref(&self) -> &Self::Target {
         self.value()
     }
 }
 
-impl<'a, T> DerefMut for DOMArenaRefMut<'a, T>
+impl<'a, T, L> DerefMut for DOMArenaRefMut<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.value_mut()
     }
 }
 
-impl<'a, T> DOMArenaRefMut<'a, T>
+impl<'a, T, L> DOMArenaRefMut<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    pub fn value(&self) -> &DOMNode<T> {
+    pub fn value(&self) -> &DOMNode<T, L> {
         self.raw.try_value().expect("Node deallocated")
     }
 
-    pub fn value_mut(&mut self) -> &mut DOMNode<T> {
+    pub fn value_mut(&mut self) -> &mut DOMNode<T, L> {
         self.raw.try_value_mut().expect("Node deallocated")
     }
 
-    pub fn into_value(self) -> &'a mut DOMNode<T> {
+    pub fn into_value(self) -> &'a mut DOMNode<T, L> {
         self.raw.try_into_value().expect("Node deallocated")
     }
 
-    pub fn get(&mut self, id: DOMNodeId<T>) -> DOMArenaRef<T> {
+    pub fn get(&mut self, id: DOMNodeId<T, L>) -> DOMArenaRef<T, L> {
         DOMArenaRef::from(self.raw.tree().get(id))
     }
 
-    pub fn get_mut(&mut self, id: DOMNodeId<T>) -> DOMArenaRefMut<T> {
+    pub fn get_mut(&mut self, id: DOMNodeId<T, L>) -> DOMArenaRefMut<T, L> {
         DOMArenaRefMut::from(self.raw.tree_mut().get_mut(id))
     }
 
-    pub fn get_mut_pair(&mut self, ids: DOMNodeIdPair<T>) -> DOMArenaRefMutPair<T> {
+    pub fn get_mut_pair(&mut self, ids: DOMNodeIdPair<T, L>) -> DOMArenaRefMutPair<T, L> {
         DOMArenaRefMutPair::from(self.raw.tree_mut().get_mut_pair(ids))
     }
 
-    pub fn get_mut_self_and(&mut self, id: DOMNodeId<T>) -> DOMArenaRefMutPair<T> {
+    pub fn get_mut_self_and(&mut self, id: DOMNodeId<T, L>) -> DOMArenaRefMutPair<T, L> {
         let ids = (self.id(), id);
         DOMArenaRefMutPair::from(self.raw.tree_mut().get_mut_pair(ids))
     }
 
-    pub fn id(&self) -> DOMNodeId<T> {
+    pub fn id(&self) -> DOMNodeId<T, L> {
         self.raw.id()
     }
 
-    pub fn parent_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn parent_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.parent_id()
     }
 
-    pub fn parent(&mut self) -> Option<DOMArenaRefMut<T>> {
+    pub fn parent(&mut self) -> Option<DOMArenaRefMut<T, L>> {
         self.raw.parent().map(DOMArenaRefMut::from)
     }
 
-    pub fn prev_sibling_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn prev_sibling_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.prev_sibling_id()
     }
 
-    pub fn prev_sibling(&mut self) -> Option<DOMArenaRefMut<T>> {
+    pub fn prev_sibling(&mut self) -> Option<DOMArenaRefMut<T, L>> {
         self.raw.prev_sibling().map(DOMArenaRefMut::from)
     }
 
-    pub fn next_sibling_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn next_sibling_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.next_sibling_id()
     }
 
-    pub fn next_sibling(&mut self) -> Option<DOMArenaRefMut<T>> {
+    pub fn next_sibling(&mut self) -> Option<DOMArenaRefMut<T, L>> {
         self.raw.next_sibling().map(DOMArenaRefMut::from)
     }
 
-    pub fn first_child_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn first_child_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.first_child_id()
     }
 
-    pub fn first_child(&mut self) -> Option<DOMArenaRefMut<T>> {
+    pub fn first_child(&mut self) -> Option<DOMArenaRefMut<T, L>> {
         self.raw.first_child().map(DOMArenaRefMut::from)
     }
 
-    pub fn last_child_id(&self) -> Option<DOMNodeId<T>> {
+    pub fn last_child_id(&self) -> Option<DOMNodeId<T, L>> {
         self.raw.last_child_id()
     }
 
-    pub fn last_child(&mut self) -> Option<DOMArenaRefMut<T>> {
+    pub fn last_child(&mut self) -> Option<DOMArenaRefMut<T, L>> {
         self.raw.last_child().map(DOMArenaRefMut::from)
     }
 
-    pub fn sibling_ids(&self) -> DOMNodeSiblingIds<T> {
+    pub fn sibling_ids(&self) -> DOMNodeSiblingIds<T, L> {
         self.raw.sibling_ids()
     }
 
-    pub fn edge_ids(&self) -> DOMNodeEdgeIds<T> {
+    pub fn edge_ids(&self) -> DOMNodeEdgeIds<T, L> {
         self.raw.edge_ids()
     }
 
-    pub fn append_tree(&mut self, other: DOMTree<T>) -> bool {
-        self.raw.append_tree(other.into_inner())
+    pub fn append_tree(&mut self, other: DOMTree<T, L>) -> bool {
+        let appended = self.raw.append_tree(other.into_inner());
+        if let (true, Some(child_id)) = (appended, self.last_child_id()) {
+            self.get_mut(child_id).mark_subtree_dirty();
+        }
+        appended
     }
 
-    pub fn prepend_tree(&mut self, other: DOMTree<T>) -> bool {
-        self.raw.prepend_tree(other.into_inner())
+    pub fn prepend_tree(&mut self, other: DOMTree<T, L>) -> bool {
+        let prepended = self.raw.prepend_tree(other.into_inner());
+        if let (true, Some(child_id)) = (prepended, self.first_child_id()) {
+            self.get_mut(child_id).mark_subtree_dirty();
+        }
+        prepended
     }
 
-    pub fn append(&mut self, node: DOMNode<T>) -> DOMArenaRefMut<T> {
-        DOMArenaRefMut::from(self.raw.append(node))
+    pub fn append(&mut self, node: DOMNode<T, L>) -> DOMArenaRefMut<T, L> {
+        let mut child = DOMArenaRefMut::from(self.raw.append(node));
+        child.mark_dirty();
+        child
     }
 
-    pub fn prepend(&mut self, node: DOMNode<T>) -> DOMArenaRefMut<T> {
-        DOMArenaRefMut::from(self.raw.prepend(node))
+    pub fn prepend(&mut self, node: DOMNode<T, L>) -> DOMArenaRefMut<T, L> {
+        let mut child = DOMArenaRefMut::from(self.raw.prepend(node));
+        child.mark_dirty();
+        child
     }
 
     pub fn detach(&mut self) {
+        if let Some(parent_id) = self.parent_id() {
+            self.get_mut(parent_id).mark_dirty();
+        }
         self.raw.detach();
     }
 }
 
-impl<'a, T> DOMArenaRefMut<'a, T>
+impl<'a, T, L> DOMArenaRefMut<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    pub fn append_with_layout(&mut self, child_id: DOMNodeId<T>) -> Result<(), ()> {
-        self.raw.append_id(child_id);
+    // Styles of dirty nodes are re-applied on the next relayout, ancestors only remember where to look.
+    pub fn mark_dirty(&mut self) {
+        self.layout_node.taint();
+
+        let mut next_id = self.parent_id();
+        while let Some(id) = next_id {
+            let mut ancestor = self.get_mut(id);
+            if ancestor.layout_node.has_tainted_descendants() {
+                break;
+            }
+            ancestor.layout_node.taint_descendants();
+            next_id = ancestor.parent_id();
+        }
+    }
 
-        let (this_node, child_node) = self.get_mut_self_and(child_id).into_values();
-        child_node.apply_measurement_metadata_to_layout();
-        child_node.append_to_layout_node(this_node);
+    pub fn mark_subtree_dirty(&mut self) {
+        let id = self.id();
+        let ids = self.get(id).descendants_ids_iter().collect::<Vec<_>>();
+        for id in ids {
+            let mut node = self.get_mut(id);
+            node.layout_node.taint();
+            node.layout_node.taint_descendants();
+        }
+        self.mark_dirty();
+    }
+
+    // Cascaded declarations keep overriding the properties they set.
+    pub fn set_styles(&mut self, styles: Style) {
+        self.styles = styles;
+        self.mark_dirty();
+    }
+
+    // Selectors of descendants can depend on the attribute, so that they're restyled too.
+    pub fn set_attribute(&mut self, name: KnownAttributeName, value: DOMAttributeValue<T, L>) {
+        if let Some(attributes) = self.data.attributes_mut() {
+            attributes.common.insert(name, value);
+        }
+        self.mark_subtree_dirty();
+    }
+
+    pub fn remove_attribute(&mut self, name: KnownAttributeName) {
+        if let Some(attributes) = self.data.attributes_mut() {
+            attributes.common.remove(&name);
+        }
+        self.mark_subtree_dirty();
+    }
+
+    // Takes the node out of layout, or puts it back, without rebuilding the layout tree.
+    pub fn set_hidden(&mut self, hidden: bool) {
+        if let Some(attributes) = self.data.attributes_mut() {
+            attributes
+                .common
+                .insert(KnownAttributeName::Hidden, DOMAttributeValue::Boolean(hidden));
+        }
+        self.mark_dirty();
+    }
+
+    // Re-applies styles of dirty nodes only, skipping subtrees without any.
+    pub fn calculate_dirty_styles(&mut self) {
+        if self.layout_node.is_tainted() {
+            self.apply_measurement_metadata_to_layout();
+        }
+        let descend = self.layout_node.has_tainted_descendants();
+        if self.layout_node.is_tainted() || descend {
+            self.sync_layout_children();
+        }
+        self.layout_node.untaint();
+        if !descend {
+            return;
+        }
+
+        let mut next_child_id = self.first_child_id();
+        while let Some(child_id) = next_child_id {
+            {
+                let mut child_ref = self.get_mut(child_id);
+                child_ref.calculate_dirty_styles();
+            }
+
+            next_child_id = self.get(child_id).next_sibling_id();
+        }
+    }
+}
+
+impl<'a, T, L> DOMArenaRefMut<'a, T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub fn append_with_layout(&mut self, child_id: DOMNodeId<T, L>) -> Result<(), ()> {
+        self.raw.append_id(child_id);
+        self.get_mut(child_id).mark_subtree_dirty();
+        self.get_mut(child_id).apply_measurement_metadata_to_layout();
+        self.sync_layout_children();
 
         Ok(())
     }
 
-    pub fn remove_with_layout(&mut self, child_id: DOMNodeId<T>) -> Result<(), ()> {
+    pub fn remove_with_layout(&mut self, child_id: DOMNodeId<T, L>) -> Result<(), ()> {
         self.raw.tree_mut().get_mut(child_id).detach();
-
-        let (this_node, child_node) = self.get_mut_self_and(child_id).into_values();
-        child_node.remove_from_layout_node(this_node);
+        self.remove_layout_child(child_id);
 
         Ok(())
     }
@@ -195,6 +306,7 @@ where
     pub fn build_layout(&mut self) {
         // @todo: adding set dimensions variable for style before calculate
         self.apply_measurement_metadata_to_layout();
+        self.layout_node.untaint();
 
         let mut next_child_id = self.first_child_id();
         while let Some(child_id) = next_child_id {
@@ -203,18 +315,79 @@ where
                 child_ref.build_layout();
             }
 
-            {
-                let (this_node, child_node) = self.get_mut_self_and(child_id).into_values();
-                child_node.append_to_layout_node(this_node);
+            next_child_id = self.get(child_id).next_sibling_id();
+        }
+
+        self.sync_layout_children();
+    }
+
+    // Reconciles the yoga children with the DOM children, only touching the ones out of place
+    // so that yoga doesn't reflow more than needed.
+    pub fn sync_layout_children(&mut self) {
+        let id = self.id();
+        let children = self.get(id).children_ids_iter().collect::<Vec<_>>();
+        if self.layout_children == children {
+            return;
+        }
+
+        let mut current = mem::replace(&mut self.layout_children, vec![]);
+        for &stale_id in current.iter().filter(|id| !children.contains(id)) {
+            self.remove_layout_child(stale_id);
+        }
+        current.retain(|id| children.contains(id));
+
+        for (index, &child_id) in children.iter().enumerate() {
+            if current.get(index) == Some(&child_id) {
+                continue;
             }
+            if let Some(position) = current.iter().position(|&id| id == child_id) {
+                current.remove(position);
+            }
+            self.insert_layout_child(child_id, index as u32);
+            current.insert(index, child_id);
+        }
 
-            next_child_id = self.get(child_id).next_sibling_id();
+        self.layout_children = current;
+    }
+
+    // Yoga nodes can only have one parent, so children moved from elsewhere are removed there first.
+    fn insert_layout_child(&mut self, child_id: DOMNodeId<T, L>, index: u32) {
+        let id = self.id();
+        let previous_parent_id = self.get(child_id).layout_parent;
+        match previous_parent_id {
+            Some(parent_id) if parent_id == id => self.remove_layout_child(child_id),
+            Some(parent_id) => self.get_mut(parent_id).remove_layout_child(child_id),
+            None => {}
+        }
+
+        let (this_node, child_node) = self.get_mut_self_and(child_id).into_values();
+        child_node.insert_into_layout_node(this_node, index);
+        child_node.layout_parent = Some(id);
+    }
+
+    fn remove_layout_child(&mut self, child_id: DOMNodeId<T, L>) {
+        let id = self.id();
+        // Deallocated nodes already left the yoga tree when their layout node was dropped.
+        let is_child = match self.raw.tree().get(child_id).try_value() {
+            Some(child) => child.layout_parent == Some(id),
+            None => false,
+        };
+        if !is_child {
+            return;
         }
+
+        let (this_node, child_node) = self.get_mut_self_and(child_id).into_values();
+        child_node.remove_from_layout_node(this_node);
+        child_node.layout_parent = None;
+        this_node.layout_children.retain(|&v| v != child_id);
     }
 
+    // Restyles the whole subtree, parents inherit before their children.
     pub fn calculate_styles(&mut self) {
+        self.inherit_styles();
         // @todo: adding set dimensions variable for style before calculate
         self.apply_measurement_metadata_to_layout();
+        self.layout_node.untaint();
 
         let mut next_child_id = self.first_child_id();
         while let Some(child_id) = next_child_id {
@@ -227,3 +400,81 @@ where
         }
     }
 }
+
+impl<'a, T, L> DOMArenaRefMut<'a, T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    // Offsets are clamped to the scrolling area, returns false when nothing scrolled.
+    // See https://drafts.csswg.org/cssom-view/#dom-element-scroll
+    pub fn scroll_to(&mut self, left: f32, top: f32) -> bool {
+        if !self.is_scroll_container() {
+            return false;
+        }
+
+        let id = self.id();
+        let max = self.get(id).max_scroll_offset();
+        let offset = ClientPosition {
+            left: left.max(0.0).min(max.left),
+            top: top.max(0.0).min(max.top),
+        };
+        if !self.scroll.set_offset(offset) {
+            return false;
+        }
+
+        let mut next_id = self.parent_id();
+        while let Some(id) = next_id {
+            let mut ancestor = self.get_mut(id);
+            if !ancestor.scroll.mark_changed_descendants() {
+                break;
+            }
+            next_id = ancestor.parent_id();
+        }
+        true
+    }
+
+    pub fn scroll_by(&mut self, dx: f32, dy: f32) -> bool {
+        let offset = self.scroll.offset();
+        self.scroll_to(offset.left + dx, offset.top + dy)
+    }
+
+    // Scrolls every scroll container ancestor just enough for this node to become visible.
+    // See https://drafts.csswg.org/cssom-view/#dom-element-scrollintoview
+    pub fn scroll_into_view(&mut self) -> bool {
+        let layout = self.layout_node.get_layout();
+        let (mut left, mut top) = (layout.left(), layout.top());
+        let (width, height) = (layout.width(), layout.height());
+
+        let mut scrolled = false;
+        let mut next_id = self.parent_id();
+        while let Some(id) = next_id {
+            let mut ancestor = self.get_mut(id);
+            let layout = ancestor.layout_node.get_layout();
+
+            if ancestor.is_scroll_container() {
+                let offset = ancestor.scroll.offset();
+                let target_left = scroll_into_view_offset(offset.left, layout.width(), left, width);
+                let target_top = scroll_into_view_offset(offset.top, layout.height(), top, height);
+                scrolled |= ancestor.scroll_to(target_left, target_top);
+            }
+
+            // Move the rect into the coordinate space of the next ancestor.
+            let offset = ancestor.scroll.offset();
+            left += layout.left() - offset.left;
+            top += layout.top() - offset.top;
+            next_id = ancestor.parent_id();
+        }
+        scrolled
+    }
+}
+
+fn scroll_into_view_offset(offset: f32, viewport: f32, start: f32, size: f32) -> f32 {
+    if start < offset {
+        start
+    } else if start + size > offset + viewport {
+        (start + size - viewport).min(start)
+    } else {
+        offset
+    }
+}
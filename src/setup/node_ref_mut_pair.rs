@@ -9,38 +9,42 @@ CONDITIONS OF ANY KIND, either express or implied. See the License for the
 specific language governing permissions and limitations under the License.
 */
 
+use layout::LayoutNode;
 use node::DOMNode;
 use rsx_tree::types::RefMutPair;
-use traits::TGenericEvent;
+use traits::{TGenericEvent, TLayoutNode};
 
 #[derive(Debug, PartialEq)]
-pub struct DOMArenaRefMutPair<'a, T: 'a>
+pub struct DOMArenaRefMutPair<'a, T: 'a, L: 'a = LayoutNode>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    raw: RefMutPair<'a, DOMNode<T>>,
+    raw: RefMutPair<'a, DOMNode<T, L>>,
 }
 
-impl<'a, T> From<RefMutPair<'a, DOMNode<T>>> for DOMArenaRefMutPair<'a, T>
+impl<'a, T, L> From<RefMutPair<'a, DOMNode<T, L>>> for DOMArenaRefMutPair<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    fn from(raw: RefMutPair<'a, DOMNode<T>>) -> Self {
+    fn from(raw: RefMutPair<'a, DOMNode<T, L>>) -> Self {
         DOMArenaRefMutPair { raw }
     }
 }
 
-impl<'a, T> DOMArenaRefMutPair<'a, T>
+impl<'a, T, L> DOMArenaRefMutPair<'a, T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     #[cfg_attr(feature = "cargo-clippy", allow(type_complexity))]
-    pub fn values(&mut self) -> (&mut DOMNode<T>, &mut DOMNode<T>) {
+    pub fn values(&mut self) -> (&mut DOMNode<T, L>, &mut DOMNode<T, L>) {
         self.raw.try_values().expect("Nodes deallocated")
     }
 
     #[cfg_attr(feature = "cargo-clippy", allow(type_complexity))]
-    pub fn into_values(self) -> (&'a mut DOMNode<T>, &'a mut DOMNode<T>) {
+    pub fn into_values(self) -> (&'a mut DOMNode<T, L>, &'a mut DOMNode<T, L>) {
         self.raw.try_into_values().expect("Nodes deallocated")
     }
 }
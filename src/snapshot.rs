@@ -0,0 +1,131 @@
+use serde_json;
+use std::fmt;
+
+use node::{DOMData, DOMNode, DOMNodeId, DOMTagName};
+use num_traits::ToPrimitive;
+use traits::{TGenericEvent, TLayoutNode};
+use tree::DOMTree;
+use types::{ClientEdges, ClientRect, DOMNodeRawId};
+
+// Computed layout of a subtree in page coordinates, scrolled like `client_rect`, meant for golden-file tests.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct LayoutSnapshot {
+    pub id: DOMNodeRawId,
+    pub name: String,
+    pub rect: ClientRect,
+    pub padding: ClientEdges,
+    pub border: ClientEdges,
+    pub margin: ClientEdges,
+    pub children: Vec<LayoutSnapshot>,
+}
+
+impl LayoutSnapshot {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Layout snapshots are always serializable")
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        let rect = &self.rect;
+        writeln!(
+            f,
+            "{}{} #{} ({}, {}, {}x{}) padding({}) border({}) margin({})",
+            "  ".repeat(depth),
+            self.name,
+            self.id,
+            rect.left(),
+            rect.top(),
+            rect.size.width,
+            rect.size.height,
+            edges_to_string(&self.padding),
+            edges_to_string(&self.border),
+            edges_to_string(&self.margin),
+        )?;
+        for child in &self.children {
+            child.write(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+// One line per node, children indented below their parent.
+impl fmt::Display for LayoutSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+fn edges_to_string(edges: &ClientEdges) -> String {
+    format!("{} {} {} {}", edges.top, edges.right, edges.bottom, edges.left)
+}
+
+fn node_name<T, L>(node: &DOMNode<T, L>) -> String
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    match node.data {
+        DOMData::Normal(ref normal) => match normal.tag {
+            DOMTagName::KnownName(name) => format!("{:?}", name),
+            DOMTagName::Simple(name) => name.to_string(),
+            DOMTagName::NamedspacedName(namespace, name) => format!("{}:{}", namespace, name),
+        },
+        DOMData::Text(_) => "#text".to_string(),
+        DOMData::ShadowHost(_) => "#shadow-host".to_string(),
+        DOMData::Void => "#void".to_string(),
+    }
+}
+
+impl<T, L> DOMTree<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub fn layout_snapshot(&self) -> LayoutSnapshot {
+        let root_id = self.root().id();
+        self.layout_snapshot_of(root_id)
+    }
+
+    pub fn layout_snapshot_of(&self, id: DOMNodeId<T, L>) -> LayoutSnapshot {
+        let mut origin = (0.0, 0.0);
+        let mut next_id = self.get(id).parent_id();
+        while let Some(ancestor_id) = next_id {
+            let ancestor = self.get(ancestor_id);
+            let layout = ancestor.layout_node.get_layout();
+            let offset = ancestor.scroll.offset();
+            origin = (
+                origin.0 + layout.left() - offset.left,
+                origin.1 + layout.top() - offset.top,
+            );
+            next_id = ancestor.parent_id();
+        }
+        self.snapshot_node(id, origin)
+    }
+
+    fn snapshot_node(&self, id: DOMNodeId<T, L>, origin: (f32, f32)) -> LayoutSnapshot {
+        let node = self.get(id);
+        let layout_node = &node.layout_node;
+        let layout = layout_node.get_layout();
+        let rect = ClientRect::new(
+            origin.0 + layout.left(),
+            origin.1 + layout.top(),
+            layout.width(),
+            layout.height(),
+        );
+
+        // Children move with the scroll offset, the scroll container itself doesn't.
+        let offset = node.scroll.offset();
+        let child_origin = (rect.left() - offset.left, rect.top() - offset.top);
+        LayoutSnapshot {
+            id: id.to_u64().expect("Invalid node id"),
+            name: node_name(&node),
+            rect,
+            padding: layout_node.get_padding(),
+            border: layout_node.get_border(),
+            margin: layout_node.get_margin(),
+            children: node
+                .children_ids_iter()
+                .map(|child_id| self.snapshot_node(child_id, child_origin))
+                .collect(),
+        }
+    }
+}
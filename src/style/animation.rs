@@ -0,0 +1,387 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::f64;
+
+use style::{interpolate, split_outside_parens, Declarations, StyleSheetError};
+
+// See https://drafts.csswg.org/css-easing/#easing-functions
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum TimingFunction {
+    Linear,
+    CubicBezier(f32, f32, f32, f32),
+    Steps(u32, StepPosition),
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum StepPosition {
+    Start,
+    End,
+}
+
+// Durations and delays are in milliseconds.
+// See https://drafts.csswg.org/css-transitions/#transition-shorthand-property
+#[derive(Debug, PartialEq, Clone)]
+pub struct Transition {
+    // A property name, or `all`.
+    pub property: String,
+    pub duration: f64,
+    pub delay: f64,
+    pub timing_function: TimingFunction,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum AnimationDirection {
+    Normal,
+    Reverse,
+    Alternate,
+    AlternateReverse,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum FillMode {
+    None,
+    Forwards,
+    Backwards,
+    Both,
+}
+
+// See https://drafts.csswg.org/css-animations/#animation
+#[derive(Debug, PartialEq, Clone)]
+pub struct Animation {
+    pub name: String,
+    pub duration: f64,
+    pub delay: f64,
+    pub timing_function: TimingFunction,
+    // Infinite for `infinite`.
+    pub iteration_count: f64,
+    pub direction: AnimationDirection,
+    pub fill_mode: FillMode,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Keyframe {
+    // Between 0 and 1.
+    pub offset: f32,
+    pub declarations: Declarations,
+}
+
+// Frames are sorted by offset.
+// See https://drafts.csswg.org/css-animations/#keyframes
+#[derive(Debug, PartialEq, Clone)]
+pub struct Keyframes {
+    pub name: String,
+    pub frames: Vec<Keyframe>,
+}
+
+impl Default for TimingFunction {
+    fn default() -> Self {
+        TimingFunction::CubicBezier(0.25, 0.1, 0.25, 1.0)
+    }
+}
+
+impl TimingFunction {
+    pub fn parse(source: &str) -> Option<Self> {
+        let source = source.trim().to_ascii_lowercase();
+        match source.as_str() {
+            "linear" => return Some(TimingFunction::Linear),
+            "ease" => return Some(TimingFunction::default()),
+            "ease-in" => return Some(TimingFunction::CubicBezier(0.42, 0.0, 1.0, 1.0)),
+            "ease-out" => return Some(TimingFunction::CubicBezier(0.0, 0.0, 0.58, 1.0)),
+            "ease-in-out" => return Some(TimingFunction::CubicBezier(0.42, 0.0, 0.58, 1.0)),
+            "step-start" => return Some(TimingFunction::Steps(1, StepPosition::Start)),
+            "step-end" => return Some(TimingFunction::Steps(1, StepPosition::End)),
+            _ => {}
+        }
+
+        let open = source.find('(')?;
+        if !source.ends_with(')') {
+            return None;
+        }
+        let arguments = source[open + 1..source.len() - 1]
+            .split(',')
+            .map(str::trim)
+            .collect::<Vec<_>>();
+        match &source[..open] {
+            "cubic-bezier" if arguments.len() == 4 => {
+                let v = arguments
+                    .iter()
+                    .map(|v| v.parse::<f32>().ok())
+                    .collect::<Option<Vec<_>>>()?;
+                // The x coordinates have to stay within the [0, 1] range.
+                if v[0] < 0.0 || v[0] > 1.0 || v[2] < 0.0 || v[2] > 1.0 {
+                    return None;
+                }
+                Some(TimingFunction::CubicBezier(v[0], v[1], v[2], v[3]))
+            }
+            "steps" if !arguments.is_empty() && arguments.len() <= 2 => {
+                let count = arguments[0].parse::<u32>().ok().filter(|&v| v > 0)?;
+                let position = match arguments.get(1).cloned() {
+                    None | Some("end") | Some("jump-end") => StepPosition::End,
+                    Some("start") | Some("jump-start") => StepPosition::Start,
+                    _ => return None,
+                };
+                Some(TimingFunction::Steps(count, position))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, progress: f32) -> f32 {
+        match self {
+            TimingFunction::Linear => progress,
+            TimingFunction::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, progress),
+            TimingFunction::Steps(count, position) => {
+                let count = count as f32;
+                let step = match position {
+                    StepPosition::Start => (progress * count).floor() + 1.0,
+                    StepPosition::End => (progress * count).floor(),
+                };
+                (step / count).max(0.0).min(1.0)
+            }
+        }
+    }
+}
+
+// Solves x(t) = progress with Newton's method, falling back to bisection where the curve is flat.
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, progress: f32) -> f32 {
+    if progress <= 0.0 || progress >= 1.0 {
+        return progress;
+    }
+    let bezier = |a: f32, b: f32, t: f32| {
+        let u = 1.0 - t;
+        3.0 * a * t * u * u + 3.0 * b * t * t * u + t * t * t
+    };
+    let derivative = |a: f32, b: f32, t: f32| {
+        let u = 1.0 - t;
+        3.0 * a * u * u + 6.0 * (b - a) * t * u + 3.0 * (1.0 - b) * t * t
+    };
+
+    let mut t = progress;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, t) - progress;
+        if error.abs() < 1e-6 {
+            return bezier(y1, y2, t);
+        }
+        let slope = derivative(x1, x2, t);
+        if slope.abs() < 1e-6 {
+            break;
+        }
+        t -= error / slope;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    t = progress;
+    for _ in 0..32 {
+        let x = bezier(x1, x2, t);
+        if (x - progress).abs() < 1e-6 {
+            break;
+        }
+        if x < progress {
+            low = t;
+        } else {
+            high = t;
+        }
+        t = (low + high) / 2.0;
+    }
+    bezier(y1, y2, t)
+}
+
+impl Transition {
+    // Parses a comma separated list like `width 200ms ease-in, color 1s`.
+    pub fn parse_list(value: &Value) -> Vec<Transition> {
+        let source = match value.as_str() {
+            Some(source) => source,
+            None => return vec![],
+        };
+        split_outside_parens(source, |c| c == ',')
+            .into_iter()
+            .filter_map(Transition::parse)
+            .collect()
+    }
+
+    pub fn parse(source: &str) -> Option<Transition> {
+        let mut transition = Transition {
+            property: "all".to_string(),
+            duration: 0.0,
+            delay: 0.0,
+            timing_function: TimingFunction::default(),
+        };
+        let mut times = 0;
+        for token in split_outside_parens(source, char::is_whitespace) {
+            if let Some(time) = parse_time(token) {
+                match times {
+                    0 => transition.duration = time.max(0.0),
+                    1 => transition.delay = time,
+                    _ => return None,
+                }
+                times += 1;
+            } else if let Some(timing_function) = TimingFunction::parse(token) {
+                transition.timing_function = timing_function;
+            } else {
+                transition.property = token.to_ascii_lowercase();
+            }
+        }
+        Some(transition)
+    }
+}
+
+impl Animation {
+    // Parses a comma separated list like `spin 1s linear infinite, fade 200ms forwards`.
+    pub fn parse_list(value: &Value) -> Vec<Animation> {
+        let source = match value.as_str() {
+            Some(source) => source,
+            None => return vec![],
+        };
+        split_outside_parens(source, |c| c == ',')
+            .into_iter()
+            .filter_map(Animation::parse)
+            .filter(|animation| animation.name != "none")
+            .collect()
+    }
+
+    pub fn parse(source: &str) -> Option<Animation> {
+        let mut animation = Animation {
+            name: "none".to_string(),
+            duration: 0.0,
+            delay: 0.0,
+            timing_function: TimingFunction::default(),
+            iteration_count: 1.0,
+            direction: AnimationDirection::Normal,
+            fill_mode: FillMode::None,
+        };
+        let mut times = 0;
+        for token in split_outside_parens(source, char::is_whitespace) {
+            if let Some(time) = parse_time(token) {
+                match times {
+                    0 => animation.duration = time.max(0.0),
+                    1 => animation.delay = time,
+                    _ => return None,
+                }
+                times += 1;
+                continue;
+            }
+            if let Some(timing_function) = TimingFunction::parse(token) {
+                animation.timing_function = timing_function;
+                continue;
+            }
+            if let Ok(count) = token.parse::<f64>() {
+                animation.iteration_count = count.max(0.0);
+                continue;
+            }
+            match token.to_ascii_lowercase().as_str() {
+                "infinite" => animation.iteration_count = f64::INFINITY,
+                "normal" => animation.direction = AnimationDirection::Normal,
+                "reverse" => animation.direction = AnimationDirection::Reverse,
+                "alternate" => animation.direction = AnimationDirection::Alternate,
+                "alternate-reverse" => animation.direction = AnimationDirection::AlternateReverse,
+                "forwards" => animation.fill_mode = FillMode::Forwards,
+                "backwards" => animation.fill_mode = FillMode::Backwards,
+                "both" => animation.fill_mode = FillMode::Both,
+                "running" | "paused" => {}
+                _ => animation.name = token.to_string(),
+            }
+        }
+        Some(animation)
+    }
+}
+
+impl Keyframes {
+    // Parses e.g. `{ "from": { "width": "0px" }, "50%, 75%": { "width": "10px" }, "to": { "width": "20px" } }`.
+    pub fn parse(name: &str, source: &Declarations) -> Result<Self, StyleSheetError> {
+        let error = || StyleSheetError::InvalidKeyframes(name.to_string());
+        let mut frames = vec![];
+        for (selectors, declarations) in source {
+            let declarations = match *declarations {
+                Value::Object(ref declarations) => declarations,
+                _ => return Err(error()),
+            };
+            for selector in selectors.split(',') {
+                frames.push(Keyframe {
+                    offset: parse_offset(selector.trim()).ok_or_else(error)?,
+                    declarations: declarations.clone(),
+                });
+            }
+        }
+        // The sort is stable, so that later frames with the same offset still win.
+        frames.sort_by(|a, b| a.offset.partial_cmp(&b.offset).unwrap_or(Ordering::Equal));
+        Ok(Keyframes {
+            name: name.to_string(),
+            frames,
+        })
+    }
+
+    pub fn properties(&self) -> BTreeSet<&str> {
+        self.frames
+            .iter()
+            .flat_map(|frame| frame.declarations.keys())
+            .map(String::as_str)
+            .collect()
+    }
+
+    // The value of a property at some progress of an iteration. The timing function applies between two frames,
+    // missing `from` and `to` frames take the value the property would have without the animation.
+    // See https://drafts.csswg.org/css-animations/#keyframes
+    pub fn value_at(
+        &self,
+        property: &str,
+        progress: f32,
+        base: Option<&Value>,
+        timing_function: TimingFunction,
+    ) -> Option<Value> {
+        let mut frames = self
+            .frames
+            .iter()
+            .filter_map(|frame| frame.declarations.get(property).map(|value| (frame.offset, value)))
+            .collect::<Vec<_>>();
+        if let Some(base) = base {
+            let starts = frames.first().map_or(false, |&(offset, _)| offset <= 0.0);
+            let ends = frames.last().map_or(false, |&(offset, _)| offset >= 1.0);
+            if !starts {
+                frames.insert(0, (0.0, base));
+            }
+            if !ends {
+                frames.push((1.0, base));
+            }
+        }
+
+        let (first, last) = (*frames.first()?, *frames.last()?);
+        if progress <= first.0 {
+            return Some(first.1.clone());
+        }
+        if progress >= last.0 {
+            return Some(last.1.clone());
+        }
+        let index = frames.iter().rposition(|&(offset, _)| offset <= progress)?;
+        let ((from_offset, from), (to_offset, to)) = (frames[index], frames[index + 1]);
+        let progress = timing_function.apply((progress - from_offset) / (to_offset - from_offset));
+        Some(interpolate(from, to, progress))
+    }
+}
+
+fn parse_offset(source: &str) -> Option<f32> {
+    match source.to_ascii_lowercase().as_str() {
+        "from" => Some(0.0),
+        "to" => Some(1.0),
+        source if source.ends_with('%') => {
+            let percentage = source[..source.len() - 1].trim().parse::<f32>().ok()?;
+            if percentage < 0.0 || percentage > 100.0 {
+                return None;
+            }
+            Some(percentage / 100.0)
+        }
+        _ => None,
+    }
+}
+
+// In milliseconds.
+fn parse_time(source: &str) -> Option<f64> {
+    let source = source.to_ascii_lowercase();
+    if source.ends_with("ms") {
+        source[..source.len() - 2].parse().ok()
+    } else if source.ends_with('s') {
+        source[..source.len() - 1].parse::<f64>().ok().map(|v| v * 1000.0)
+    } else {
+        None
+    }
+}
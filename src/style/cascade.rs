@@ -0,0 +1,195 @@
+use serde_json;
+
+use node::{DOMAttributeValue, DOMNode, DOMNodeId};
+use style::{expand_shorthands, Declarations, PseudoClass, StyleSheet};
+use traits::{TGenericEvent, TLayoutNode};
+use tree::DOMTree;
+use types::{ElementState, KnownAttributeName, Viewport};
+
+impl<T, L> DOMTree<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub fn stylesheets(&self) -> &[StyleSheet] {
+        &self.stylesheets
+    }
+
+    // Later stylesheets win over earlier ones for selectors of the same specificity.
+    pub fn add_stylesheet(&mut self, sheet: StyleSheet) {
+        self.stylesheets.push(sheet);
+        self.root_mut().mark_subtree_dirty();
+    }
+
+    pub fn remove_stylesheet(&mut self, index: usize) -> Option<StyleSheet> {
+        if index >= self.stylesheets.len() {
+            return None;
+        }
+        let sheet = self.stylesheets.remove(index);
+        self.root_mut().mark_subtree_dirty();
+        Some(sheet)
+    }
+
+    // Restyles the nodes matched by rules depending on one of the flags which changed, before or
+    // after the change. Returns false when the state didn't change.
+    // The checked and disabled flags are ignored, see `DOMNode::element_state`.
+    pub fn set_element_state(&mut self, id: DOMNodeId<T, L>, state: ElementState) -> bool {
+        let state = ElementState {
+            checked: false,
+            disabled: false,
+            ..state
+        };
+        let previous = self.get(id).state;
+        if previous == state {
+            return false;
+        }
+
+        let changed = [
+            (PseudoClass::Hover, previous.hovered != state.hovered),
+            (PseudoClass::Active, previous.active != state.active),
+            (PseudoClass::Focus, previous.focused != state.focused),
+            (PseudoClass::FocusWithin, previous.focus_within != state.focus_within),
+        ];
+        let flipped = self
+            .stylesheets
+            .iter()
+            .flat_map(|sheet| sheet.rules())
+            .filter(|rule| rule.matches_media(&self.viewport))
+            .filter(|rule| {
+                changed
+                    .iter()
+                    .any(|&(pseudo_class, changed)| changed && rule.uses_pseudo_class(pseudo_class))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if flipped.is_empty() {
+            self.get_mut(id).state = state;
+            return true;
+        }
+
+        // Descendant selectors can depend on the state of the node too.
+        let subtree = self.get(id).descendants_ids_iter().collect::<Vec<_>>();
+        let matching = |tree: &Self| {
+            subtree
+                .iter()
+                .cloned()
+                .filter(|&id| {
+                    flipped
+                        .iter()
+                        .any(|rule| rule.match_specificity(|selector| selector.matches(tree, id)).is_some())
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut ids = matching(self);
+        self.get_mut(id).state = state;
+        ids.extend(matching(self));
+        for id in ids {
+            self.get_mut(id).mark_dirty();
+        }
+        true
+    }
+
+    // Restyles the nodes matched by rules whose media queries started or stopped matching the viewport.
+    pub(crate) fn restyle_media_rules(&mut self, previous: &Viewport) {
+        let flipped = self
+            .stylesheets
+            .iter()
+            .flat_map(|sheet| sheet.rules())
+            .filter(|rule| rule.matches_media(previous) != rule.matches_media(&self.viewport))
+            .cloned()
+            .collect::<Vec<_>>();
+        if flipped.is_empty() {
+            return;
+        }
+
+        let ids = self
+            .root()
+            .descendants_ids_iter()
+            .filter(|&id| {
+                flipped
+                    .iter()
+                    .any(|rule| rule.match_specificity(|selector| selector.matches(self, id)).is_some())
+            })
+            .collect::<Vec<_>>();
+        for id in ids {
+            self.get_mut(id).mark_dirty();
+        }
+    }
+
+    // Declarations of the matching rules by ascending specificity then source order, followed by the inline style.
+    // Rules within `@media` rules only take part while their media queries match the viewport.
+    // See https://drafts.csswg.org/css-cascade/#cascade-sort
+    pub fn cascaded_declarations(&self, id: DOMNodeId<T, L>) -> Declarations {
+        let mut matched = vec![];
+        for sheet in &self.stylesheets {
+            for rule in sheet.rules() {
+                if !rule.matches_media(&self.viewport) {
+                    continue;
+                }
+                if let Some(specificity) = rule.match_specificity(|selector| selector.matches(self, id)) {
+                    matched.push((specificity, &rule.declarations));
+                }
+            }
+        }
+        // The sort is stable, so that source order is kept.
+        matched.sort_by_key(|&(specificity, _)| specificity);
+
+        let mut declarations = Declarations::new();
+        for (_, rule_declarations) in matched {
+            declarations.extend(rule_declarations.clone());
+        }
+        if let Some(inline) = inline_declarations(self.get(id).into_value()) {
+            declarations.extend(inline);
+        }
+        declarations
+    }
+
+    // Re-cascades the given nodes, which come parents first, and lets the changes inherit down.
+    // Returns them along with the descendants which had to inherit again.
+    pub(crate) fn cascade_styles(&mut self, mut ids: Vec<DOMNodeId<T, L>>) -> Vec<DOMNodeId<T, L>> {
+        let mut cascaded = vec![];
+        ids.reverse();
+        while let Some(id) = ids.pop() {
+            let declarations = self.cascaded_declarations(id);
+            let changed = self.get(id).declarations != declarations;
+            if changed {
+                self.update_animations(id, &declarations);
+            }
+            let inherited = {
+                let mut node = self.get_mut(id);
+                if changed {
+                    node.declarations = declarations;
+                }
+                let inherited = node.inherit_styles();
+                if changed && !inherited {
+                    node.rebuild_styles();
+                }
+                inherited
+            };
+
+            if inherited {
+                let children = self.get(id).children_ids_iter().collect::<Vec<_>>();
+                for &child_id in children.iter().rev() {
+                    self.get_mut(child_id).mark_dirty();
+                    ids.push(child_id);
+                }
+            }
+            cascaded.push(id);
+        }
+        cascaded
+    }
+}
+
+// Inline jss styles are already parsed, they're merged over the cascaded styles by property when
+// the layout is applied, see `DOMNode::apply_measurement_metadata_to_layout`.
+fn inline_declarations<T, L>(node: &DOMNode<T, L>) -> Option<Declarations>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    let attributes = node.data.attributes_ref()?;
+    match attributes.common.get(&KnownAttributeName::Style)? {
+        &DOMAttributeValue::Str(ref source) => serde_json::from_str(source.as_ref()).ok().map(expand_shorthands),
+        _ => None,
+    }
+}
@@ -0,0 +1,223 @@
+use serde_json::{self, Value};
+use std::collections::BTreeMap;
+
+use node::DOMNodeId;
+use style::{parse_px, Declarations};
+use traits::{TGenericEvent, TLayoutNode};
+use tree::DOMTree;
+use types::{ClientEdges, TextFont, Visibility};
+
+// Channels are 0-255, the alpha 0-1.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: f32,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum ComputedValue {
+    // In pixels.
+    Length(f32),
+    Number(f32),
+    Color(Rgba),
+    Keyword(String),
+    // Values which couldn't be resolved any further, like transforms.
+    Specified(Value),
+}
+
+// Resolved values of a node by property name, like `getComputedStyle` returns them.
+// See https://drafts.csswg.org/cssom/#resolved-values
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ComputedStyle {
+    pub properties: BTreeMap<String, ComputedValue>,
+}
+
+const LENGTH_PROPERTIES: &[&str] = &[
+    "width",
+    "height",
+    "min-width",
+    "min-height",
+    "max-width",
+    "max-height",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "flex-basis",
+    "font-size",
+    "line-height",
+    "border-radius",
+];
+
+impl ComputedStyle {
+    pub fn get(&self, name: &str) -> Option<&ComputedValue> {
+        self.properties.get(name)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Computed styles are always serializable")
+    }
+}
+
+impl<T, L> DOMTree<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    // Declared and inherited values are resolved, box sizes and edges are the used values of the last layout.
+    pub fn get_computed_style(&self, id: DOMNodeId<T, L>) -> ComputedStyle {
+        let node = self.get(id).into_value();
+        let declarations = &node.computed_declarations;
+        let font_size = declarations
+            .get("font-size")
+            .and_then(parse_px)
+            .unwrap_or(TextFont::default().size);
+
+        let mut properties = BTreeMap::new();
+        for (name, value) in declarations {
+            properties.insert(name.clone(), resolve_value(name, value, font_size, declarations));
+        }
+
+        let layout = node.layout_node.get_layout();
+        properties.insert("width".to_string(), ComputedValue::Length(layout.width()));
+        properties.insert("height".to_string(), ComputedValue::Length(layout.height()));
+        insert_edges(&mut properties, "margin-{}", node.layout_node.get_margin());
+        insert_edges(&mut properties, "padding-{}", node.layout_node.get_padding());
+        insert_edges(&mut properties, "border-{}-width", node.layout_node.get_border());
+
+        let display = if node.is_display_none() { "none" } else { "flex" };
+        let visibility = match self.computed_visibility(id) {
+            Visibility::Visible => "visible",
+            Visibility::Hidden => "hidden",
+        };
+        properties.insert("display".to_string(), ComputedValue::Keyword(display.to_string()));
+        properties.insert("visibility".to_string(), ComputedValue::Keyword(visibility.to_string()));
+
+        ComputedStyle { properties }
+    }
+}
+
+fn insert_edges(properties: &mut BTreeMap<String, ComputedValue>, pattern: &str, edges: ClientEdges) {
+    let sides = [("top", edges.top), ("right", edges.right), ("bottom", edges.bottom), ("left", edges.left)];
+    for &(side, value) in &sides {
+        properties.insert(pattern.replace("{}", side), ComputedValue::Length(value));
+    }
+}
+
+fn resolve_value(name: &str, value: &Value, font_size: f32, declarations: &Declarations) -> ComputedValue {
+    if name.ends_with("color") {
+        let color = match value.as_str().map(str::trim) {
+            // See https://drafts.csswg.org/css-color/#currentcolor-color
+            Some(current) if current.eq_ignore_ascii_case("currentcolor") && name != "color" => {
+                declarations.get("color").and_then(Value::as_str).and_then(parse_color)
+            }
+            Some(color) => parse_color(color),
+            None => None,
+        };
+        return color.map_or_else(|| ComputedValue::Specified(value.clone()), ComputedValue::Color);
+    }
+
+    // Unitless line heights stay numbers, so that they keep scaling with the font size.
+    if name == "line-height" {
+        if let Some(number) = value.as_f64() {
+            return ComputedValue::Number(number as f32);
+        }
+    }
+
+    match *value {
+        Value::Number(ref number) if LENGTH_PROPERTIES.contains(&name) => {
+            ComputedValue::Length(number.as_f64().unwrap_or_default() as f32)
+        }
+        Value::Number(ref number) => ComputedValue::Number(number.as_f64().unwrap_or_default() as f32),
+        Value::String(ref text) => {
+            let text = text.trim();
+            if text.ends_with("em") && !text.ends_with("rem") {
+                let em = text[..text.len() - 2].trim().parse::<f32>().ok();
+                if let Some(em) = em {
+                    return ComputedValue::Length(em * font_size);
+                }
+            }
+            match parse_px(value) {
+                Some(px) if LENGTH_PROPERTIES.contains(&name) || text.ends_with("px") => ComputedValue::Length(px),
+                Some(number) => ComputedValue::Number(number),
+                None if text.chars().all(|c| c.is_alphanumeric() || c == '-') => {
+                    ComputedValue::Keyword(text.to_ascii_lowercase())
+                }
+                None => ComputedValue::Specified(value.clone()),
+            }
+        }
+        _ => ComputedValue::Specified(value.clone()),
+    }
+}
+
+// Hex notations, `rgb()`, `rgba()` and the basic named colors.
+// See https://drafts.csswg.org/css-color/#typedef-color
+pub fn parse_color(source: &str) -> Option<Rgba> {
+    let source = source.trim().to_ascii_lowercase();
+    if source.starts_with('#') {
+        return parse_hex_color(&source[1..]);
+    }
+    if source.starts_with("rgb") && source.ends_with(')') {
+        let start = source.find('(')? + 1;
+        let parts = source[start..source.len() - 1]
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>();
+        if parts.len() < 3 || parts.len() > 4 {
+            return None;
+        }
+        let channel = |v: &str| -> Option<u8> {
+            let value = if v.ends_with('%') {
+                v[..v.len() - 1].parse::<f32>().ok()? * 2.55
+            } else {
+                v.parse::<f32>().ok()?
+            };
+            Some(value.round().max(0.0).min(255.0) as u8)
+        };
+        let alpha = match parts.get(3).cloned() {
+            Some(v) if v.ends_with('%') => v[..v.len() - 1].parse::<f32>().ok()? / 100.0,
+            Some(v) => v.parse::<f32>().ok()?,
+            None => 1.0,
+        };
+        return Some(Rgba {
+            r: channel(parts[0])?,
+            g: channel(parts[1])?,
+            b: channel(parts[2])?,
+            a: alpha.max(0.0).min(1.0),
+        });
+    }
+
+    let (r, g, b, a) = match source.as_str() {
+        "transparent" => (0, 0, 0, 0.0),
+        "black" => (0, 0, 0, 1.0),
+        "white" => (255, 255, 255, 1.0),
+        "red" => (255, 0, 0, 1.0),
+        "green" => (0, 128, 0, 1.0),
+        "lime" => (0, 255, 0, 1.0),
+        "blue" => (0, 0, 255, 1.0),
+        "yellow" => (255, 255, 0, 1.0),
+        "orange" => (255, 165, 0, 1.0),
+        "purple" => (128, 0, 128, 1.0),
+        "gray" | "grey" => (128, 128, 128, 1.0),
+        "silver" => (192, 192, 192, 1.0),
+        _ => return None,
+    };
+    Some(Rgba { r, g, b, a })
+}
+
+fn parse_hex_color(hex: &str) -> Option<Rgba> {
+    let digits = hex.chars().map(|c| c.to_digit(16).map(|v| v as u8)).collect::<Option<Vec<_>>>()?;
+    let channels = match digits.len() {
+        3 | 4 => digits.iter().map(|&v| v * 17).collect::<Vec<_>>(),
+        6 | 8 => digits.chunks(2).map(|v| v[0] * 16 + v[1]).collect::<Vec<_>>(),
+        _ => return None,
+    };
+    Some(Rgba {
+        r: channels[0],
+        g: channels[1],
+        b: channels[2],
+        a: channels.get(3).map_or(1.0, |&v| f32::from(v) / 255.0),
+    })
+}
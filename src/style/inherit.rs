@@ -0,0 +1,141 @@
+use serde_json::Value;
+
+use setup::DOMArenaRefMut;
+use style::{build_style, parse_px, Declarations};
+use traits::{TGenericEvent, TLayoutNode};
+use types::{TextFont, Visibility};
+
+// See https://drafts.csswg.org/css-cascade/#inherited-property
+pub const INHERITED_PROPERTIES: &[&str] = &[
+    "color",
+    "direction",
+    "font-family",
+    "font-size",
+    "line-height",
+    "text-align",
+    "visibility",
+];
+
+pub fn is_inherited(name: &str) -> bool {
+    INHERITED_PROPERTIES.contains(&name)
+}
+
+// Inherited properties the node doesn't declare take the value of the parent, and the defaulting
+// keywords are resolved. Initial values are left out.
+// See https://drafts.csswg.org/css-cascade/#defaulting-keywords
+pub fn inherit_declarations(declarations: &Declarations, parent: Option<&Declarations>) -> Declarations {
+    let mut computed = parent
+        .into_iter()
+        .flat_map(|parent| parent.iter())
+        .filter(|&(name, _)| is_inherited(name))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect::<Declarations>();
+
+    for (name, value) in declarations {
+        let keyword = value.as_str().map(|v| v.trim().to_ascii_lowercase());
+        match keyword.as_ref().map(String::as_str) {
+            Some("inherit") => match parent.and_then(|parent| parent.get(name)) {
+                Some(value) => computed.insert(name.clone(), value.clone()),
+                None => computed.remove(name),
+            },
+            Some("unset") if is_inherited(name) => continue,
+            Some("initial") | Some("unset") => computed.remove(name),
+            _ => computed.insert(name.clone(), value.clone()),
+        };
+    }
+
+    // Relative font sizes are resolved, so that children inherit the computed size.
+    // See https://drafts.csswg.org/css-fonts/#font-size-prop
+    let parent_size = parent
+        .and_then(|parent| parent.get("font-size"))
+        .and_then(parse_px)
+        .unwrap_or(TextFont::default().size);
+    let font_size = declarations
+        .get("font-size")
+        .and_then(Value::as_str)
+        .and_then(|value| relative_font_size(value, parent_size));
+    if let Some(size) = font_size {
+        computed.insert("font-size".to_string(), Value::from(format!("{}px", size)));
+    }
+    computed
+}
+
+fn relative_font_size(value: &str, parent_size: f32) -> Option<f32> {
+    let value = value.trim();
+    if value.ends_with("em") {
+        value[..value.len() - 2].trim().parse::<f32>().ok().map(|v| v * parent_size)
+    } else if value.ends_with('%') {
+        value[..value.len() - 1].trim().parse::<f32>().ok().map(|v| v * parent_size / 100.0)
+    } else {
+        None
+    }
+}
+
+// The font text nodes are measured with, falling back to the one the measurer was installed with.
+pub fn text_font(declarations: &Declarations, fallback: &TextFont) -> TextFont {
+    let size = declarations.get("font-size").and_then(parse_px).unwrap_or(fallback.size);
+    let family = declarations
+        .get("font-family")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| fallback.family.clone());
+    // Unitless line heights are multiples of the font size.
+    // See https://drafts.csswg.org/css2/#propdef-line-height
+    let line_height = match declarations.get("line-height") {
+        Some(&Value::Number(ref value)) => value.as_f64().map(|v| v as f32 * size),
+        Some(&Value::String(ref value)) if value == "normal" => None,
+        Some(value) => parse_px(value),
+        None => fallback.line_height,
+    };
+    TextFont {
+        family,
+        size,
+        line_height,
+    }
+}
+
+impl<'a, T, L> DOMArenaRefMut<'a, T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    // Recomputes the declarations of the node from the ones of its parent, rebuilding its styles.
+    // Returns whether they changed, in which case the children have to inherit again.
+    pub fn inherit_styles(&mut self) -> bool {
+        let parent_declarations = match self.parent_id() {
+            Some(parent_id) => Some(self.get(parent_id).computed_declarations.clone()),
+            None => None,
+        };
+        let mut declarations = self.declarations.clone();
+        declarations.extend(self.animated_declarations.clone());
+        let computed = inherit_declarations(&declarations, parent_declarations.as_ref());
+        if computed == self.computed_declarations {
+            return false;
+        }
+        self.visibility = match computed.get("visibility").and_then(Value::as_str) {
+            Some("hidden") | Some("collapse") => Some(Visibility::Hidden),
+            Some("visible") => Some(Visibility::Visible),
+            // Nodes never styled with a visibility keep the one they were given directly.
+            _ if self.computed_declarations.contains_key("visibility") => None,
+            _ => self.visibility,
+        };
+        self.computed_declarations = computed;
+        self.rebuild_styles();
+        true
+    }
+
+    // Only the properties the node declares itself are merged into its styles when the layout is
+    // applied, the inherited ones are only found in its computed declarations.
+    pub(crate) fn rebuild_styles(&mut self) {
+        let (mut cascaded, mut animated) = (Declarations::new(), Declarations::new());
+        for (name, value) in &self.computed_declarations {
+            if self.animated_declarations.contains_key(name) {
+                animated.insert(name.clone(), value.clone());
+            } else if self.declarations.contains_key(name) {
+                cascaded.insert(name.clone(), value.clone());
+            }
+        }
+        self.cascaded_styles = if cascaded.is_empty() { None } else { Some(build_style(&cascaded)) };
+        self.animated_styles = if animated.is_empty() { None } else { Some(build_style(&animated)) };
+    }
+}
@@ -0,0 +1,104 @@
+use serde_json::Value;
+use std::mem;
+
+use style::{parse_color, parse_px, Rgba};
+
+// Numbers, lengths, colors and the numbers within values of the same shape, like transforms, are interpolated.
+// Anything else flips from one value to the other halfway through.
+// See https://drafts.csswg.org/web-animations/#animating-properties
+pub fn interpolate(from: &Value, to: &Value, progress: f32) -> Value {
+    match (from, to) {
+        (&Value::Number(ref a), &Value::Number(ref b)) => {
+            let (a, b) = (a.as_f64().unwrap_or_default(), b.as_f64().unwrap_or_default());
+            Value::from(a + (b - a) * f64::from(progress))
+        }
+        (&Value::Array(ref a), &Value::Array(ref b)) if a.len() == b.len() => {
+            Value::Array(a.iter().zip(b).map(|(a, b)| interpolate(a, b, progress)).collect())
+        }
+        (&Value::String(ref a), &Value::String(ref b)) => match interpolate_str(a, b, progress) {
+            Some(value) => Value::from(value),
+            None => discrete(from, to, progress),
+        },
+        // Plain numbers are pixels.
+        (&Value::Number(_), &Value::String(_)) | (&Value::String(_), &Value::Number(_)) => {
+            match (parse_px(from), parse_px(to)) {
+                (Some(a), Some(b)) => Value::from(format!("{}px", lerp(a, b, progress))),
+                _ => discrete(from, to, progress),
+            }
+        }
+        _ => discrete(from, to, progress),
+    }
+}
+
+fn discrete(from: &Value, to: &Value, progress: f32) -> Value {
+    if progress < 0.5 {
+        from.clone()
+    } else {
+        to.clone()
+    }
+}
+
+fn lerp(a: f32, b: f32, progress: f32) -> f32 {
+    a + (b - a) * progress
+}
+
+fn interpolate_str(from: &str, to: &str, progress: f32) -> Option<String> {
+    if let (Some(a), Some(b)) = (parse_color(from), parse_color(to)) {
+        let channel = |a: u8, b: u8| lerp(f32::from(a), f32::from(b), progress).round().max(0.0).min(255.0) as u8;
+        let color = Rgba {
+            r: channel(a.r, b.r),
+            g: channel(a.g, b.g),
+            b: channel(a.b, b.b),
+            a: lerp(a.a, b.a, progress).max(0.0).min(1.0),
+        };
+        return Some(format!("rgba({}, {}, {}, {})", color.r, color.g, color.b, color.a));
+    }
+
+    let (from_text, from_numbers) = split_numbers(from);
+    let (to_text, to_numbers) = split_numbers(to);
+    if from_numbers.is_empty() || from_text != to_text {
+        return None;
+    }
+    let mut result = from_text[0].clone();
+    for (index, (&a, &b)) in from_numbers.iter().zip(&to_numbers).enumerate() {
+        result.push_str(&lerp(a, b, progress).to_string());
+        result.push_str(&from_text[index + 1]);
+    }
+    Some(result)
+}
+
+// Splits e.g. `rotate(-45deg) translate(10px, 0)` into the text around the numbers, and the numbers.
+// Digits within identifiers like `translate3d` are text.
+fn split_numbers(source: &str) -> (Vec<String>, Vec<f32>) {
+    let chars = source.chars().collect::<Vec<_>>();
+    let (mut text, mut numbers) = (vec![], vec![]);
+    let mut current = String::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let in_word = index > 0 && (chars[index - 1].is_alphanumeric() || chars[index - 1] == '_');
+        let next_is_digit = chars.get(index + 1).map_or(false, |&v| v.is_ascii_digit() || v == '.');
+        let starts_number = !in_word && (c.is_ascii_digit() || ((c == '-' || c == '.') && next_is_digit));
+        if !starts_number {
+            current.push(c);
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        index += 1;
+        while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == '.') {
+            index += 1;
+        }
+        let number = chars[start..index].iter().collect::<String>();
+        match number.parse::<f32>() {
+            Ok(number) => {
+                text.push(mem::replace(&mut current, String::new()));
+                numbers.push(number);
+            }
+            Err(_) => current.push_str(&number),
+        }
+    }
+    text.push(current);
+    (text, numbers)
+}
@@ -0,0 +1,316 @@
+use std::f32;
+
+use style::{split_outside_parens, StyleSheetError};
+use types::{ColorScheme, TextFont, Viewport};
+
+// Comma separated queries, matching when any of them does. An empty list always matches.
+// See https://drafts.csswg.org/mediaqueries/#media-query-list
+#[derive(Debug, PartialEq, Clone)]
+pub struct MediaQueryList {
+    pub queries: Vec<MediaQuery>,
+}
+
+// Conditions all have to match.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MediaQuery {
+    pub negated: bool,
+    pub media_type: MediaType,
+    pub conditions: Vec<MediaCondition>,
+}
+
+// There's only ever a screen, so `print` never matches.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MediaType {
+    All,
+    Screen,
+    Print,
+}
+
+// Lengths are in CSS pixels, resolutions in dots per pixel.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum MediaCondition {
+    Range(RangeFeature, Comparison, f32),
+    Orientation(Orientation),
+    PrefersColorScheme(ColorScheme),
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RangeFeature {
+    Width,
+    Height,
+    Resolution,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+// See https://drafts.csswg.org/mediaqueries/#orientation
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+impl MediaQueryList {
+    // Parses e.g. `screen and (min-width: 600px), (orientation: portrait)`.
+    pub fn parse(source: &str) -> Result<Self, StyleSheetError> {
+        let error = || StyleSheetError::InvalidMediaQuery(source.trim().to_string());
+        let source = source.to_ascii_lowercase();
+        let queries = split_outside_parens(&source, |c| c == ',')
+            .into_iter()
+            .map(MediaQuery::parse)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(error)?;
+        Ok(MediaQueryList { queries })
+    }
+
+    pub fn matches(&self, viewport: &Viewport) -> bool {
+        self.queries.is_empty() || self.queries.iter().any(|query| query.matches(viewport))
+    }
+}
+
+impl MediaQuery {
+    // See https://drafts.csswg.org/mediaqueries/#mq-syntax
+    pub fn parse(source: &str) -> Option<Self> {
+        let mut tokens = split_outside_parens(source, char::is_whitespace).into_iter().peekable();
+        let negated = tokens.peek() == Some(&"not");
+        match tokens.peek().cloned() {
+            Some("not") | Some("only") => {
+                tokens.next();
+            }
+            _ => {}
+        }
+        let media_type = match tokens.peek().cloned() {
+            Some(token) if !token.starts_with('(') => {
+                tokens.next();
+                Some(MediaType::parse(token)?)
+            }
+            _ => None,
+        };
+
+        let mut conditions = vec![];
+        let mut expects_and = media_type.is_some();
+        while let Some(token) = tokens.next() {
+            let token = if expects_and {
+                if token != "and" {
+                    return None;
+                }
+                tokens.next()?
+            } else {
+                token
+            };
+            conditions.extend(MediaCondition::parse(token)?);
+            expects_and = true;
+        }
+
+        Some(MediaQuery {
+            negated,
+            media_type: media_type.unwrap_or(MediaType::All),
+            conditions,
+        })
+    }
+
+    pub fn matches(&self, viewport: &Viewport) -> bool {
+        let matches = self.media_type != MediaType::Print
+            && self.conditions.iter().all(|condition| condition.matches(viewport));
+        matches != self.negated
+    }
+}
+
+impl MediaType {
+    pub fn parse(source: &str) -> Option<Self> {
+        match source {
+            "all" => Some(MediaType::All),
+            "screen" => Some(MediaType::Screen),
+            "print" => Some(MediaType::Print),
+            _ => None,
+        }
+    }
+}
+
+impl MediaCondition {
+    // Parses a parenthesized feature, like `(min-width: 600px)` or `(400px <= width < 800px)`.
+    pub fn parse(source: &str) -> Option<Vec<Self>> {
+        if !source.starts_with('(') || !source.ends_with(')') {
+            return None;
+        }
+        let inner = source[1..source.len() - 1].trim();
+        match inner.find(':') {
+            Some(index) => {
+                let (name, value) = (inner[..index].trim(), inner[index + 1..].trim());
+                MediaCondition::parse_plain(name, value).map(|condition| vec![condition])
+            }
+            None => MediaCondition::parse_range(inner),
+        }
+    }
+
+    fn parse_plain(name: &str, value: &str) -> Option<Self> {
+        match name {
+            "orientation" => {
+                let orientation = match value {
+                    "portrait" => Orientation::Portrait,
+                    "landscape" => Orientation::Landscape,
+                    _ => return None,
+                };
+                return Some(MediaCondition::Orientation(orientation));
+            }
+            "prefers-color-scheme" => {
+                let color_scheme = match value {
+                    "light" => ColorScheme::Light,
+                    "dark" => ColorScheme::Dark,
+                    _ => return None,
+                };
+                return Some(MediaCondition::PrefersColorScheme(color_scheme));
+            }
+            _ => {}
+        }
+
+        let (comparison, name) = if name.starts_with("min-") {
+            (Comparison::GreaterOrEqual, &name[4..])
+        } else if name.starts_with("max-") {
+            (Comparison::LessOrEqual, &name[4..])
+        } else {
+            (Comparison::Equal, name)
+        };
+        let feature = RangeFeature::parse(name)?;
+        Some(MediaCondition::Range(feature, comparison, feature.parse_value(value)?))
+    }
+
+    // See https://drafts.csswg.org/mediaqueries/#mq-range-context
+    fn parse_range(source: &str) -> Option<Vec<Self>> {
+        let (mut operands, mut comparisons) = (vec![], vec![]);
+        let mut rest = source;
+        while let Some((index, comparison, length)) = find_comparison(rest) {
+            operands.push(rest[..index].trim());
+            comparisons.push(comparison);
+            rest = &rest[index + length..];
+        }
+        operands.push(rest.trim());
+
+        match (operands.len(), RangeFeature::parse(operands[0])) {
+            // `width >= 600px`
+            (2, Some(feature)) => {
+                let value = feature.parse_value(operands[1])?;
+                Some(vec![MediaCondition::Range(feature, comparisons[0], value)])
+            }
+            // `600px <= width`
+            (2, None) => {
+                let feature = RangeFeature::parse(operands[1])?;
+                let value = feature.parse_value(operands[0])?;
+                Some(vec![MediaCondition::Range(feature, comparisons[0].flip(), value)])
+            }
+            // `400px <= width < 800px`
+            (3, None) => {
+                let feature = RangeFeature::parse(operands[1])?;
+                let (min, max) = (feature.parse_value(operands[0])?, feature.parse_value(operands[2])?);
+                Some(vec![
+                    MediaCondition::Range(feature, comparisons[0].flip(), min),
+                    MediaCondition::Range(feature, comparisons[1], max),
+                ])
+            }
+            _ => None,
+        }
+    }
+
+    pub fn matches(&self, viewport: &Viewport) -> bool {
+        match *self {
+            MediaCondition::Range(feature, comparison, value) => {
+                let actual = match feature {
+                    RangeFeature::Width => viewport.size.width,
+                    RangeFeature::Height => viewport.size.height,
+                    RangeFeature::Resolution => viewport.device_pixel_ratio,
+                };
+                comparison.compare(actual, value)
+            }
+            MediaCondition::Orientation(orientation) => {
+                let actual = if viewport.size.height >= viewport.size.width {
+                    Orientation::Portrait
+                } else {
+                    Orientation::Landscape
+                };
+                actual == orientation
+            }
+            MediaCondition::PrefersColorScheme(color_scheme) => viewport.color_scheme == color_scheme,
+        }
+    }
+}
+
+impl RangeFeature {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "width" => Some(RangeFeature::Width),
+            "height" => Some(RangeFeature::Height),
+            "resolution" => Some(RangeFeature::Resolution),
+            _ => None,
+        }
+    }
+
+    // Relative lengths are relative to the initial font size.
+    // See https://drafts.csswg.org/mediaqueries/#units
+    fn parse_value(self, source: &str) -> Option<f32> {
+        let (number, unit) = split_unit(source);
+        let number = number.parse::<f32>().ok()?;
+        let font_size = TextFont::default().size;
+        let value = match (self, unit) {
+            (RangeFeature::Width, "px") | (RangeFeature::Height, "px") => number,
+            (RangeFeature::Width, "") | (RangeFeature::Height, "") if number.abs() < f32::EPSILON => number,
+            (RangeFeature::Width, "em") | (RangeFeature::Height, "em") => number * font_size,
+            (RangeFeature::Width, "rem") | (RangeFeature::Height, "rem") => number * font_size,
+            (RangeFeature::Resolution, "dppx") | (RangeFeature::Resolution, "x") => number,
+            (RangeFeature::Resolution, "dpi") => number / 96.0,
+            (RangeFeature::Resolution, "dpcm") => number * 2.54 / 96.0,
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+impl Comparison {
+    // For values on the left of the feature.
+    fn flip(self) -> Self {
+        match self {
+            Comparison::Less => Comparison::Greater,
+            Comparison::LessOrEqual => Comparison::GreaterOrEqual,
+            Comparison::Equal => Comparison::Equal,
+            Comparison::GreaterOrEqual => Comparison::LessOrEqual,
+            Comparison::Greater => Comparison::Less,
+        }
+    }
+
+    pub fn compare(self, actual: f32, value: f32) -> bool {
+        match self {
+            Comparison::Less => actual < value,
+            Comparison::LessOrEqual => actual <= value,
+            Comparison::Equal => (actual - value).abs() < f32::EPSILON,
+            Comparison::GreaterOrEqual => actual >= value,
+            Comparison::Greater => actual > value,
+        }
+    }
+}
+
+// The index, comparison and length of the first comparison operator.
+fn find_comparison(source: &str) -> Option<(usize, Comparison, usize)> {
+    let index = source.find(|c| c == '<' || c == '>' || c == '=')?;
+    let or_equal = source[index + 1..].starts_with('=');
+    let comparison = match (&source[index..index + 1], or_equal) {
+        ("<", true) => Comparison::LessOrEqual,
+        ("<", false) => Comparison::Less,
+        (">", true) => Comparison::GreaterOrEqual,
+        (">", false) => Comparison::Greater,
+        _ => return Some((index, Comparison::Equal, 1)),
+    };
+    Some((index, comparison, if or_equal { 2 } else { 1 }))
+}
+
+fn split_unit(source: &str) -> (&str, &str) {
+    let index = source
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or_else(|| source.len());
+    (&source[..index], source[index..].trim())
+}
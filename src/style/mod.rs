@@ -0,0 +1,134 @@
+use jss::types::{Case, Style, StyleBuilder};
+use serde_json::{Map, Value};
+
+mod animation;
+mod cascade;
+mod computed;
+mod inherit;
+mod interpolate;
+mod media;
+mod selector;
+mod sheet;
+mod timeline;
+
+pub use self::animation::*;
+pub use self::computed::*;
+pub use self::inherit::*;
+pub use self::interpolate::*;
+pub use self::media::*;
+pub use self::selector::*;
+pub use self::sheet::*;
+pub use self::timeline::*;
+
+// Property names to JSON values, in the form jss parses them and in source order.
+pub type Declarations = Map<String, Value>;
+
+// Properties handled by the DOM itself rather than by jss.
+const DOM_PROPERTIES: &[&str] = &["visibility", "transition", "animation"];
+
+// Shorthands setting the four sides of a box, in the order their values are given.
+const BOX_SHORTHANDS: &[&str] = &["margin", "padding"];
+const BOX_SIDES: [&str; 4] = ["top", "right", "bottom", "left"];
+
+// Shorthands are expanded into their longhands, so that they cascade property by property
+// and a later longhand overrides the side of an earlier shorthand.
+// See https://drafts.csswg.org/css-cascade/#shorthand
+pub(crate) fn expand_shorthands(declarations: Declarations) -> Declarations {
+    let mut expanded = Declarations::new();
+    for (name, value) in declarations {
+        match box_sides(&name, &value) {
+            Some(sides) => {
+                for (side, value) in BOX_SIDES.iter().zip(sides) {
+                    expanded.insert(format!("{}-{}", name, side), value);
+                }
+            }
+            None => {
+                expanded.insert(name, value);
+            }
+        }
+    }
+    expanded
+}
+
+// Missing values are taken from the opposite side, like with `margin: 10px 20px`.
+// See https://drafts.csswg.org/css-box/#margin-shorthand
+fn box_sides(name: &str, value: &Value) -> Option<Vec<Value>> {
+    if !BOX_SHORTHANDS.contains(&name) {
+        return None;
+    }
+    let values = match *value {
+        Value::String(ref source) => source.split_whitespace().map(Value::from).collect::<Vec<_>>(),
+        ref value => vec![value.clone()],
+    };
+    let indices = match values.len() {
+        1 => [0, 0, 0, 0],
+        2 => [0, 1, 0, 1],
+        3 => [0, 1, 2, 1],
+        4 => [0, 1, 2, 3],
+        _ => return None,
+    };
+    Some(indices.iter().map(|&index| values[index].clone()).collect())
+}
+
+// Declarations jss can't parse are dropped one by one, like invalid declarations in CSS.
+// See https://drafts.csswg.org/css-syntax/#consume-declaration
+pub(crate) fn build_style(declarations: &Declarations) -> Style {
+    let declarations = declarations
+        .iter()
+        .filter(|&(name, _)| !DOM_PROPERTIES.contains(&name.as_str()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect::<Declarations>();
+    if let Some(style) = parse_style(&declarations) {
+        return style;
+    }
+
+    let valid = declarations
+        .into_iter()
+        .filter(|&(ref name, ref value)| {
+            let mut single = Declarations::new();
+            single.insert(name.clone(), value.clone());
+            parse_style(&single).is_some()
+        })
+        .collect::<Declarations>();
+    parse_style(&valid).unwrap_or_default()
+}
+
+fn parse_style(declarations: &Declarations) -> Option<Style> {
+    let source = Value::Object(declarations.clone()).to_string();
+    StyleBuilder::default().case(Case::Ignore).parse_from_str(&source).ok()
+}
+
+// Plain numbers are taken as pixels.
+pub(crate) fn parse_px(value: &Value) -> Option<f32> {
+    match *value {
+        Value::Number(ref value) => value.as_f64().map(|v| v as f32),
+        Value::String(ref value) => {
+            let value = value.trim();
+            let value = if value.ends_with("px") { &value[..value.len() - 2] } else { value };
+            value.trim().parse().ok()
+        }
+        _ => None,
+    }
+}
+
+// Splits at separators which aren't nested in parentheses, like the commas of `cubic-bezier()`.
+pub(crate) fn split_outside_parens<F>(source: &str, is_separator: F) -> Vec<&str>
+where
+    F: Fn(char) -> bool,
+{
+    let mut parts = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (index, c) in source.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if depth == 0 && is_separator(c) => {
+                parts.push(&source[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&source[start..]);
+    parts.into_iter().map(str::trim).filter(|v| !v.is_empty()).collect()
+}
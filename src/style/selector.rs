@@ -0,0 +1,345 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use node::{DOMAttributeValue, DOMNode, DOMNodeId};
+use style::StyleSheetError;
+use traits::{TGenericEvent, TLayoutNode};
+use tree::DOMTree;
+use types::ElementState;
+
+// Ids, then classes, attributes and pseudo-classes, then types.
+// See https://drafts.csswg.org/selectors/#specificity-rules
+pub type Specificity = (u32, u32, u32);
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SimpleSelector {
+    Universal,
+    Type(String),
+    Id(String),
+    Class(String),
+    Attribute(String, Option<String>),
+    PseudoClass(PseudoClass),
+}
+
+// See https://drafts.csswg.org/selectors/#useraction-pseudos
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum PseudoClass {
+    Hover,
+    Active,
+    Focus,
+    FocusWithin,
+    Checked,
+    Disabled,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CompoundSelector {
+    pub simple: Vec<SimpleSelector>,
+}
+
+// Compounds are stored left to right, each one after the first along with how it relates to the previous one.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Selector {
+    pub first: CompoundSelector,
+    pub rest: Vec<(Combinator, CompoundSelector)>,
+}
+
+impl SimpleSelector {
+    pub fn specificity(&self) -> Specificity {
+        match *self {
+            SimpleSelector::Universal => (0, 0, 0),
+            SimpleSelector::Type(_) => (0, 0, 1),
+            SimpleSelector::Id(_) => (1, 0, 0),
+            SimpleSelector::Class(_) | SimpleSelector::Attribute(..) | SimpleSelector::PseudoClass(_) => (0, 1, 0),
+        }
+    }
+
+    pub fn matches<T, L>(&self, node: &DOMNode<T, L>) -> bool
+    where
+        T: TGenericEvent,
+        L: TLayoutNode,
+    {
+        match *self {
+            SimpleSelector::Universal => true,
+            SimpleSelector::Type(ref name) => node.has_local_name(name),
+            SimpleSelector::Id(ref id) => node.element_id() == Some(id.as_str()),
+            SimpleSelector::Class(ref class) => node.class_list().contains(&class.as_str()),
+            SimpleSelector::Attribute(ref name, ref expected) => match (node.attribute(name), expected) {
+                (Some(&DOMAttributeValue::Boolean(false)), _) | (None, _) => false,
+                (Some(_), &None) => true,
+                (Some(value), &Some(ref expected)) => attribute_value_eq(value, expected),
+            },
+            SimpleSelector::PseudoClass(pseudo_class) => pseudo_class.matches(node.element_state()),
+        }
+    }
+}
+
+impl PseudoClass {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "hover" => Some(PseudoClass::Hover),
+            "active" => Some(PseudoClass::Active),
+            "focus" => Some(PseudoClass::Focus),
+            "focus-within" => Some(PseudoClass::FocusWithin),
+            "checked" => Some(PseudoClass::Checked),
+            "disabled" => Some(PseudoClass::Disabled),
+            _ => None,
+        }
+    }
+
+    pub fn matches(self, state: ElementState) -> bool {
+        match self {
+            PseudoClass::Hover => state.hovered,
+            PseudoClass::Active => state.active,
+            PseudoClass::Focus => state.focused,
+            PseudoClass::FocusWithin => state.focus_within,
+            PseudoClass::Checked => state.checked,
+            PseudoClass::Disabled => state.disabled,
+        }
+    }
+}
+
+impl CompoundSelector {
+    pub fn specificity(&self) -> Specificity {
+        self.simple.iter().map(SimpleSelector::specificity).fold((0, 0, 0), add)
+    }
+
+    // Only elements are matched, never text nodes.
+    pub fn matches<T, L>(&self, node: &DOMNode<T, L>) -> bool
+    where
+        T: TGenericEvent,
+        L: TLayoutNode,
+    {
+        node.is_normal() && self.simple.iter().all(|simple| simple.matches(node))
+    }
+}
+
+impl Selector {
+    pub fn pseudo_classes<'a>(&'a self) -> impl Iterator<Item = PseudoClass> + 'a {
+        let rest = self.rest.iter().map(|&(_, ref compound)| compound);
+        ::std::iter::once(&self.first)
+            .chain(rest)
+            .flat_map(|compound| compound.simple.iter())
+            .filter_map(|simple| match *simple {
+                SimpleSelector::PseudoClass(pseudo_class) => Some(pseudo_class),
+                _ => None,
+            })
+    }
+
+    // Parses a comma separated selector list, commas within attribute selectors don't separate selectors.
+    pub fn parse_list(source: &str) -> Result<Vec<Selector>, StyleSheetError> {
+        split_list(source).into_iter().map(Selector::parse).collect()
+    }
+
+    pub fn parse(source: &str) -> Result<Selector, StyleSheetError> {
+        let error = || StyleSheetError::InvalidSelector(source.trim().to_string());
+        let mut chars = source.trim().chars().peekable();
+        let first = parse_compound(&mut chars).ok_or_else(error)?;
+        let mut rest = vec![];
+
+        loop {
+            let mut combinator = None;
+            while let Some(&c) = chars.peek() {
+                match c {
+                    '>' if combinator != Some(Combinator::Child) => combinator = Some(Combinator::Child),
+                    c if c.is_whitespace() => combinator = combinator.or(Some(Combinator::Descendant)),
+                    _ => break,
+                }
+                chars.next();
+            }
+            match (combinator, chars.peek().is_some()) {
+                (None, false) => break,
+                (Some(combinator), true) => {
+                    let compound = parse_compound(&mut chars).ok_or_else(error)?;
+                    rest.push((combinator, compound));
+                }
+                _ => return Err(error()),
+            }
+        }
+
+        Ok(Selector { first, rest })
+    }
+
+    pub fn specificity(&self) -> Specificity {
+        self.rest
+            .iter()
+            .map(|&(_, ref compound)| compound.specificity())
+            .fold(self.first.specificity(), add)
+    }
+
+    pub fn matches<T, L>(&self, tree: &DOMTree<T, L>, id: DOMNodeId<T, L>) -> bool
+    where
+        T: TGenericEvent,
+        L: TLayoutNode,
+    {
+        self.matches_from(self.rest.len(), tree, id)
+    }
+
+    // Matches right to left, backtracking over ancestors for descendant combinators.
+    fn matches_from<T, L>(&self, index: usize, tree: &DOMTree<T, L>, id: DOMNodeId<T, L>) -> bool
+    where
+        T: TGenericEvent,
+        L: TLayoutNode,
+    {
+        let (combinator, compound) = match index {
+            0 => return self.first.matches(tree.get(id).into_value()),
+            _ => {
+                let &(combinator, ref compound) = &self.rest[index - 1];
+                (combinator, compound)
+            }
+        };
+        if !compound.matches(tree.get(id).into_value()) {
+            return false;
+        }
+
+        let mut next_id = tree.get(id).parent_id();
+        while let Some(ancestor_id) = next_id {
+            if self.matches_from(index - 1, tree, ancestor_id) {
+                return true;
+            }
+            if combinator == Combinator::Child {
+                return false;
+            }
+            next_id = tree.get(ancestor_id).parent_id();
+        }
+        false
+    }
+}
+
+// Splits on the commas outside of brackets and quotes, unbalanced ones are left for `Selector::parse` to reject.
+fn split_list(source: &str) -> Vec<&str> {
+    let mut selectors = vec![];
+    let (mut start, mut brackets, mut quote) = (0, 0, None);
+    for (index, c) in source.char_indices() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '[') => brackets += 1,
+            (None, ']') => brackets -= 1,
+            (None, ',') if brackets == 0 => {
+                selectors.push(&source[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    selectors.push(&source[start..]);
+    selectors
+}
+
+fn add(a: Specificity, b: Specificity) -> Specificity {
+    (a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+
+fn attribute_value_eq<T, L>(value: &DOMAttributeValue<T, L>, expected: &str) -> bool
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    match *value {
+        DOMAttributeValue::Str(ref value) => value.as_ref() == expected,
+        DOMAttributeValue::Number(value) => value.to_string() == expected,
+        DOMAttributeValue::Boolean(value) => value.to_string() == expected,
+        DOMAttributeValue::Char(value) => value.to_string() == expected,
+        _ => false,
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+fn parse_name(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut name = String::new();
+    while let Some(&c) = chars.peek() {
+        if !is_name_char(c) {
+            break;
+        }
+        name.push(c);
+        chars.next();
+    }
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+fn parse_attribute_value(chars: &mut Peekable<Chars>) -> Option<String> {
+    match chars.peek().cloned() {
+        Some(quote) if quote == '"' || quote == '\'' => {
+            chars.next();
+            let mut value = String::new();
+            for c in chars.by_ref() {
+                if c == quote {
+                    return Some(value);
+                }
+                value.push(c);
+            }
+            // Unterminated strings are invalid.
+            None
+        }
+        _ => parse_name(chars),
+    }
+}
+
+fn parse_compound(chars: &mut Peekable<Chars>) -> Option<CompoundSelector> {
+    let mut simple = vec![];
+    match chars.peek().cloned() {
+        Some('*') => {
+            chars.next();
+            simple.push(SimpleSelector::Universal);
+        }
+        Some(c) if is_name_char(c) => simple.push(SimpleSelector::Type(parse_name(chars)?.to_ascii_lowercase())),
+        _ => {}
+    }
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            '#' => {
+                chars.next();
+                simple.push(SimpleSelector::Id(parse_name(chars)?));
+            }
+            '.' => {
+                chars.next();
+                simple.push(SimpleSelector::Class(parse_name(chars)?));
+            }
+            '[' => {
+                chars.next();
+                let name = parse_name(chars)?.to_ascii_lowercase();
+                let value = match chars.next() {
+                    Some(']') => None,
+                    Some('=') => {
+                        let value = parse_attribute_value(chars)?;
+                        if chars.next() != Some(']') {
+                            return None;
+                        }
+                        Some(value)
+                    }
+                    _ => return None,
+                };
+                simple.push(SimpleSelector::Attribute(name, value));
+            }
+            ':' => {
+                chars.next();
+                let pseudo_class = PseudoClass::from_name(&parse_name(chars)?)?;
+                simple.push(SimpleSelector::PseudoClass(pseudo_class));
+            }
+            c if c.is_whitespace() || c == '>' => break,
+            _ => return None,
+        }
+    }
+
+    if simple.is_empty() {
+        None
+    } else {
+        Some(CompoundSelector { simple })
+    }
+}
@@ -0,0 +1,188 @@
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde_json;
+use std::fmt;
+
+use style::{expand_shorthands, Declarations, Keyframes, MediaQueryList, PseudoClass, Selector, Specificity};
+use types::Viewport;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StyleSheetError {
+    InvalidJson(String),
+    InvalidSelector(String),
+    InvalidKeyframes(String),
+    InvalidMediaQuery(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleRule {
+    pub selectors: Vec<Selector>,
+    pub declarations: Declarations,
+    // The media queries of the enclosing `@media` rules, which all have to match.
+    pub media: Vec<MediaQueryList>,
+}
+
+// Rules keep their source order, which breaks ties between selectors of the same specificity.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyleSheet {
+    rules: Vec<StyleRule>,
+    keyframes: Vec<Keyframes>,
+}
+
+// A JSON object mapping selectors to declarations, read in source order.
+struct RuleSource(Vec<(String, RuleBody)>);
+
+enum RuleBody {
+    Declarations(Declarations),
+    // The rules of an `@media` rule.
+    Nested(RuleSource),
+}
+
+impl StyleRule {
+    // The specificity of the most specific selector matching, if any.
+    pub fn match_specificity<F>(&self, matches: F) -> Option<Specificity>
+    where
+        F: Fn(&Selector) -> bool,
+    {
+        self.selectors
+            .iter()
+            .filter(|selector| matches(selector))
+            .map(Selector::specificity)
+            .max()
+    }
+
+    pub fn matches_media(&self, viewport: &Viewport) -> bool {
+        self.media.iter().all(|media| media.matches(viewport))
+    }
+
+    pub fn uses_pseudo_class(&self, pseudo_class: PseudoClass) -> bool {
+        self.selectors
+            .iter()
+            .any(|selector| selector.pseudo_classes().any(|v| v == pseudo_class))
+    }
+}
+
+impl StyleSheet {
+    pub fn new() -> Self {
+        StyleSheet::default()
+    }
+
+    // Parses e.g. `{ ".item > span": { "width": "10px" } }`. Keyframes are given as
+    // `{ "@keyframes name": { "from": { ... }, "50%": { ... }, "to": { ... } } }`,
+    // and conditional rules as `{ "@media (min-width: 600px)": { ".item": { ... } } }`.
+    pub fn parse_from_str(source: &str) -> Result<Self, StyleSheetError> {
+        let RuleSource(rules) =
+            serde_json::from_str(source).map_err(|error| StyleSheetError::InvalidJson(error.to_string()))?;
+
+        let mut sheet = StyleSheet::new();
+        sheet.add_rule_source(rules, &[])?;
+        Ok(sheet)
+    }
+
+    fn add_rule_source(
+        &mut self,
+        rules: Vec<(String, RuleBody)>,
+        media: &[MediaQueryList],
+    ) -> Result<(), StyleSheetError> {
+        for (prelude, body) in rules {
+            match body {
+                RuleBody::Nested(RuleSource(nested)) => {
+                    let mut media = media.to_vec();
+                    media.push(MediaQueryList::parse(&prelude["@media".len()..])?);
+                    self.add_rule_source(nested, &media)?;
+                }
+                RuleBody::Declarations(declarations) => {
+                    if prelude.starts_with("@keyframes ") {
+                        let name = prelude["@keyframes ".len()..].trim();
+                        self.add_keyframes(Keyframes::parse(name, &declarations)?);
+                    } else {
+                        self.push_rule(&prelude, declarations, media.to_vec())?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_rule(&mut self, selectors: &str, declarations: Declarations) -> Result<(), StyleSheetError> {
+        self.push_rule(selectors, declarations, vec![])
+    }
+
+    // Adds a rule which only applies while the media query list matches the viewport.
+    pub fn add_media_rule(
+        &mut self,
+        media: &str,
+        selectors: &str,
+        declarations: Declarations,
+    ) -> Result<(), StyleSheetError> {
+        let media = MediaQueryList::parse(media)?;
+        self.push_rule(selectors, declarations, vec![media])
+    }
+
+    fn push_rule(
+        &mut self,
+        selectors: &str,
+        declarations: Declarations,
+        media: Vec<MediaQueryList>,
+    ) -> Result<(), StyleSheetError> {
+        let selectors = Selector::parse_list(selectors)?;
+        self.rules.push(StyleRule {
+            selectors,
+            declarations: expand_shorthands(declarations),
+            media,
+        });
+        Ok(())
+    }
+
+    pub fn rules(&self) -> &[StyleRule] {
+        &self.rules
+    }
+
+    pub fn add_keyframes(&mut self, keyframes: Keyframes) {
+        self.keyframes.push(keyframes);
+    }
+
+    // The last keyframes defined with the name win.
+    // See https://drafts.csswg.org/css-animations/#keyframes
+    pub fn keyframes(&self, name: &str) -> Option<&Keyframes> {
+        self.keyframes.iter().rev().find(|keyframes| keyframes.name == name)
+    }
+
+    pub fn uses_pseudo_class(&self, pseudo_class: PseudoClass) -> bool {
+        self.rules.iter().any(|rule| rule.uses_pseudo_class(pseudo_class))
+    }
+}
+
+impl<'de> Deserialize<'de> for RuleSource {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RuleSourceVisitor;
+
+        impl<'de> Visitor<'de> for RuleSourceVisitor {
+            type Value = RuleSource;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an object of selectors to declarations")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut rules = vec![];
+                while let Some(prelude) = map.next_key::<String>()? {
+                    let body = if prelude.starts_with("@media") {
+                        RuleBody::Nested(map.next_value()?)
+                    } else {
+                        RuleBody::Declarations(map.next_value()?)
+                    };
+                    rules.push((prelude, body));
+                }
+                Ok(RuleSource(rules))
+            }
+        }
+
+        deserializer.deserialize_map(RuleSourceVisitor)
+    }
+}
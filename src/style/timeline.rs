@@ -0,0 +1,348 @@
+use serde_json::Value;
+use std::mem;
+
+use node::DOMNodeId;
+use style::{interpolate, Animation, AnimationDirection, Declarations, FillMode, Keyframes, TimingFunction, Transition};
+use traits::{TGenericEvent, TLayoutNode};
+use tree::DOMTree;
+use types::EventType;
+
+// Transitions and animations in flight, advanced by `DOMTree::tick`. Times are in milliseconds.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Timeline<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    now: f64,
+    transitions: Vec<RunningTransition<T, L>>,
+    // Ended animations are kept, so that they don't start over when other animations of the node change.
+    animations: Vec<RunningAnimation<T, L>>,
+    finished: Vec<FinishedAnimation<T, L>>,
+}
+
+#[derive(Debug, PartialEq)]
+struct RunningTransition<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    id: DOMNodeId<T, L>,
+    property: String,
+    from: Value,
+    to: Value,
+    // After the delay.
+    start: f64,
+    duration: f64,
+    timing_function: TimingFunction,
+}
+
+#[derive(Debug, PartialEq)]
+struct RunningAnimation<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    id: DOMNodeId<T, L>,
+    animation: Animation,
+    // Before the delay.
+    start: f64,
+    ended: bool,
+}
+
+// A transition or animation which ended, for which an event is fired with the next broadcast.
+#[derive(Debug, PartialEq)]
+pub(crate) struct FinishedAnimation<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub id: DOMNodeId<T, L>,
+    pub event_type: EventType,
+    // The animation name, or the transitioned property.
+    pub name: String,
+    // In seconds.
+    pub elapsed_time: f32,
+}
+
+impl<T, L> Default for Timeline<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    fn default() -> Self {
+        Timeline {
+            now: 0.0,
+            transitions: vec![],
+            animations: vec![],
+            finished: vec![],
+        }
+    }
+}
+
+impl<T, L> RunningTransition<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    // The start value is kept during the delay.
+    fn value(&self, now: f64) -> Value {
+        let progress = if self.duration > 0.0 {
+            ((now - self.start) / self.duration).max(0.0).min(1.0)
+        } else {
+            1.0
+        };
+        interpolate(&self.from, &self.to, self.timing_function.apply(progress as f32))
+    }
+}
+
+impl<T, L> RunningAnimation<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    fn active_duration(&self) -> f64 {
+        if self.animation.duration > 0.0 {
+            self.animation.duration * self.animation.iteration_count
+        } else {
+            0.0
+        }
+    }
+
+    fn end(&self) -> f64 {
+        self.start + self.animation.delay + self.active_duration()
+    }
+
+    // The directed progress within the current iteration, or None while the animation has no effect.
+    // See https://drafts.csswg.org/web-animations/#calculating-the-directed-progress
+    fn progress(&self, now: f64) -> Option<f32> {
+        let animation = &self.animation;
+        let local = now - self.start - animation.delay;
+        let active = self.active_duration();
+        let (iteration, progress) = if local < 0.0 {
+            match animation.fill_mode {
+                FillMode::Backwards | FillMode::Both => (0.0, 0.0),
+                FillMode::None | FillMode::Forwards => return None,
+            }
+        } else if local >= active {
+            match animation.fill_mode {
+                FillMode::Forwards | FillMode::Both => {
+                    let overall = if animation.duration > 0.0 {
+                        active / animation.duration
+                    } else {
+                        animation.iteration_count
+                    };
+                    let iteration = overall.floor();
+                    // Animations ending on an iteration boundary stay at the end of the last iteration.
+                    if overall - iteration <= 0.0 && overall > 0.0 {
+                        (iteration - 1.0, 1.0)
+                    } else {
+                        (iteration, overall - iteration)
+                    }
+                }
+                FillMode::None | FillMode::Backwards => return None,
+            }
+        } else {
+            let iteration = (local / animation.duration).floor();
+            (iteration, local / animation.duration - iteration)
+        };
+
+        let odd = iteration % 2.0 >= 1.0;
+        let reversed = match animation.direction {
+            AnimationDirection::Normal => false,
+            AnimationDirection::Reverse => true,
+            AnimationDirection::Alternate => odd,
+            AnimationDirection::AlternateReverse => !odd,
+        };
+        let progress = if reversed { 1.0 - progress } else { progress };
+        Some(progress as f32)
+    }
+}
+
+impl<T, L> DOMTree<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    // Advances transitions and animations to `now`, in milliseconds of any monotonic clock.
+    // Nodes whose animated values changed are marked dirty and returned, end events are fired with the next broadcast.
+    pub fn tick(&mut self, now: u64) -> Vec<DOMNodeId<T, L>> {
+        let now = now as f64;
+        self.timeline.now = now;
+
+        let mut transitions = mem::replace(&mut self.timeline.transitions, vec![]);
+        let mut animations = mem::replace(&mut self.timeline.animations, vec![]);
+        transitions.retain(|transition| self.contains(transition.id));
+        animations.retain(|animation| self.contains(animation.id));
+
+        let mut ids = vec![];
+        {
+            let running = transitions
+                .iter()
+                .map(|transition| transition.id)
+                .chain(animations.iter().filter(|animation| !animation.ended).map(|animation| animation.id));
+            for id in running {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+
+        let mut finished = vec![];
+        transitions.retain(|transition| {
+            if now < transition.start + transition.duration {
+                return true;
+            }
+            finished.push(FinishedAnimation {
+                id: transition.id,
+                event_type: EventType::TransitionEnd,
+                name: transition.property.clone(),
+                elapsed_time: (transition.duration / 1000.0) as f32,
+            });
+            false
+        });
+        for animation in &mut animations {
+            if !animation.ended && now >= animation.end() {
+                animation.ended = true;
+                finished.push(FinishedAnimation {
+                    id: animation.id,
+                    event_type: EventType::AnimationEnd,
+                    name: animation.animation.name.clone(),
+                    elapsed_time: (animation.active_duration() / 1000.0) as f32,
+                });
+            }
+        }
+        self.timeline.transitions = transitions;
+        self.timeline.animations = animations;
+        self.timeline.finished.extend(finished);
+
+        let mut changed = vec![];
+        for id in ids {
+            let animated = {
+                let declarations = &self.get(id).into_value().declarations;
+                self.animated_declarations(id, declarations)
+            };
+            let mut node = self.get_mut(id);
+            if node.animated_declarations != animated {
+                node.animated_declarations = animated;
+                node.mark_dirty();
+                changed.push(id);
+            }
+        }
+        changed
+    }
+
+    // Whether ticking may still change anything, so that frames only have to be requested while animating.
+    pub fn is_animating(&self) -> bool {
+        !self.timeline.transitions.is_empty() || self.timeline.animations.iter().any(|animation| !animation.ended)
+    }
+
+    // Starts the transitions and animations caused by new cascaded declarations of a node, before they're stored.
+    // They start at the time of the last tick.
+    // See https://drafts.csswg.org/css-transitions/#starting
+    pub(crate) fn update_animations(&mut self, id: DOMNodeId<T, L>, declarations: &Declarations) {
+        let now = self.timeline.now;
+        let (previous, current) = {
+            let node = self.get(id).into_value();
+            (node.declarations.clone(), node.computed_declarations.clone())
+        };
+
+        // Animations which keep their name keep running.
+        if previous.get("animation") != declarations.get("animation") {
+            let definitions = declarations.get("animation").map(Animation::parse_list).unwrap_or_default();
+            let running = mem::replace(&mut self.timeline.animations, vec![]);
+            let (mut own, mut animations): (Vec<_>, Vec<_>) = running.into_iter().partition(|v| v.id == id);
+            for animation in definitions {
+                let (start, ended) = match own.iter().position(|v| v.animation.name == animation.name) {
+                    Some(index) => {
+                        let kept = own.remove(index);
+                        (kept.start, kept.ended)
+                    }
+                    None => (now, false),
+                };
+                animations.push(RunningAnimation {
+                    id,
+                    animation,
+                    start,
+                    ended,
+                });
+            }
+            self.timeline.animations = animations;
+        }
+
+        // Transitions towards values which are no longer the cascaded ones are cancelled.
+        let definitions = declarations.get("transition").map(Transition::parse_list).unwrap_or_default();
+        let mut transitions = mem::replace(&mut self.timeline.transitions, vec![]);
+        transitions.retain(|v| v.id != id || declarations.get(&v.property) == Some(&v.to));
+        for (property, value) in declarations {
+            if property == "transition" || property == "animation" || previous.get(property) == Some(value) {
+                continue;
+            }
+            if transitions.iter().any(|v| v.id == id && v.property == *property) {
+                continue;
+            }
+            // The last definition for the property wins.
+            let definition = definitions
+                .iter()
+                .rev()
+                .find(|v| v.property == *property || v.property == "all");
+            let definition = match definition {
+                Some(definition) if definition.duration > 0.0 && definition.duration + definition.delay > 0.0 => {
+                    definition
+                }
+                _ => continue,
+            };
+            // Transitions start from the current value, which may be the one of an interrupted transition.
+            let from = match current.get(property) {
+                Some(from) if from != value => from.clone(),
+                _ => continue,
+            };
+            transitions.push(RunningTransition {
+                id,
+                property: property.clone(),
+                from,
+                to: value.clone(),
+                start: now + definition.delay,
+                duration: definition.duration,
+                timing_function: definition.timing_function,
+            });
+        }
+        self.timeline.transitions = transitions;
+
+        let animated = self.animated_declarations(id, declarations);
+        self.get_mut(id).animated_declarations = animated;
+    }
+
+    pub(crate) fn take_finished_animations(&mut self) -> Vec<FinishedAnimation<T, L>> {
+        mem::replace(&mut self.timeline.finished, vec![])
+    }
+
+    // Animations override the cascaded declarations, and transitions override animations.
+    // See https://drafts.csswg.org/css-cascade/#cascade-origin
+    fn animated_declarations(&self, id: DOMNodeId<T, L>, declarations: &Declarations) -> Declarations {
+        let now = self.timeline.now;
+        let mut animated = Declarations::new();
+        for running in self.timeline.animations.iter().filter(|v| v.id == id) {
+            let (keyframes, progress) = match (self.find_keyframes(&running.animation.name), running.progress(now)) {
+                (Some(keyframes), Some(progress)) => (keyframes, progress),
+                _ => continue,
+            };
+            let timing_function = running.animation.timing_function;
+            for property in keyframes.properties() {
+                let value = keyframes.value_at(property, progress, declarations.get(property), timing_function);
+                if let Some(value) = value {
+                    animated.insert(property.to_string(), value);
+                }
+            }
+        }
+        for transition in self.timeline.transitions.iter().filter(|v| v.id == id) {
+            animated.insert(transition.property.clone(), transition.value(now));
+        }
+        animated
+    }
+
+    // Later stylesheets win.
+    fn find_keyframes(&self, name: &str) -> Option<&Keyframes> {
+        self.stylesheets.iter().rev().filter_map(|sheet| sheet.keyframes(name)).next()
+    }
+}
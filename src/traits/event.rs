@@ -12,7 +12,7 @@ specific language governing permissions and limitations under the License.
 use std::fmt::Debug;
 
 use traits::{TDOMNode, TDOMTree};
-use types::{Closure, DOMNodeRawId, EventType, VirtualEventType};
+use types::{Atom, Closure, DOMNodeRawId, EventType, VirtualEventType};
 
 use serde::{Deserialize as Des, Serialize as Ser};
 
@@ -34,6 +34,10 @@ pub trait TEvent: Debug + PartialEq + Ser + for<'a> Des<'a> {
     fn target(&self) -> DOMNodeRawId;
 
     fn ty(&self) -> EventType;
+
+    fn prevent_default(&self);
+
+    fn default_prevented(&self) -> bool;
 }
 
 #[fundamental]
@@ -51,12 +55,13 @@ pub trait TUIEvent: TEvent {
 // See https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent
 pub trait TKeyboardEvent: TUIEvent {
     type KeyCode;
+    type KeyModifier;
 
     fn code(&self) -> Self::KeyCode;
 
     fn key(&self) -> &'static str;
 
-    fn get_modifier_state(&self) -> bool;
+    fn get_modifier_state(&self, Self::KeyModifier) -> bool;
 
     fn repeat(&self) -> bool;
 }
@@ -79,18 +84,86 @@ pub trait TMouseEvent: TUIEvent {
     fn page_x(&self) -> u32;
 
     fn page_y(&self) -> u32;
+
+    fn related_target(&self) -> Option<DOMNodeRawId>;
+}
+
+#[fundamental]
+// See https://developer.mozilla.org/en-US/docs/Web/API/PointerEvent
+pub trait TPointerEvent: TMouseEvent {
+    type PointerType;
+
+    fn pointer_id(&self) -> u32;
+
+    fn pointer_type(&self) -> Self::PointerType;
+
+    fn pressure(&self) -> f32;
+
+    fn width(&self) -> f32;
+
+    fn height(&self) -> f32;
+
+    fn is_primary(&self) -> bool;
+}
+
+#[fundamental]
+// See https://developer.mozilla.org/en-US/docs/Web/API/WheelEvent
+pub trait TWheelEvent: TMouseEvent {
+    type DeltaMode;
+
+    fn delta_x(&self) -> f64;
+
+    fn delta_y(&self) -> f64;
+
+    fn delta_z(&self) -> f64;
+
+    fn delta_mode(&self) -> Self::DeltaMode;
+}
+
+#[fundamental]
+// See https://developer.mozilla.org/en-US/docs/Web/API/DragEvent
+pub trait TDragEvent: TMouseEvent {
+    type DataTransfer;
+
+    fn data_transfer(&self) -> Option<&Self::DataTransfer>;
+}
+
+#[fundamental]
+// See https://developer.mozilla.org/en-US/docs/Web/API/CustomEvent
+pub trait TCustomEvent: TEvent {
+    type Detail;
+
+    fn custom_type(&self) -> Option<Atom>;
+
+    fn detail(&self) -> Option<&Self::Detail>;
 }
 
 #[fundamental]
-pub trait TGenericEvent: TKeyboardEvent + TMouseEvent {}
+// See https://developer.mozilla.org/en-US/docs/Web/API/AnimationEvent
+pub trait TAnimationEvent: TEvent {
+    // The animation name, or the transitioned property for transition events.
+    fn animation_name(&self) -> Option<&str>;
+
+    // In seconds.
+    fn elapsed_time(&self) -> f32;
+}
+
+#[fundamental]
+pub trait TGenericEvent:
+    TKeyboardEvent + TMouseEvent + TPointerEvent + TWheelEvent + TDragEvent + TCustomEvent + TAnimationEvent
+{
+}
 
 #[fundamental]
 pub trait TEventManager: Debug + PartialEq + Default {
     type Target: TDOMNode;
+    type Tree: TDOMTree<Node = Self::Target>;
     type KeyCode;
     type MouseButton;
     type KeyEventData;
     type MouseEventData;
+    type PointerEventData;
+    type WheelEventData;
 
     fn add_event_listener<F>(&mut self, <Self::Target as TDOMNode>::Id, EventType, F)
     where
@@ -104,12 +177,13 @@ pub trait TEventManager: Debug + PartialEq + Default {
 
     fn receive_mouse_event(&mut self, VirtualEventType, Self::MouseEventData);
 
-    fn broadcast_events<A>(&mut self, &A)
-    where
-        A: TDOMTree<Node = Self::Target>;
+    fn receive_pointer_event(&mut self, VirtualEventType, Self::PointerEventData);
+
+    fn receive_wheel_event(&mut self, Self::WheelEventData);
+
+    fn broadcast_events(&mut self, &mut Self::Tree);
 
-    fn intercept_events<A, F>(&mut self, &A, F)
+    fn intercept_events<F>(&mut self, &mut Self::Tree, F)
     where
-        A: TDOMTree<Node = Self::Target>,
         F: FnMut(<Self::Target as TDOMNode>::Event);
 }
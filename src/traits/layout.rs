@@ -11,7 +11,7 @@ specific language governing permissions and limitations under the License.
 
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
-use yoga::Layout;
+use types::{ClientEdges, FlexStyle, Layout, TextFont, TextMetrics};
 
 #[fundamental]
 pub trait TClientRect: Debug + PartialEq + Copy + Serialize + for<'a> Deserialize<'a> {
@@ -36,12 +36,20 @@ pub trait TClientPosition: Debug + PartialEq + Copy + Serialize + for<'a> Deseri
 pub trait TClientSize: Debug + PartialEq + Copy + Serialize + for<'a> Deserialize<'a> {}
 
 #[fundamental]
-pub trait TLayoutNode: Debug + PartialEq {
-    // type TextMeasureMetadata;
+pub trait TLayoutNode: Debug + PartialEq + Default + 'static {
+    type TextMeasureMetadata;
     type ReflowDirection;
 
     fn is_tainted(&self) -> bool;
 
+    fn has_tainted_descendants(&self) -> bool;
+
+    fn taint(&mut self);
+
+    fn taint_descendants(&mut self);
+
+    fn untaint(&mut self);
+
     fn insert_child(&mut self, &mut Self, u32);
 
     fn append_child(&mut self, &mut Self);
@@ -54,5 +62,21 @@ pub trait TLayoutNode: Debug + PartialEq {
 
     fn get_layout(&self) -> Layout;
 
+    fn get_padding(&self) -> ClientEdges;
+
+    fn get_border(&self) -> ClientEdges;
+
+    fn get_margin(&self) -> ClientEdges;
+
     fn reset(&mut self);
+
+    fn apply_styles(&mut self, &[FlexStyle]);
+
+    // Leaf nodes with text measure metadata are sized by measuring their text.
+    fn set_text_measure(&mut self, Option<Self::TextMeasureMetadata>);
+}
+
+// Wraps at whitespace when given a maximum width, lines which can't be wrapped may overflow it.
+pub trait TTextMeasurer: Debug {
+    fn measure(&self, text: &str, font: &TextFont, max_width: Option<f32>) -> TextMetrics;
 }
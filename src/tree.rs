@@ -11,119 +11,514 @@ specific language governing permissions and limitations under the License.
 
 use rsx_tree::types::Tree;
 use std::ops::{Deref, DerefMut};
-use traits::{TDOMTree, TGenericEvent};
+use std::rc::Rc;
+use traits::{TClientRect, TDOMNode, TDOMTree, TGenericEvent, TLayoutNode, TTextMeasurer};
+use types::{ClientRect, ClientSize, Direction, Layout, TextFont, Viewport, Visibility};
 
+use layout::{LayoutNode, TextMeasure, TextMeasurement};
 use node::{DOMNode, DOMNodeId, DOMNodeIdPair};
 use setup::{DOMArenaRef, DOMArenaRefMut, DOMArenaRefMutPair};
+use style::{text_font, StyleSheet, Timeline};
 
 #[derive(Debug, PartialEq)]
-pub struct DOMTree<T>
+pub struct DOMTree<T, L = LayoutNode>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    raw: Tree<DOMNode<T>>,
+    raw: Tree<DOMNode<T, L>>,
+    active_element: Option<DOMNodeId<T, L>>,
+    text_measurement: Option<TextMeasurement>,
+    pub(crate) viewport: Viewport,
+    resized: bool,
+    pub(crate) stylesheets: Vec<StyleSheet>,
+    pub(crate) timeline: Timeline<T, L>,
 }
 
-impl<T> Default for DOMTree<T>
+impl<T, L> Default for DOMTree<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     fn default() -> Self {
         DOMTree {
             raw: Tree::new(DOMNode::default()),
+            active_element: None,
+            text_measurement: None,
+            viewport: Viewport::default(),
+            resized: false,
+            stylesheets: vec![],
+            timeline: Timeline::default(),
         }
     }
 }
 
-impl<T> Deref for DOMTree<T>
+impl<T, L> Deref for DOMTree<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    type Target = DOMNode<T>;
+    type Target = DOMNode<T, L>;
 
     fn deref(&self) -> &Self::Target {
         self.root().into_value()
     }
 }
 
-impl<T> DerefMut for DOMTree<T>
+impl<T, L> DerefMut for DOMTree<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.root_mut().into_value()
     }
 }
 
-impl<T> DOMTree<T>
+impl<T, L> DOMTree<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    pub(crate) fn into_inner(self) -> Tree<DOMNode<T>> {
+    pub(crate) fn into_inner(self) -> Tree<DOMNode<T, L>> {
         self.raw
     }
 
-    pub fn root(&self) -> DOMArenaRef<T> {
+    pub fn root(&self) -> DOMArenaRef<T, L> {
         let id = self.raw.root();
         DOMArenaRef::from(self.raw.get(id))
     }
 
-    pub fn root_mut(&mut self) -> DOMArenaRefMut<T> {
+    pub fn root_mut(&mut self) -> DOMArenaRefMut<T, L> {
         let id = self.raw.root();
         DOMArenaRefMut::from(self.raw.get_mut(id))
     }
 
-    pub fn document(&self) -> DOMArenaRef<T> {
+    pub fn document(&self) -> DOMArenaRef<T, L> {
         let id = self.root().first_child_id().unwrap();
         DOMArenaRef::from(self.raw.get(id))
     }
 
-    pub fn document_mut(&mut self) -> DOMArenaRefMut<T> {
+    pub fn document_mut(&mut self) -> DOMArenaRefMut<T, L> {
         let id = self.root().first_child_id().unwrap();
         DOMArenaRefMut::from(self.raw.get_mut(id))
     }
 
-    pub fn alloc(&mut self, node: DOMNode<T>) -> DOMNodeId<T> {
+    pub fn contains(&self, id: DOMNodeId<T, L>) -> bool {
+        self.raw.get(id).try_value().is_some()
+    }
+
+    // Detached nodes stay in the arena, but they aren't part of the tree anymore.
+    pub fn is_connected(&self, id: DOMNodeId<T, L>) -> bool {
+        if !self.contains(id) {
+            return false;
+        }
+        let root_id = self.root().id();
+        let mut next_id = Some(id);
+        while let Some(node_id) = next_id {
+            if node_id == root_id {
+                return true;
+            }
+            next_id = self.get(node_id).parent_id();
+        }
+        false
+    }
+
+    pub fn alloc(&mut self, node: DOMNode<T, L>) -> DOMNodeId<T, L> {
         self.raw.alloc(node)
     }
 
-    pub fn get(&self, id: DOMNodeId<T>) -> DOMArenaRef<T> {
+    pub fn get(&self, id: DOMNodeId<T, L>) -> DOMArenaRef<T, L> {
         DOMArenaRef::from(self.raw.get(id))
     }
 
-    pub fn get_mut(&mut self, id: DOMNodeId<T>) -> DOMArenaRefMut<T> {
+    pub fn get_mut(&mut self, id: DOMNodeId<T, L>) -> DOMArenaRefMut<T, L> {
         DOMArenaRefMut::from(self.raw.get_mut(id))
     }
 
-    pub fn get_mut_pair(&mut self, ids: DOMNodeIdPair<T>) -> DOMArenaRefMutPair<T> {
+    pub fn get_mut_pair(&mut self, ids: DOMNodeIdPair<T, L>) -> DOMArenaRefMutPair<T, L> {
         DOMArenaRefMutPair::from(self.raw.get_mut_pair(ids))
     }
+
+    // Scroll containers whose offset changed since the last call, in document order.
+    pub fn take_scrolled_ids(&mut self) -> Vec<DOMNodeId<T, L>> {
+        let mut ids = vec![];
+        let mut stack = vec![self.root().id()];
+        while let Some(id) = stack.pop() {
+            let (changed, changed_descendants) = {
+                let mut node = self.get_mut(id);
+                (node.scroll.take_changed(), node.scroll.take_changed_descendants())
+            };
+            if changed {
+                ids.push(id);
+            }
+            if changed_descendants {
+                let children = self.get(id).children_ids_iter().collect::<Vec<_>>();
+                stack.extend(children.into_iter().rev());
+            }
+        }
+        ids
+    }
+
+    // Returns whether the viewport was resized since the last call, so that a resize event can be fired.
+    pub(crate) fn take_resized(&mut self) -> bool {
+        ::std::mem::replace(&mut self.resized, false)
+    }
 }
 
-impl<T> TDOMTree for DOMTree<T>
+impl<T, L> TDOMTree for DOMTree<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
-    type Node = DOMNode<T>;
+    type Node = DOMNode<T, L>;
 
-    fn get_node(&self, id: DOMNodeId<T>) -> &Self::Node {
+    fn get_node(&self, id: DOMNodeId<T, L>) -> &Self::Node {
         self.get(id).into_value()
     }
 
-    fn get_node_mut(&mut self, id: DOMNodeId<T>) -> &mut Self::Node {
+    fn get_node_mut(&mut self, id: DOMNodeId<T, L>) -> &mut Self::Node {
         self.get_mut(id).into_value()
     }
 
-    fn get_node_mut_pair(&mut self, ids: DOMNodeIdPair<T>) -> (&mut Self::Node, &mut Self::Node) {
+    fn get_node_mut_pair(&mut self, ids: DOMNodeIdPair<T, L>) -> (&mut Self::Node, &mut Self::Node) {
         self.get_mut_pair(ids).into_values()
     }
 }
 
-impl<T> DOMTree<T>
+// Backends without text measurement can take `TextMeasure` as metadata and ignore it.
+impl<T, L> DOMTree<T, L>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
+    L::TextMeasureMetadata: From<TextMeasure>,
 {
     pub fn generate_layout_tree(&mut self) {
+        let ids = self.root().descendants_ids_iter().collect::<Vec<_>>();
+        let ids = self.cascade_styles(ids);
+        self.apply_text_measures(ids);
         self.root_mut().build_layout();
     }
+
+    // Re-cascades and re-applies the styles of dirty nodes and reflows, the backend skips the subtrees which didn't change.
+    // Returns the nodes whose computed layout changed.
+    pub fn relayout(&mut self, width: u32, height: u32, direction: L::ReflowDirection) -> Vec<DOMNodeId<T, L>> {
+        let previous = self
+            .layout_candidate_ids()
+            .into_iter()
+            .map(|id| (id, self.get(id).layout_node.get_layout()))
+            .collect::<Vec<_>>();
+
+        let ids = self.tainted_ids();
+        let ids = self.cascade_styles(ids);
+        self.apply_text_measures(ids);
+        self.root_mut().calculate_dirty_styles();
+        self.root_mut().reflow_subtree(width, height, direction);
+
+        previous
+            .into_iter()
+            .filter(|&(id, ref layout)| self.get(id).layout_node.get_layout() != *layout)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    // Text nodes only take up space once a measurer is installed.
+    pub fn set_text_measurer<M: 'static>(&mut self, measurer: M, font: TextFont)
+    where
+        M: TTextMeasurer,
+    {
+        self.text_measurement = Some(TextMeasurement {
+            measurer: Rc::new(measurer),
+            font,
+        });
+        self.mark_text_dirty();
+    }
+
+    pub fn remove_text_measurer(&mut self) {
+        self.text_measurement = None;
+        self.mark_text_dirty();
+    }
+
+    fn mark_text_dirty(&mut self) {
+        let ids = self
+            .root()
+            .descendants_ids_iter()
+            .filter(|&id| self.get(id).is_text())
+            .collect::<Vec<_>>();
+        for id in ids {
+            self.get_mut(id).mark_dirty();
+        }
+    }
+
+    // Walks down the paths to dirty nodes only.
+    fn tainted_ids(&self) -> Vec<DOMNodeId<T, L>> {
+        let mut ids = vec![];
+        let mut stack = vec![self.root().id()];
+        while let Some(id) = stack.pop() {
+            let node = self.get(id);
+            if node.layout_node.is_tainted() {
+                ids.push(id);
+            }
+            if node.layout_node.has_tainted_descendants() {
+                stack.extend(node.children_ids_iter());
+            }
+        }
+        ids
+    }
+
+    // Layouts are relative to the parent, so only dirty subtrees and the children of their ancestors can move.
+    // New viewport sizes go through `layout_viewport`, which dirties the root.
+    fn layout_candidate_ids(&self) -> Vec<DOMNodeId<T, L>> {
+        let mut ids = vec![];
+        let mut stack = vec![self.root().id()];
+        while let Some(id) = stack.pop() {
+            let node = self.get(id);
+            if node.layout_node.is_tainted() {
+                ids.extend(node.descendants_ids_iter());
+                continue;
+            }
+            ids.push(id);
+            if node.layout_node.has_tainted_descendants() {
+                stack.extend(node.children_ids_iter());
+            }
+        }
+        ids
+    }
+
+    fn apply_text_measures(&mut self, ids: Vec<DOMNodeId<T, L>>) {
+        for id in ids {
+            // Fonts are inherited from the ancestors of the text node.
+            let measure = {
+                let node = self.get(id).into_value();
+                match (node.data.text(), &self.text_measurement) {
+                    (Some(text), &Some(ref measurement)) => Some(From::from(TextMeasure {
+                        measurement: TextMeasurement {
+                            measurer: measurement.measurer.clone(),
+                            font: text_font(&node.computed_declarations, &measurement.font),
+                        },
+                        text: text.as_ref().to_string(),
+                    })),
+                    (Some(_), &None) => None,
+                    (None, _) => continue,
+                }
+            };
+            self.get_mut(id).layout_node.set_text_measure(measure);
+        }
+    }
+}
+
+impl<T, L> DOMTree<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+    L::TextMeasureMetadata: From<TextMeasure>,
+    L::ReflowDirection: From<Direction>,
+{
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    // Relayouts within the new viewport, a resize event is fired at the document with the next broadcast.
+    // Only nodes matched by rules whose media queries flipped are restyled.
+    // Returns the nodes whose computed layout changed.
+    pub fn set_viewport(&mut self, viewport: Viewport) -> Vec<DOMNodeId<T, L>> {
+        if viewport.size != self.viewport.size {
+            self.resized = true;
+        }
+        let previous = self.viewport;
+        self.viewport = viewport;
+        self.restyle_media_rules(&previous);
+        self.layout_viewport()
+    }
+
+    pub fn resize(&mut self, width: f32, height: f32) -> Vec<DOMNodeId<T, L>> {
+        let viewport = Viewport {
+            size: ClientSize { width, height },
+            ..self.viewport
+        };
+        self.set_viewport(viewport)
+    }
+
+    // Relayouts with the size and direction of the viewport, which styles of the root are resolved against.
+    // Every node resolves viewport units against the same size, so they're all restyled when it changes.
+    pub fn layout_viewport(&mut self) -> Vec<DOMNodeId<T, L>> {
+        let Viewport { size, direction, .. } = self.viewport;
+        let viewport = Layout::new(0.0, 0.0, 0.0, 0.0, size.width, size.height);
+        {
+            let mut root = self.root_mut();
+            if root.containing_block != Some(viewport) {
+                root.containing_block = Some(viewport);
+                root.mark_dirty();
+            }
+        }
+        let ids = self.root().descendants_ids_iter().collect::<Vec<_>>();
+        for id in ids {
+            let mut node = self.get_mut(id);
+            if node.viewport != viewport {
+                node.viewport = viewport;
+                node.mark_dirty();
+            }
+        }
+        let (width, height) = (size.width.round() as u32, size.height.round() as u32);
+        self.relayout(width, height, direction.into())
+    }
+}
+
+impl<T, L> DOMTree<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub fn client_rect(&self, id: DOMNodeId<T, L>) -> ClientRect {
+        let mut rect = ClientRect::default();
+        let mut next_id = Some(id);
+        while let Some(node_id) = next_id {
+            let node = self.get(node_id);
+            let layout = node.layout_node.get_layout();
+            if node_id == id {
+                rect.size.width = layout.width();
+                rect.size.height = layout.height();
+            } else {
+                let offset = node.scroll.offset();
+                rect = rect.translate((-offset.left, -offset.top));
+            }
+            rect = rect.translate((layout.left(), layout.top()));
+            next_id = node.parent_id();
+        }
+        rect
+    }
+
+    // Nodes within a `display: none` subtree aren't rendered.
+    pub fn is_rendered(&self, id: DOMNodeId<T, L>) -> bool {
+        let mut next_id = Some(id);
+        while let Some(node_id) = next_id {
+            let node = self.get(node_id);
+            if node.is_display_none() {
+                return false;
+            }
+            next_id = node.parent_id();
+        }
+        true
+    }
+
+    // Visibility is inherited, but descendants can be made visible again.
+    // See https://drafts.csswg.org/css-display/#visibility
+    pub fn computed_visibility(&self, id: DOMNodeId<T, L>) -> Visibility {
+        let mut next_id = Some(id);
+        while let Some(node_id) = next_id {
+            let node = self.get(node_id);
+            if let Some(visibility) = node.visibility {
+                return visibility;
+            }
+            next_id = node.parent_id();
+        }
+        Visibility::default()
+    }
+
+    pub fn is_visible(&self, id: DOMNodeId<T, L>) -> bool {
+        self.is_rendered(id) && self.computed_visibility(id) == Visibility::Visible
+    }
+
+    pub fn hit_test(&self, point: (u32, u32)) -> Option<DOMNodeId<T, L>> {
+        let root_id = self.root().id();
+        self.hit_test_node(root_id, (0.0, 0.0), Visibility::default(), point)
+    }
+
+    // Invisible nodes are skipped but their visible descendants can still be hit.
+    fn hit_test_node(
+        &self,
+        id: DOMNodeId<T, L>,
+        origin: (f32, f32),
+        visibility: Visibility,
+        point: (u32, u32),
+    ) -> Option<DOMNodeId<T, L>> {
+        let node = self.get(id);
+        if node.is_display_none() {
+            return None;
+        }
+        let visibility = node.visibility.unwrap_or(visibility);
+        let layout = node.layout_node.get_layout();
+        let rect = ClientRect::new(origin.0, origin.1, layout.width(), layout.height())
+            .translate((layout.left(), layout.top()));
+
+        // Scroll containers clip their children, and move them by the scroll offset.
+        let offset = node.scroll.offset();
+        let children_origin = (rect.left() - offset.left, rect.top() - offset.top);
+        let mut next_child_id = if node.is_scroll_container() && !rect.contains_point(point) {
+            None
+        } else {
+            // Later siblings are painted on top, so they win the hit test.
+            node.last_child_id()
+        };
+        while let Some(child_id) = next_child_id {
+            if let Some(hit_id) = self.hit_test_node(child_id, children_origin, visibility, point) {
+                return Some(hit_id);
+            }
+            next_child_id = self.get(child_id).prev_sibling_id();
+        }
+
+        if node.is_normal() && visibility == Visibility::Visible && rect.contains_point(point) {
+            Some(id)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, L> DOMTree<T, L>
+where
+    T: TGenericEvent,
+    L: TLayoutNode,
+{
+    pub fn active_element(&self) -> Option<DOMNodeId<T, L>> {
+        self.active_element
+    }
+
+    // Moves focus without firing any events, returning the previously focused node.
+    // Nodes which can't be focused leave the focus where it is.
+    // See https://html.spec.whatwg.org/multipage/interaction.html#focusing-steps
+    pub fn focus(&mut self, id: Option<DOMNodeId<T, L>>) -> Option<DOMNodeId<T, L>> {
+        match id {
+            Some(id) if !self.get(id).is_focusable() || !self.is_visible(id) => self.active_element,
+            id => ::std::mem::replace(&mut self.active_element, id),
+        }
+    }
+
+    pub fn blur(&mut self) -> Option<DOMNodeId<T, L>> {
+        self.active_element.take()
+    }
+
+    // Nodes which aren't rendered or visible can't be focused.
+    // See https://html.spec.whatwg.org/multipage/interaction.html#sequential-focus-navigation-order
+    pub fn focus_order(&self) -> Vec<DOMNodeId<T, L>> {
+        let mut candidates: Vec<(i32, usize, DOMNodeId<T, L>)> = self
+            .root()
+            .descendants_ids_iter()
+            .filter(|&id| self.get(id).is_focusable() && self.is_visible(id))
+            .filter_map(|id| self.get(id).tab_index().map(|index| (index, id)))
+            .filter(|&(index, _)| index >= 0)
+            .enumerate()
+            .map(|(order, (index, id))| (index, order, id))
+            .collect();
+
+        // Positive indices come first in ascending order, then zero in document order.
+        candidates.sort_by_key(|&(index, order, _)| (index == 0, index, order));
+        candidates.into_iter().map(|(_, _, id)| id).collect()
+    }
+
+    pub fn next_focusable(&self, from: Option<DOMNodeId<T, L>>, backwards: bool) -> Option<DOMNodeId<T, L>> {
+        let order = self.focus_order();
+        if order.is_empty() {
+            return None;
+        }
+
+        let count = order.len();
+        let index = match from.and_then(|id| order.iter().position(|&v| v == id)) {
+            Some(index) if backwards => (index + count - 1) % count,
+            Some(index) => (index + 1) % count,
+            None if backwards => count - 1,
+            None => 0,
+        };
+        Some(order[index])
+    }
 }
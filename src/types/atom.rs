@@ -0,0 +1,94 @@
+use hashbrown::HashSet;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::RefCell;
+use std::fmt;
+
+// Interned names are never freed, so at most this many distinct names are ever interned.
+const MAX_ATOMS: usize = 4096;
+
+thread_local! {
+    static ATOMS: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::default());
+}
+
+// Interned string, used for names which are only known at runtime.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct Atom(&'static str);
+
+impl Atom {
+    // Panics once too many distinct names were interned, use `try_new` for untrusted input.
+    pub fn new(value: &str) -> Self {
+        Atom::try_new(value).expect("Too many distinct names were interned")
+    }
+
+    // Returns None once too many distinct names were interned.
+    pub fn try_new(value: &str) -> Option<Self> {
+        Atom::intern(value, |value| Box::leak(value.to_string().into_boxed_str()))
+    }
+
+    fn intern<F>(value: &str, leak: F) -> Option<Self>
+    where
+        F: FnOnce(&str) -> &'static str,
+    {
+        ATOMS.with(|atoms| {
+            let mut atoms = atoms.borrow_mut();
+            if let Some(&interned) = atoms.get(value) {
+                return Some(Atom(interned));
+            }
+            if atoms.len() >= MAX_ATOMS {
+                return None;
+            }
+            let interned = leak(value);
+            atoms.insert(interned);
+            Some(Atom(interned))
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl From<&'static str> for Atom {
+    // Static names are interned as they are, without copying them.
+    fn from(value: &'static str) -> Self {
+        Atom::intern(value, |_| value).expect("Too many distinct names were interned")
+    }
+}
+
+impl From<String> for Atom {
+    fn from(value: String) -> Self {
+        Atom::new(&value)
+    }
+}
+
+impl AsRef<str> for Atom {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.write_str(self.0)
+    }
+}
+
+impl Serialize for Atom {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Atom {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Atom::try_new(&value).ok_or_else(|| D::Error::custom(format!("too many distinct names to intern {:?}", value)))
+    }
+}
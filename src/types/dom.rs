@@ -10,10 +10,12 @@ specific language governing permissions and limitations under the License.
 */
 
 use self_tokenize_trait::ToCustomTokens;
+use std::str::FromStr;
 
 use types::{
     VIRTUAL_AUX_CLICK, VIRTUAL_CLICK, VIRTUAL_CONTEXT_MENU, VIRTUAL_DBL_CLICK, VIRTUAL_KEY_DOWN,
     VIRTUAL_KEY_PRESS, VIRTUAL_KEY_UP, VIRTUAL_MOUSE_DOWN, VIRTUAL_MOUSE_MOVE, VIRTUAL_MOUSE_UP,
+    VIRTUAL_POINTER_CANCEL, VIRTUAL_POINTER_DOWN, VIRTUAL_POINTER_MOVE, VIRTUAL_POINTER_UP,
     VIRTUAL_SELECT, VIRTUAL_WHEEL,
 };
 
@@ -62,10 +64,103 @@ pub enum EventType {
     MouseLeave = 22,
     MouseOver = 23,
     MouseOut = 24,
+
+    // Scroll events
+    Scroll = 25,
+
+    // Viewport events
+    Resize = 26,
+
+    // Form events
+    Input = 61,
+    Change = 62,
+    Submit = 63,
+    Reset = 64,
+
+    // Drag and drop events
+    DragStart = 71,
+    Drag = 72,
+    DragEnter = 73,
+    DragOver = 74,
+    DragLeave = 75,
+    Drop = 76,
+    DragEnd = 77,
+
+    // Animation events
+    TransitionEnd = 81,
+    AnimationEnd = 82,
+
+    // Focus events
+    Focus = 31,
+    Blur = 32,
+    FocusIn = 33,
+    FocusOut = 34,
+
+    // Pointer events
+    PointerDown = VIRTUAL_POINTER_DOWN,
+    PointerMove = VIRTUAL_POINTER_MOVE,
+    PointerUp = VIRTUAL_POINTER_UP,
+    PointerCancel = VIRTUAL_POINTER_CANCEL,
+
+    // Synthetic pointer events
+    PointerOver = 45,
+    PointerOut = 46,
+    PointerEnter = 47,
+    PointerLeave = 48,
+    GotPointerCapture = 49,
+    LostPointerCapture = 50,
+
+    // Touch events
+    TouchStart = 51,
+    TouchMove = 52,
+    TouchEnd = 53,
+    TouchCancel = 54,
+
+    // Application defined events, the name is carried by the event itself
+    Custom = 100,
+}
+
+impl EventType {
+    pub fn bubbles(self) -> bool {
+        match self {
+            EventType::MouseEnter | EventType::MouseLeave => false,
+            EventType::PointerEnter | EventType::PointerLeave => false,
+            EventType::Focus | EventType::Blur => false,
+            EventType::Scroll | EventType::Resize => false,
+            _ => true,
+        }
+    }
+
+    // See https://dom.spec.whatwg.org/#dom-event-cancelable
+    pub fn cancelable(self) -> bool {
+        match self {
+            EventType::MouseEnter | EventType::MouseLeave => false,
+            EventType::PointerEnter | EventType::PointerLeave => false,
+            EventType::GotPointerCapture | EventType::LostPointerCapture => false,
+            EventType::Focus | EventType::Blur | EventType::FocusIn | EventType::FocusOut => false,
+            EventType::Scroll | EventType::Resize | EventType::Input | EventType::Change => false,
+            EventType::DragLeave | EventType::DragEnd => false,
+            EventType::TransitionEnd | EventType::AnimationEnd => false,
+            _ => true,
+        }
+    }
 }
 
 // See https://www.w3.org/TR/html51/dom.html#sec-global-attributes
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash, Primitive, SelfTokenize)]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Copy,
+    Clone,
+    Hash,
+    Serialize,
+    Deserialize,
+    Primitive,
+    SelfTokenize,
+)]
 #[repr(u8)]
 pub enum KnownAttributeName {
     // HTML global attributes
@@ -89,7 +184,20 @@ pub enum KnownAttributeName {
 
 // See https://developer.mozilla.org/en-US/docs/Web/HTML/Element
 // See https://facebook.github.io/react-native/docs/components-and-apis.html#basic-components
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash, Primitive, SelfTokenize)]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Copy,
+    Clone,
+    Hash,
+    Serialize,
+    Deserialize,
+    Primitive,
+    SelfTokenize,
+)]
 #[repr(u16)]
 pub enum KnownElementName {
     // RSX
@@ -192,3 +300,139 @@ pub enum KnownElementName {
     FlatList = 1010,
     SectionList = 1011,
 }
+
+// Names as written in markup and selectors, HTML elements by their tag and React Native components
+// in kebab case.
+macro_rules! known_names {
+    ($known:ident { $($variant:ident => $name:tt,)* }) => {
+        impl $known {
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($known::$variant => $name,)*
+                }
+            }
+        }
+
+        impl FromStr for $known {
+            type Err = ();
+
+            fn from_str(name: &str) -> Result<Self, ()> {
+                match name {
+                    $($name => Ok($known::$variant),)*
+                    _ => Err(()),
+                }
+            }
+        }
+    };
+}
+
+known_names!(KnownAttributeName {
+    Accesskey => "accesskey",
+    Class => "class",
+    CntEditable => "contenteditable",
+    Contextmenu => "contextmenu",
+    Dir => "dir",
+    Draggable => "draggable",
+    Dropzone => "dropzone",
+    Hidden => "hidden",
+    Id => "id",
+    Lang => "lang",
+    Spellcheck => "spellcheck",
+    Src => "src",
+    Style => "style",
+    Tabindex => "tabindex",
+    Title => "title",
+    Translate => "translate",
+});
+
+known_names!(KnownElementName {
+    Root => "root",
+    Address => "address",
+    Article => "article",
+    Aside => "aside",
+    Footer => "footer",
+    Header => "header",
+    Nav => "nav",
+    Section => "section",
+    Hgroup => "hgroup",
+    H1 => "h1",
+    H2 => "h2",
+    H3 => "h3",
+    H4 => "h4",
+    H5 => "h5",
+    H6 => "h6",
+    Main => "main",
+    Div => "div",
+    Span => "span",
+    P => "p",
+    Ol => "ol",
+    Ul => "ul",
+    Li => "li",
+    Dl => "dl",
+    Dt => "dt",
+    Dd => "dd",
+    Figure => "figure",
+    Figcaption => "figcaption",
+    Hr => "hr",
+    Pre => "pre",
+    Blockquote => "blockquote",
+    A => "a",
+    Bold => "b",
+    Italic => "i",
+    Underline => "u",
+    Strikethrough => "s",
+    Emphasis => "em",
+    Mark => "mark",
+    Quotation => "q",
+    Citation => "cite",
+    Code => "code",
+    Data => "data",
+    Time => "time",
+    Sub => "sub",
+    Sup => "sup",
+    Br => "br",
+    Wbr => "wbr",
+    Image => "img",
+    Area => "area",
+    Map => "map",
+    Audio => "audio",
+    Video => "video",
+    Track => "track",
+    Button => "button",
+    Datalist => "datalist",
+    Fieldset => "fieldset",
+    Form => "form",
+    Input => "input",
+    Label => "label",
+    Legend => "legend",
+    Meter => "meter",
+    Optgroup => "optgroup",
+    Option => "option",
+    Output => "output",
+    Progress => "progress",
+    Select => "select",
+    Textarea => "textarea",
+    Fragment => "fragment",
+    View => "view",
+    Text => "text",
+    TextInput => "text-input",
+    ScrollView => "scroll-view",
+    Picker => "picker",
+    Slider => "slider",
+    Switch => "switch",
+    FlatList => "flat-list",
+    SectionList => "section-list",
+});
+
+// Matched by the user action and input pseudo-classes. The event manager keeps the user action
+// states up to date, the input ones are taken from the controls.
+// See https://html.spec.whatwg.org/multipage/semantics-other.html#pseudo-classes
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub struct ElementState {
+    pub hovered: bool,
+    pub active: bool,
+    pub focused: bool,
+    pub focus_within: bool,
+    pub checked: bool,
+    pub disabled: bool,
+}
@@ -9,6 +9,7 @@ CONDITIONS OF ANY KIND, either express or implied. See the License for the
 specific language governing permissions and limitations under the License.
 */
 
+use num_traits::FromPrimitive;
 use self_tokenize_trait::ToCustomTokens;
 use std::cell::RefCell;
 use std::fmt;
@@ -33,8 +34,25 @@ pub const VIRTUAL_DBL_CLICK: u8 = VirtualEventType::DblClick as u8;
 pub const VIRTUAL_CONTEXT_MENU: u8 = VirtualEventType::ContextMenu as u8;
 pub const VIRTUAL_WHEEL: u8 = VirtualEventType::Wheel as u8;
 pub const VIRTUAL_SELECT: u8 = VirtualEventType::Select as u8;
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash, Primitive, SelfTokenize)]
+pub const VIRTUAL_POINTER_DOWN: u8 = VirtualEventType::PointerDown as u8;
+pub const VIRTUAL_POINTER_MOVE: u8 = VirtualEventType::PointerMove as u8;
+pub const VIRTUAL_POINTER_UP: u8 = VirtualEventType::PointerUp as u8;
+pub const VIRTUAL_POINTER_CANCEL: u8 = VirtualEventType::PointerCancel as u8;
+
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Copy,
+    Clone,
+    Hash,
+    Serialize,
+    Deserialize,
+    Primitive,
+    SelfTokenize,
+)]
 #[repr(u8)]
 pub enum VirtualEventType {
     // Keyboard events
@@ -52,6 +70,12 @@ pub enum VirtualEventType {
     ContextMenu = 17,
     Wheel = 18,
     Select = 19,
+
+    // Pointer events
+    PointerDown = 41,
+    PointerMove = 42,
+    PointerUp = 43,
+    PointerCancel = 44,
 }
 
 impl PartialEq<EventType> for VirtualEventType {
@@ -66,6 +90,12 @@ impl PartialEq<VirtualEventType> for EventType {
     }
 }
 
+impl From<VirtualEventType> for EventType {
+    fn from(ty: VirtualEventType) -> Self {
+        EventType::from_u8(ty as u8).expect("Virtual event type without a matching event type")
+    }
+}
+
 pub enum Closure<A> {
     Headless { f: HeadlessClosureId },
     Fn { f: Rc<Fn(A)> },
@@ -0,0 +1,358 @@
+use std::{f32, mem};
+use yoga;
+
+// Flexbox styles and layouts as the layout backends see them, so that backends don't depend on yoga's types.
+// jss still computes yoga's copies, which are converted below.
+// See https://yogalayout.com/docs
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum Align {
+    Auto,
+    FlexStart,
+    Center,
+    FlexEnd,
+    Stretch,
+    Baseline,
+    SpaceBetween,
+    SpaceAround,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    Inherit,
+    LTR,
+    RTL,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum Display {
+    Flex,
+    None,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum FlexDirection {
+    Column,
+    ColumnReverse,
+    Row,
+    RowReverse,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum Justify {
+    FlexStart,
+    Center,
+    FlexEnd,
+    SpaceBetween,
+    SpaceAround,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum Overflow {
+    Visible,
+    Hidden,
+    Scroll,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum PositionType {
+    Relative,
+    Absolute,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum Wrap {
+    NoWrap,
+    Wrap,
+    WrapReverse,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum StyleUnit {
+    UndefinedValue,
+    Point(f32),
+    Percent(f32),
+    Auto,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum FlexStyle {
+    AlignContent(Align),
+    AlignItems(Align),
+    AlignSelf(Align),
+    AspectRatio(f32),
+    BorderBottom(f32),
+    BorderEnd(f32),
+    BorderLeft(f32),
+    BorderRight(f32),
+    BorderStart(f32),
+    BorderTop(f32),
+    Bottom(StyleUnit),
+    Display(Display),
+    End(StyleUnit),
+    Flex(f32),
+    FlexBasis(StyleUnit),
+    FlexDirection(FlexDirection),
+    FlexGrow(f32),
+    FlexShrink(f32),
+    FlexWrap(Wrap),
+    Height(StyleUnit),
+    JustifyContent(Justify),
+    Left(StyleUnit),
+    Margin(StyleUnit),
+    MarginBottom(StyleUnit),
+    MarginEnd(StyleUnit),
+    MarginHorizontal(StyleUnit),
+    MarginLeft(StyleUnit),
+    MarginRight(StyleUnit),
+    MarginStart(StyleUnit),
+    MarginTop(StyleUnit),
+    MarginVertical(StyleUnit),
+    MaxHeight(StyleUnit),
+    MaxWidth(StyleUnit),
+    MinHeight(StyleUnit),
+    MinWidth(StyleUnit),
+    Overflow(Overflow),
+    Padding(StyleUnit),
+    PaddingBottom(StyleUnit),
+    PaddingEnd(StyleUnit),
+    PaddingHorizontal(StyleUnit),
+    PaddingLeft(StyleUnit),
+    PaddingRight(StyleUnit),
+    PaddingStart(StyleUnit),
+    PaddingTop(StyleUnit),
+    PaddingVertical(StyleUnit),
+    Position(PositionType),
+    Right(StyleUnit),
+    Start(StyleUnit),
+    Top(StyleUnit),
+    Width(StyleUnit),
+}
+
+// Computed box of a node, relative to its parent.
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Layout {
+    left: f32,
+    right: f32,
+    top: f32,
+    bottom: f32,
+    width: f32,
+    height: f32,
+}
+
+impl FlexStyle {
+    pub fn is_same_property(&self, other: &FlexStyle) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+    }
+
+    // The value of the property when it isn't styled, numbers left undefined are NaN like in yoga.
+    // See https://yogalayout.com/docs/styling
+    pub fn initial(&self) -> FlexStyle {
+        use self::FlexStyle::*;
+        use self::StyleUnit::{Auto, UndefinedValue};
+        match *self {
+            AlignContent(_) => AlignContent(Align::FlexStart),
+            AlignItems(_) => AlignItems(Align::Stretch),
+            AlignSelf(_) => AlignSelf(Align::Auto),
+            AspectRatio(_) => AspectRatio(f32::NAN),
+            BorderBottom(_) => BorderBottom(f32::NAN),
+            BorderEnd(_) => BorderEnd(f32::NAN),
+            BorderLeft(_) => BorderLeft(f32::NAN),
+            BorderRight(_) => BorderRight(f32::NAN),
+            BorderStart(_) => BorderStart(f32::NAN),
+            BorderTop(_) => BorderTop(f32::NAN),
+            Bottom(_) => Bottom(UndefinedValue),
+            Display(_) => Display(self::Display::Flex),
+            End(_) => End(UndefinedValue),
+            Flex(_) => Flex(f32::NAN),
+            FlexBasis(_) => FlexBasis(Auto),
+            FlexDirection(_) => FlexDirection(self::FlexDirection::Column),
+            FlexGrow(_) => FlexGrow(f32::NAN),
+            FlexShrink(_) => FlexShrink(f32::NAN),
+            FlexWrap(_) => FlexWrap(Wrap::NoWrap),
+            Height(_) => Height(Auto),
+            JustifyContent(_) => JustifyContent(Justify::FlexStart),
+            Left(_) => Left(UndefinedValue),
+            Margin(_) => Margin(UndefinedValue),
+            MarginBottom(_) => MarginBottom(UndefinedValue),
+            MarginEnd(_) => MarginEnd(UndefinedValue),
+            MarginHorizontal(_) => MarginHorizontal(UndefinedValue),
+            MarginLeft(_) => MarginLeft(UndefinedValue),
+            MarginRight(_) => MarginRight(UndefinedValue),
+            MarginStart(_) => MarginStart(UndefinedValue),
+            MarginTop(_) => MarginTop(UndefinedValue),
+            MarginVertical(_) => MarginVertical(UndefinedValue),
+            MaxHeight(_) => MaxHeight(UndefinedValue),
+            MaxWidth(_) => MaxWidth(UndefinedValue),
+            MinHeight(_) => MinHeight(UndefinedValue),
+            MinWidth(_) => MinWidth(UndefinedValue),
+            Overflow(_) => Overflow(self::Overflow::Visible),
+            Padding(_) => Padding(UndefinedValue),
+            PaddingBottom(_) => PaddingBottom(UndefinedValue),
+            PaddingEnd(_) => PaddingEnd(UndefinedValue),
+            PaddingHorizontal(_) => PaddingHorizontal(UndefinedValue),
+            PaddingLeft(_) => PaddingLeft(UndefinedValue),
+            PaddingRight(_) => PaddingRight(UndefinedValue),
+            PaddingStart(_) => PaddingStart(UndefinedValue),
+            PaddingTop(_) => PaddingTop(UndefinedValue),
+            PaddingVertical(_) => PaddingVertical(UndefinedValue),
+            Position(_) => Position(PositionType::Relative),
+            Right(_) => Right(UndefinedValue),
+            Start(_) => Start(UndefinedValue),
+            Top(_) => Top(UndefinedValue),
+            Width(_) => Width(Auto),
+        }
+    }
+}
+
+impl Layout {
+    pub fn new(left: f32, right: f32, top: f32, bottom: f32, width: f32, height: f32) -> Self {
+        Layout {
+            left,
+            right,
+            top,
+            bottom,
+            width,
+            height,
+        }
+    }
+
+    pub fn left(&self) -> f32 {
+        self.left
+    }
+
+    pub fn right(&self) -> f32 {
+        self.right
+    }
+
+    pub fn top(&self) -> f32 {
+        self.top
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.bottom
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+}
+
+// Unit-only enums have the same variants on both sides.
+macro_rules! yoga_enum {
+    ($name:ident { $($variant:ident),* }) => {
+        impl From<yoga::$name> for $name {
+            fn from(value: yoga::$name) -> Self {
+                match value {
+                    $(yoga::$name::$variant => $name::$variant,)*
+                }
+            }
+        }
+
+        impl From<$name> for yoga::$name {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => yoga::$name::$variant,)*
+                }
+            }
+        }
+    };
+}
+
+// Yoga wraps numbers in `OrderedFloat`, so that its styles can be hashed.
+macro_rules! yoga_flex_style {
+    (floats: [$($float:ident),*], others: [$($other:ident),*]) => {
+        impl From<yoga::FlexStyle> for FlexStyle {
+            fn from(style: yoga::FlexStyle) -> Self {
+                match style {
+                    $(yoga::FlexStyle::$float(value) => FlexStyle::$float(value.into_inner()),)*
+                    $(yoga::FlexStyle::$other(value) => FlexStyle::$other(value.into()),)*
+                }
+            }
+        }
+
+        impl From<FlexStyle> for yoga::FlexStyle {
+            fn from(style: FlexStyle) -> Self {
+                match style {
+                    $(FlexStyle::$float(value) => yoga::FlexStyle::$float(value.into()),)*
+                    $(FlexStyle::$other(value) => yoga::FlexStyle::$other(value.into()),)*
+                }
+            }
+        }
+    };
+}
+
+yoga_enum!(Align { Auto, FlexStart, Center, FlexEnd, Stretch, Baseline, SpaceBetween, SpaceAround });
+yoga_enum!(Direction { Inherit, LTR, RTL });
+yoga_enum!(Display { Flex, None });
+yoga_enum!(FlexDirection { Column, ColumnReverse, Row, RowReverse });
+yoga_enum!(Justify { FlexStart, Center, FlexEnd, SpaceBetween, SpaceAround });
+yoga_enum!(Overflow { Visible, Hidden, Scroll });
+yoga_enum!(PositionType { Relative, Absolute });
+yoga_enum!(Wrap { NoWrap, Wrap, WrapReverse });
+
+yoga_flex_style!(
+    floats: [
+        AspectRatio, BorderBottom, BorderEnd, BorderLeft, BorderRight, BorderStart, BorderTop, Flex, FlexGrow,
+        FlexShrink
+    ],
+    others: [
+        AlignContent, AlignItems, AlignSelf, Bottom, Display, End, FlexBasis, FlexDirection, FlexWrap, Height,
+        JustifyContent, Left, Margin, MarginBottom, MarginEnd, MarginHorizontal, MarginLeft, MarginRight,
+        MarginStart, MarginTop, MarginVertical, MaxHeight, MaxWidth, MinHeight, MinWidth, Overflow, Padding,
+        PaddingBottom, PaddingEnd, PaddingHorizontal, PaddingLeft, PaddingRight, PaddingStart, PaddingTop,
+        PaddingVertical, Position, Right, Start, Top, Width
+    ]
+);
+
+impl From<yoga::StyleUnit> for StyleUnit {
+    fn from(unit: yoga::StyleUnit) -> Self {
+        match unit {
+            yoga::StyleUnit::UndefinedValue => StyleUnit::UndefinedValue,
+            yoga::StyleUnit::Point(value) => StyleUnit::Point(value.into_inner()),
+            yoga::StyleUnit::Percent(value) => StyleUnit::Percent(value.into_inner()),
+            yoga::StyleUnit::Auto => StyleUnit::Auto,
+        }
+    }
+}
+
+impl From<StyleUnit> for yoga::StyleUnit {
+    fn from(unit: StyleUnit) -> Self {
+        match unit {
+            StyleUnit::UndefinedValue => yoga::StyleUnit::UndefinedValue,
+            StyleUnit::Point(value) => yoga::StyleUnit::Point(value.into()),
+            StyleUnit::Percent(value) => yoga::StyleUnit::Percent(value.into()),
+            StyleUnit::Auto => yoga::StyleUnit::Auto,
+        }
+    }
+}
+
+impl From<yoga::Layout> for Layout {
+    fn from(layout: yoga::Layout) -> Self {
+        Layout::new(
+            layout.left(),
+            layout.right(),
+            layout.top(),
+            layout.bottom(),
+            layout.width(),
+            layout.height(),
+        )
+    }
+}
+
+impl From<Layout> for yoga::Layout {
+    fn from(layout: Layout) -> Self {
+        yoga::Layout::new(
+            layout.left,
+            layout.right,
+            layout.top,
+            layout.bottom,
+            layout.width,
+            layout.height,
+        )
+    }
+}
@@ -0,0 +1,351 @@
+// See https://www.w3.org/TR/uievents-code/
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash, Serialize, Deserialize, Primitive)]
+#[repr(u8)]
+pub enum KeyCode {
+    Unidentified = 0,
+
+    // Alphanumeric section, writing system keys
+    KeyA = 1,
+    KeyB = 2,
+    KeyC = 3,
+    KeyD = 4,
+    KeyE = 5,
+    KeyF = 6,
+    KeyG = 7,
+    KeyH = 8,
+    KeyI = 9,
+    KeyJ = 10,
+    KeyK = 11,
+    KeyL = 12,
+    KeyM = 13,
+    KeyN = 14,
+    KeyO = 15,
+    KeyP = 16,
+    KeyQ = 17,
+    KeyR = 18,
+    KeyS = 19,
+    KeyT = 20,
+    KeyU = 21,
+    KeyV = 22,
+    KeyW = 23,
+    KeyX = 24,
+    KeyY = 25,
+    KeyZ = 26,
+    Digit0 = 27,
+    Digit1 = 28,
+    Digit2 = 29,
+    Digit3 = 30,
+    Digit4 = 31,
+    Digit5 = 32,
+    Digit6 = 33,
+    Digit7 = 34,
+    Digit8 = 35,
+    Digit9 = 36,
+    Backquote = 37,
+    Backslash = 38,
+    BracketLeft = 39,
+    BracketRight = 40,
+    Comma = 41,
+    Equal = 42,
+    IntlBackslash = 43,
+    Minus = 44,
+    Period = 45,
+    Quote = 46,
+    Semicolon = 47,
+    Slash = 48,
+
+    // Alphanumeric section, functional keys
+    AltLeft = 51,
+    AltRight = 52,
+    Backspace = 53,
+    CapsLock = 54,
+    ContextMenu = 55,
+    ControlLeft = 56,
+    ControlRight = 57,
+    Enter = 58,
+    MetaLeft = 59,
+    MetaRight = 60,
+    ShiftLeft = 61,
+    ShiftRight = 62,
+    Space = 63,
+    Tab = 64,
+
+    // Control pad section
+    Delete = 71,
+    End = 72,
+    Help = 73,
+    Home = 74,
+    Insert = 75,
+    PageDown = 76,
+    PageUp = 77,
+
+    // Arrow pad section
+    ArrowDown = 81,
+    ArrowLeft = 82,
+    ArrowRight = 83,
+    ArrowUp = 84,
+
+    // Numpad section
+    NumLock = 91,
+    Numpad0 = 92,
+    Numpad1 = 93,
+    Numpad2 = 94,
+    Numpad3 = 95,
+    Numpad4 = 96,
+    Numpad5 = 97,
+    Numpad6 = 98,
+    Numpad7 = 99,
+    Numpad8 = 100,
+    Numpad9 = 101,
+    NumpadAdd = 102,
+    NumpadDecimal = 103,
+    NumpadDivide = 104,
+    NumpadEnter = 105,
+    NumpadEqual = 106,
+    NumpadMultiply = 107,
+    NumpadSubtract = 108,
+
+    // Function section
+    Escape = 111,
+    F1 = 112,
+    F2 = 113,
+    F3 = 114,
+    F4 = 115,
+    F5 = 116,
+    F6 = 117,
+    F7 = 118,
+    F8 = 119,
+    F9 = 120,
+    F10 = 121,
+    F11 = 122,
+    F12 = 123,
+    PrintScreen = 124,
+    ScrollLock = 125,
+    Pause = 126,
+}
+
+// See https://www.w3.org/TR/uievents-key/#keys-modifier
+// AltGraph is left out, as the only supported layout has no AltGr key and reads AltRight as Alt.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash, Serialize, Deserialize, Primitive)]
+#[repr(u8)]
+pub enum KeyModifier {
+    Alt = 1,
+    CapsLock = 3,
+    Control = 4,
+    Meta = 5,
+    NumLock = 6,
+    ScrollLock = 7,
+    Shift = 8,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub struct KeyModifiers {
+    pub alt: bool,
+    pub caps_lock: bool,
+    pub ctrl: bool,
+    pub meta: bool,
+    pub num_lock: bool,
+    pub scroll_lock: bool,
+    pub shift: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum KeyboardLayout {
+    UsQwerty,
+}
+
+const US_LOWER_LETTERS: [&str; 26] = [
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z",
+];
+
+const US_UPPER_LETTERS: [&str; 26] = [
+    "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z",
+];
+
+const US_DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+const US_SHIFTED_DIGITS: [&str; 10] = [")", "!", "@", "#", "$", "%", "^", "&", "*", "("];
+
+const FUNCTION_KEYS: [&str; 12] = ["F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12"];
+
+impl Default for KeyCode {
+    fn default() -> Self {
+        KeyCode::Unidentified
+    }
+}
+
+impl KeyCode {
+    pub fn is_letter(self) -> bool {
+        self >= KeyCode::KeyA && self <= KeyCode::KeyZ
+    }
+
+    pub fn is_digit(self) -> bool {
+        self >= KeyCode::Digit0 && self <= KeyCode::Digit9
+    }
+
+    pub fn is_numpad_digit(self) -> bool {
+        self >= KeyCode::Numpad0 && self <= KeyCode::Numpad9
+    }
+
+    pub fn is_function(self) -> bool {
+        self >= KeyCode::F1 && self <= KeyCode::F12
+    }
+
+    pub fn is_modifier(self) -> bool {
+        self.modifier().is_some()
+    }
+
+    pub fn modifier(self) -> Option<KeyModifier> {
+        match self {
+            KeyCode::AltLeft | KeyCode::AltRight => Some(KeyModifier::Alt),
+            KeyCode::CapsLock => Some(KeyModifier::CapsLock),
+            KeyCode::ControlLeft | KeyCode::ControlRight => Some(KeyModifier::Control),
+            KeyCode::MetaLeft | KeyCode::MetaRight => Some(KeyModifier::Meta),
+            KeyCode::NumLock => Some(KeyModifier::NumLock),
+            KeyCode::ScrollLock => Some(KeyModifier::ScrollLock),
+            KeyCode::ShiftLeft | KeyCode::ShiftRight => Some(KeyModifier::Shift),
+            _ => None,
+        }
+    }
+}
+
+impl KeyModifiers {
+    pub fn get(&self, modifier: KeyModifier) -> bool {
+        match modifier {
+            KeyModifier::Alt => self.alt,
+            KeyModifier::CapsLock => self.caps_lock,
+            KeyModifier::Control => self.ctrl,
+            KeyModifier::Meta => self.meta,
+            KeyModifier::NumLock => self.num_lock,
+            KeyModifier::ScrollLock => self.scroll_lock,
+            KeyModifier::Shift => self.shift,
+        }
+    }
+
+    pub fn set(&mut self, modifier: KeyModifier, value: bool) {
+        match modifier {
+            KeyModifier::Alt => self.alt = value,
+            KeyModifier::CapsLock => self.caps_lock = value,
+            KeyModifier::Control => self.ctrl = value,
+            KeyModifier::Meta => self.meta = value,
+            KeyModifier::NumLock => self.num_lock = value,
+            KeyModifier::ScrollLock => self.scroll_lock = value,
+            KeyModifier::Shift => self.shift = value,
+        }
+    }
+}
+
+impl Default for KeyboardLayout {
+    fn default() -> Self {
+        KeyboardLayout::UsQwerty
+    }
+}
+
+impl KeyboardLayout {
+    // See https://www.w3.org/TR/uievents-key/
+    pub fn key(self, code: KeyCode, modifiers: &KeyModifiers) -> &'static str {
+        match self {
+            KeyboardLayout::UsQwerty => us_qwerty_key(code, modifiers),
+        }
+    }
+}
+
+#[cfg_attr(feature = "cargo-clippy", allow(cyclomatic_complexity))]
+fn us_qwerty_key(code: KeyCode, modifiers: &KeyModifiers) -> &'static str {
+    use self::KeyCode::*;
+
+    if code.is_letter() {
+        let index = (code as u8 - KeyA as u8) as usize;
+        return if modifiers.shift != modifiers.caps_lock {
+            US_UPPER_LETTERS[index]
+        } else {
+            US_LOWER_LETTERS[index]
+        };
+    }
+
+    if code.is_digit() {
+        let index = (code as u8 - Digit0 as u8) as usize;
+        return if modifiers.shift {
+            US_SHIFTED_DIGITS[index]
+        } else {
+            US_DIGITS[index]
+        };
+    }
+
+    if code.is_numpad_digit() && modifiers.num_lock {
+        return US_DIGITS[(code as u8 - Numpad0 as u8) as usize];
+    }
+
+    if code.is_function() {
+        return FUNCTION_KEYS[(code as u8 - F1 as u8) as usize];
+    }
+
+    let shift = modifiers.shift;
+    match code {
+        // Punctuation
+        Backquote => shifted(shift, "`", "~"),
+        Backslash | IntlBackslash => shifted(shift, "\\", "|"),
+        BracketLeft => shifted(shift, "[", "{"),
+        BracketRight => shifted(shift, "]", "}"),
+        Comma => shifted(shift, ",", "<"),
+        Equal => shifted(shift, "=", "+"),
+        Minus => shifted(shift, "-", "_"),
+        Period => shifted(shift, ".", ">"),
+        Quote => shifted(shift, "'", "\""),
+        Semicolon => shifted(shift, ";", ":"),
+        Slash => shifted(shift, "/", "?"),
+
+        // Functional keys
+        AltLeft | AltRight => "Alt",
+        Backspace => "Backspace",
+        CapsLock => "CapsLock",
+        ContextMenu => "ContextMenu",
+        ControlLeft | ControlRight => "Control",
+        Enter | NumpadEnter => "Enter",
+        MetaLeft | MetaRight => "Meta",
+        ShiftLeft | ShiftRight => "Shift",
+        Space => " ",
+        Tab => "Tab",
+
+        // Control and arrow pads, including the numpad without num lock
+        NumpadDecimal if modifiers.num_lock => ".",
+        Delete | NumpadDecimal => "Delete",
+        End | Numpad1 => "End",
+        Help => "Help",
+        Home | Numpad7 => "Home",
+        Insert | Numpad0 => "Insert",
+        PageDown | Numpad3 => "PageDown",
+        PageUp | Numpad9 => "PageUp",
+        ArrowDown | Numpad2 => "ArrowDown",
+        ArrowLeft | Numpad4 => "ArrowLeft",
+        ArrowRight | Numpad6 => "ArrowRight",
+        ArrowUp | Numpad8 => "ArrowUp",
+        Numpad5 => "Clear",
+
+        // Numpad operators
+        NumLock => "NumLock",
+        NumpadAdd => "+",
+        NumpadDivide => "/",
+        NumpadEqual => "=",
+        NumpadMultiply => "*",
+        NumpadSubtract => "-",
+
+        // Function section
+        Escape => "Escape",
+        PrintScreen => "PrintScreen",
+        ScrollLock => "ScrollLock",
+        Pause => "Pause",
+
+        _ => "Unidentified",
+    }
+}
+
+fn shifted(shift: bool, unshifted: &'static str, shifted: &'static str) -> &'static str {
+    if shift {
+        shifted
+    } else {
+        unshifted
+    }
+}
@@ -0,0 +1,217 @@
+use traits::{TClientPosition, TClientRect, TClientSize};
+use types::Direction;
+
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ClientPosition {
+    pub left: f32,
+    pub top: f32,
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ClientSize {
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ClientRect {
+    pub position: ClientPosition,
+    pub size: ClientSize,
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ClientEdges {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+// Sizes are in pixels, the line height defaults to whatever the measurer considers normal.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct TextFont {
+    pub family: Option<String>,
+    pub size: f32,
+    pub line_height: Option<f32>,
+}
+
+// The baseline is the distance from the top to the baseline of the first line.
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct TextMetrics {
+    pub width: f32,
+    pub height: f32,
+    pub baseline: f32,
+}
+
+// The initial containing block, sizes are in CSS pixels.
+// See https://drafts.csswg.org/css2/#initial-containing-block
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Viewport {
+    pub size: ClientSize,
+    pub device_pixel_ratio: f32,
+    pub direction: Direction,
+    // The color scheme preferred by the user.
+    pub color_scheme: ColorScheme,
+}
+
+// See https://drafts.csswg.org/mediaqueries-5/#prefers-color-scheme
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+// See https://drafts.csswg.org/css-display/#visibility
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum Visibility {
+    Visible,
+    Hidden,
+}
+
+// See https://drafts.csswg.org/cssom-view/#scrolling-area
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ScrollState {
+    offset: ClientPosition,
+    changed: bool,
+    // Set on the ancestors of changed nodes, so that they can be found without visiting the whole tree.
+    changed_descendants: bool,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            size: ClientSize::default(),
+            device_pixel_ratio: 1.0,
+            direction: Direction::LTR,
+            color_scheme: ColorScheme::default(),
+        }
+    }
+}
+
+impl Viewport {
+    pub fn new(width: f32, height: f32) -> Self {
+        Viewport {
+            size: ClientSize { width, height },
+            ..Viewport::default()
+        }
+    }
+
+    // Size in device pixels.
+    pub fn physical_size(&self) -> (u32, u32) {
+        let ratio = self.device_pixel_ratio;
+        ((self.size.width * ratio).round() as u32, (self.size.height * ratio).round() as u32)
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::Light
+    }
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Visibility::Visible
+    }
+}
+
+impl Default for TextFont {
+    fn default() -> Self {
+        TextFont {
+            family: None,
+            size: 16.0,
+            line_height: None,
+        }
+    }
+}
+
+impl TClientPosition for ClientPosition {}
+
+impl TClientSize for ClientSize {}
+
+impl ClientRect {
+    pub fn new(left: f32, top: f32, width: f32, height: f32) -> Self {
+        ClientRect {
+            position: ClientPosition { left, top },
+            size: ClientSize { width, height },
+        }
+    }
+
+    pub fn left(&self) -> f32 {
+        self.position.left
+    }
+
+    pub fn top(&self) -> f32 {
+        self.position.top
+    }
+
+    pub fn right(&self) -> f32 {
+        self.position.left + self.size.width
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.position.top + self.size.height
+    }
+
+    pub fn translate(&self, (dx, dy): (f32, f32)) -> Self {
+        ClientRect::new(self.left() + dx, self.top() + dy, self.size.width, self.size.height)
+    }
+}
+
+impl ScrollState {
+    pub fn offset(&self) -> ClientPosition {
+        self.offset
+    }
+
+    // Returns false when the offset didn't change.
+    pub fn set_offset(&mut self, offset: ClientPosition) -> bool {
+        if self.offset == offset {
+            return false;
+        }
+        self.offset = offset;
+        self.changed = true;
+        true
+    }
+
+    // Returns whether the offset changed since the last call, so that a scroll event can be fired.
+    pub fn take_changed(&mut self) -> bool {
+        ::std::mem::replace(&mut self.changed, false)
+    }
+
+    // Returns false when it was already marked.
+    pub fn mark_changed_descendants(&mut self) -> bool {
+        !::std::mem::replace(&mut self.changed_descendants, true)
+    }
+
+    pub fn take_changed_descendants(&mut self) -> bool {
+        ::std::mem::replace(&mut self.changed_descendants, false)
+    }
+}
+
+impl TClientRect for ClientRect {
+    type Position = ClientPosition;
+    type Size = ClientSize;
+
+    fn position(&self) -> Self::Position {
+        self.position
+    }
+
+    fn size(&self) -> Self::Size {
+        self.size
+    }
+
+    fn offset_from_page(&self, (x, y): (u32, u32)) -> (u32, u32) {
+        let x = (x as f32 - self.left()).max(0.0);
+        let y = (y as f32 - self.top()).max(0.0);
+        (x as u32, y as u32)
+    }
+
+    fn client_from_page(&self, point: (u32, u32)) -> (u32, u32) {
+        point
+    }
+
+    fn contains_point(&self, (x, y): (u32, u32)) -> bool {
+        let (x, y) = (x as f32, y as f32);
+        x >= self.left() && x < self.right() && y >= self.top() && y < self.bottom()
+    }
+}
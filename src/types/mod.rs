@@ -1,7 +1,15 @@
+mod atom;
 mod dom;
 mod event;
+mod flex;
+mod keyboard;
+mod layout;
 mod props;
 
+pub use self::atom::*;
 pub use self::dom::*;
 pub use self::event::*;
+pub use self::flex::*;
+pub use self::keyboard::*;
+pub use self::layout::*;
 pub use self::props::*;
@@ -10,12 +10,13 @@ specific language governing permissions and limitations under the License.
 */
 
 use node::{DOMAttribute, DOMAttributeName, DOMAttributeValue, DOMText};
-use traits::TGenericEvent;
+use traits::{TGenericEvent, TLayoutNode};
 use types::{Closure, EventType, KnownAttributeName};
 
-pub fn is_event_listener<T>(attribute: &DOMAttribute<T>) -> Option<&Closure<T>>
+pub fn is_event_listener<T, L>(attribute: &DOMAttribute<T, L>) -> Option<&Closure<T>>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     use self::DOMAttributeName::KnownName;
     use self::DOMAttributeValue::EventListener;
@@ -26,9 +27,10 @@ where
     }
 }
 
-pub fn is_src<T>(attribute: &DOMAttribute<T>) -> Option<&DOMText>
+pub fn is_src<T, L>(attribute: &DOMAttribute<T, L>) -> Option<&DOMText>
 where
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     use self::DOMAttributeName::KnownName;
     use self::DOMAttributeValue::Str;
@@ -39,27 +41,30 @@ where
     }
 }
 
-pub fn find_attribute<'a, I, T>(iter: I, name: &DOMAttributeName) -> Option<&'a DOMAttribute<T>>
+pub fn find_attribute<'a, I, T, L>(iter: I, name: &DOMAttributeName) -> Option<&'a DOMAttribute<T, L>>
 where
-    I: IntoIterator<Item = &'a DOMAttribute<T>>,
+    I: IntoIterator<Item = &'a DOMAttribute<T, L>>,
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     iter.into_iter().find(|v| &v.0 == name)
 }
 
-pub fn find_event_listener<'a, I, T: 'a>(iter: I, ty: EventType) -> Option<&'a Closure<T>>
+pub fn find_event_listener<'a, I, T: 'a, L>(iter: I, ty: EventType) -> Option<&'a Closure<T>>
 where
-    I: IntoIterator<Item = &'a DOMAttribute<T>>,
+    I: IntoIterator<Item = &'a DOMAttribute<T, L>>,
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     let name = DOMAttributeName::EventType(ty);
     is_event_listener(find_attribute(iter, &name)?)
 }
 
-pub fn find_src<'a, I, T: 'a>(iter: I) -> Option<&'a DOMText>
+pub fn find_src<'a, I, T: 'a, L>(iter: I) -> Option<&'a DOMText>
 where
-    I: IntoIterator<Item = &'a DOMAttribute<T>>,
+    I: IntoIterator<Item = &'a DOMAttribute<T, L>>,
     T: TGenericEvent,
+    L: TLayoutNode,
 {
     let name = DOMAttributeName::KnownName(KnownAttributeName::Src);
     is_src(find_attribute(iter, &name)?)
@@ -0,0 +1,138 @@
+extern crate dom;
+extern crate jss;
+extern crate serde_json;
+
+mod common;
+
+use common::{element, Id};
+use dom::events::{BasicEvent, EventManager};
+use dom::node::DOMAttributeValue;
+use dom::style::{interpolate, StyleSheet, StyleSheetError, TimingFunction};
+use dom::traits::{TAnimationEvent, TEvent};
+use dom::tree::DOMTree;
+use dom::types::{EventType, KnownAttributeName, KnownElementName};
+use serde_json::Value;
+
+const SHEET: &str = r#"{
+    ".box": {
+        "width": "100px",
+        "height": "10px",
+        "color": "#000000",
+        "transition": "width 1s linear, color 1s linear"
+    },
+    ".box.wide": { "width": "200px", "color": "#ff0000" },
+    "@keyframes grow": {
+        "from": { "width": "0px" },
+        "50%": { "width": "100px" },
+        "to": { "width": "50px" }
+    },
+    ".grow": { "height": "10px", "width": "10px", "animation": "grow 1s linear 2 alternate forwards" },
+    "@keyframes lift": {
+        "from": { "margin-top": "10px" },
+        "to": { "margin-top": "20px" }
+    },
+    ".lift": { "height": "10px", "width": "10px", "animation": "lift 1s linear" }
+}"#;
+
+fn tree(class: &'static str) -> (DOMTree<BasicEvent>, Id) {
+    let mut tree = DOMTree::default();
+    tree.add_stylesheet(StyleSheet::parse_from_str(SHEET).unwrap());
+    let id = tree.root_mut().append(element(KnownElementName::Div, KnownAttributeName::Class, class)).id();
+    tree.generate_layout_tree();
+    tree.resize(400.0, 100.0);
+    (tree, id)
+}
+
+fn width_at(tree: &mut DOMTree<BasicEvent>, id: Id, now: u64) -> f32 {
+    tree.tick(now);
+    tree.layout_viewport();
+    tree.client_rect(id).size.width
+}
+
+#[test]
+fn transitions_interpolate_towards_new_values() {
+    let (mut tree, id) = tree("box");
+    let mut manager = EventManager::default();
+    assert!(!tree.is_animating());
+
+    tree.get_mut(id)
+        .set_attribute(KnownAttributeName::Class, DOMAttributeValue::from("box wide"));
+    tree.layout_viewport();
+    assert!(tree.is_animating());
+    assert_eq!(tree.client_rect(id).size.width, 100.0);
+
+    assert_eq!(width_at(&mut tree, id, 500), 150.0);
+    let color = tree.get(id).computed_declarations().get("color").cloned();
+    assert_eq!(color, Some(Value::from("rgba(128, 0, 0, 1)")));
+    assert!(manager.broadcast_and_collect_events(&mut tree).is_empty());
+
+    assert_eq!(width_at(&mut tree, id, 1000), 200.0);
+    assert!(!tree.is_animating());
+    let events = manager.broadcast_and_collect_events(&mut tree);
+    let mut names = events
+        .iter()
+        .inspect(|event| assert_eq!(event.ty(), EventType::TransitionEnd))
+        .inspect(|event| assert_eq!(event.elapsed_time(), 1.0))
+        .filter_map(|event| event.animation_name())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["color", "width"]);
+}
+
+#[test]
+fn keyframes_run_their_iterations() {
+    let (mut tree, id) = tree("grow");
+    let mut manager = EventManager::default();
+    assert_eq!(tree.client_rect(id).size.width, 0.0);
+
+    assert_eq!(width_at(&mut tree, id, 250), 50.0);
+    // The second iteration runs backwards.
+    assert_eq!(width_at(&mut tree, id, 1250), 75.0);
+    assert!(manager.broadcast_and_collect_events(&mut tree).is_empty());
+
+    // Filling forwards keeps the end of the last iteration.
+    assert_eq!(width_at(&mut tree, id, 2500), 0.0);
+    assert!(!tree.is_animating());
+    let events = manager.broadcast_and_collect_events(&mut tree);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].ty(), EventType::AnimationEnd);
+    assert_eq!(events[0].animation_name(), Some("grow"));
+    assert_eq!(events[0].elapsed_time(), 2.0);
+
+    // Removing the animation lets the cascaded value apply again.
+    tree.get_mut(id)
+        .set_attribute(KnownAttributeName::Class, DOMAttributeValue::from("other"));
+    tree.layout_viewport();
+    assert!(tree.get(id).computed_declarations().get("width").is_none());
+}
+
+#[test]
+fn properties_only_set_by_an_animation_are_dropped_when_it_ends() {
+    let (mut tree, id) = tree("lift");
+    let mut top_at = |now| {
+        tree.tick(now);
+        tree.layout_viewport();
+        tree.client_rect(id).position.top
+    };
+    assert_eq!(top_at(500), 15.0);
+    assert_eq!(top_at(1000), 0.0);
+}
+
+#[test]
+fn values_and_timing_functions() {
+    let rotate = |deg: &str| Value::from(vec![format!("rotate({})", deg), "translate(0px, 10px)".to_string()]);
+    assert_eq!(interpolate(&rotate("-90deg"), &rotate("90deg"), 0.25), rotate("-45deg"));
+    assert_eq!(interpolate(&Value::from(10), &Value::from("20px"), 0.5), Value::from("15px"));
+    assert_eq!(interpolate(&Value::from("row"), &Value::from("column"), 0.4), Value::from("row"));
+    assert_eq!(interpolate(&Value::from("row"), &Value::from("column"), 0.5), Value::from("column"));
+
+    let ease_in_out = TimingFunction::parse("ease-in-out").unwrap();
+    assert!((ease_in_out.apply(0.5) - 0.5).abs() < 1e-3);
+    assert!(ease_in_out.apply(0.25) < 0.25);
+    let steps = TimingFunction::parse("steps(4, end)").unwrap();
+    assert_eq!(steps.apply(0.3), 0.25);
+    assert_eq!(TimingFunction::parse("cubic-bezier(2, 0, 0, 1)"), None);
+
+    let sheet = StyleSheet::parse_from_str(r#"{ "@keyframes broken": { "sometimes": {} } }"#);
+    assert_eq!(sheet, Err(StyleSheetError::InvalidKeyframes("broken".to_string())));
+}
@@ -0,0 +1,102 @@
+#![allow(dead_code)]
+
+use dom::events::{node_id, BasicEvent, EventManager, KeyboardEvent, MouseButton, MouseEvent};
+use dom::node::{DOMAttributeValue, DOMNode, DOMNodeId, DOMTagName};
+use dom::traits::{TEvent, TEventManager};
+use dom::tree::DOMTree;
+use dom::types::{EventType, KeyCode, KeyModifiers, KnownAttributeName, KnownElementName, VirtualEventType};
+use jss::types::{Case, Style, StyleBuilder};
+
+pub type Id = DOMNodeId<BasicEvent>;
+
+pub fn style(source: &str) -> Style {
+    StyleBuilder::default().case(Case::Ignore).parse_from_str(source).unwrap()
+}
+
+pub fn styled(tag: KnownElementName, source: &str) -> DOMNode<BasicEvent> {
+    DOMNode::from((DOMTagName::from(tag), style(source)))
+}
+
+pub fn div(source: &str) -> DOMNode<BasicEvent> {
+    styled(KnownElementName::Div, source)
+}
+
+// An element without authored styles, holding a single attribute.
+pub fn element(tag: KnownElementName, name: KnownAttributeName, value: &'static str) -> DOMNode<BasicEvent> {
+    with_attribute(DOMNode::from(DOMTagName::from(tag)), name, value)
+}
+
+pub fn with_attribute<V>(mut node: DOMNode<BasicEvent>, name: KnownAttributeName, value: V) -> DOMNode<BasicEvent>
+where
+    V: Into<DOMAttributeValue<BasicEvent>>,
+{
+    node.data.attributes_mut().unwrap().common.insert(name, value.into());
+    node
+}
+
+pub fn with_simple_attribute<V>(mut node: DOMNode<BasicEvent>, name: &'static str, value: V) -> DOMNode<BasicEvent>
+where
+    V: Into<DOMAttributeValue<BasicEvent>>,
+{
+    node.data.attributes_mut().unwrap().simple.insert(name, value.into());
+    node
+}
+
+pub fn set_inline_style(tree: &mut DOMTree<BasicEvent>, id: Id, source: &'static str) {
+    tree.get_mut(id).set_attribute(KnownAttributeName::Style, DOMAttributeValue::from(source));
+}
+
+pub const ROW: &str = r#"{ "flex-direction": "row", "align-items": "flex-start" }"#;
+pub const BOX: &str = r#"{ "width": "50px", "height": "50px" }"#;
+
+// A row holding two 50x50 boxes.
+pub fn row() -> (DOMTree<BasicEvent>, Id, Id, Id) {
+    let mut tree = DOMTree::default();
+    let row_id = tree.root_mut().append(div(ROW)).id();
+    let first_id = tree.get_mut(row_id).append(div(BOX)).id();
+    let second_id = tree.get_mut(row_id).append(div(BOX)).id();
+    (tree, row_id, first_id, second_id)
+}
+
+// Lays the tree out in a 200x100 viewport and drains the resize event that causes.
+pub fn start(tree: &mut DOMTree<BasicEvent>) -> EventManager {
+    tree.generate_layout_tree();
+    tree.resize(200.0, 100.0);
+    let mut manager = EventManager::default();
+    manager.broadcast_events(tree);
+    manager
+}
+
+pub fn fired(events: &[BasicEvent]) -> Vec<(EventType, Id)> {
+    events.iter().map(|event| (event.ty(), node_id(event.target()))).collect()
+}
+
+pub fn mouse(
+    manager: &mut EventManager,
+    tree: &mut DOMTree<BasicEvent>,
+    ty: VirtualEventType,
+    button: MouseButton,
+    point: (u32, u32),
+    timestamp: u64,
+) -> Vec<BasicEvent> {
+    manager.receive_mouse_event(ty, MouseEvent::new(button, point, timestamp));
+    manager.broadcast_and_collect_events(tree)
+}
+
+pub fn click(manager: &mut EventManager, tree: &mut DOMTree<BasicEvent>, point: (u32, u32)) -> Vec<BasicEvent> {
+    let main = MouseButton::Main;
+    manager.receive_mouse_event(VirtualEventType::MouseDown, MouseEvent::new(main, point, 0));
+    manager.receive_mouse_event(VirtualEventType::MouseUp, MouseEvent::new(main, point, 0));
+    manager.broadcast_and_collect_events(tree)
+}
+
+pub fn key(
+    manager: &mut EventManager,
+    tree: &mut DOMTree<BasicEvent>,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> Vec<BasicEvent> {
+    manager.receive_key_event(VirtualEventType::KeyDown, KeyboardEvent::new(code, modifiers, false));
+    manager.receive_key_event(VirtualEventType::KeyUp, KeyboardEvent::new(code, modifiers, false));
+    manager.broadcast_and_collect_events(tree)
+}
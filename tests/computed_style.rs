@@ -0,0 +1,71 @@
+extern crate dom;
+extern crate jss;
+extern crate serde_json;
+
+mod common;
+
+use common::element;
+use dom::events::BasicEvent;
+use dom::node::DOMNode;
+use dom::style::{parse_color, ComputedStyle, ComputedValue, Rgba, StyleSheet};
+use dom::tree::DOMTree;
+use dom::types::{KnownAttributeName, KnownElementName};
+
+const SHEET: &str = r#"{
+    "#outer": { "color": "rgb(255, 0, 0)", "font-size": "20px", "width": "50%", "height": "40px" },
+    "#inner": {
+        "min-height": "2em",
+        "background-color": "currentColor",
+        "flex-grow": 1,
+        "flex-direction": "Row",
+        "visibility": "hidden"
+    }
+}"#;
+
+#[test]
+fn resolves_lengths_colors_and_keywords() {
+    let mut tree: DOMTree<BasicEvent> = DOMTree::default();
+    tree.add_stylesheet(StyleSheet::parse_from_str(SHEET).unwrap());
+    let outer_id = tree.root_mut().append(element(KnownElementName::Div, KnownAttributeName::Id, "outer")).id();
+    let inner = element(KnownElementName::Div, KnownAttributeName::Id, "inner");
+    let inner_id = tree.get_mut(outer_id).append(inner).id();
+    let text_id = tree.get_mut(inner_id).append(DOMNode::from("text")).id();
+    tree.generate_layout_tree();
+    tree.resize(200.0, 100.0);
+
+    let red = ComputedValue::Color(Rgba { r: 255, g: 0, b: 0, a: 1.0 });
+    let outer = tree.get_computed_style(outer_id);
+    assert_eq!(outer.get("width"), Some(&ComputedValue::Length(100.0)));
+    assert_eq!(outer.get("height"), Some(&ComputedValue::Length(40.0)));
+    assert_eq!(outer.get("margin-left"), Some(&ComputedValue::Length(0.0)));
+    assert_eq!(outer.get("color"), Some(&red));
+    assert_eq!(outer.get("display"), Some(&ComputedValue::Keyword("flex".to_string())));
+
+    let inner = tree.get_computed_style(inner_id);
+    assert_eq!(inner.get("min-height"), Some(&ComputedValue::Length(40.0)));
+    assert_eq!(inner.get("background-color"), Some(&red));
+    assert_eq!(inner.get("flex-grow"), Some(&ComputedValue::Number(1.0)));
+    assert_eq!(inner.get("flex-direction"), Some(&ComputedValue::Keyword("row".to_string())));
+    assert_eq!(inner.get("visibility"), Some(&ComputedValue::Keyword("hidden".to_string())));
+
+    // Inherited values reach text nodes.
+    let text = tree.get_computed_style(text_id);
+    assert_eq!(text.get("color"), Some(&red));
+    assert_eq!(text.get("font-size"), Some(&ComputedValue::Length(20.0)));
+    assert_eq!(text.get("visibility"), Some(&ComputedValue::Keyword("hidden".to_string())));
+
+    let json = text.to_json();
+    assert_eq!(serde_json::from_str::<ComputedStyle>(&json).unwrap(), text);
+}
+
+#[test]
+fn parses_color_notations() {
+    let color = |r, g, b, a| Some(Rgba { r, g, b, a });
+    assert_eq!(parse_color("#0f0"), color(0, 255, 0, 1.0));
+    assert_eq!(parse_color("#00000000"), color(0, 0, 0, 0.0));
+    assert_eq!(parse_color("rgba(10, 20, 30, 0.5)"), color(10, 20, 30, 0.5));
+    assert_eq!(parse_color("rgb(100% 0% 0% / 25%)"), color(255, 0, 0, 0.25));
+    assert_eq!(parse_color("Transparent"), color(0, 0, 0, 0.0));
+    assert_eq!(parse_color("#12345"), None);
+    assert_eq!(parse_color("nope"), None);
+}
@@ -0,0 +1,92 @@
+extern crate dom;
+extern crate jss;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use common::*;
+use dom::events::{raw_id, BasicEvent};
+use dom::traits::{TCustomEvent, TEvent};
+use dom::types::{Atom, Closure};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::panic;
+use std::rc::Rc;
+use std::thread;
+
+#[test]
+fn custom_events_bubble_with_their_detail() {
+    let (mut tree, row_id, first_id, _) = row();
+    let mut manager = start(&mut tree);
+    let seen = Rc::new(RefCell::new(vec![]));
+
+    let log = seen.clone();
+    manager.add_custom_event_listener(row_id, "select", Closure::new(move |event: BasicEvent| {
+        log.borrow_mut().push((event.target(), event.detail().cloned()));
+    }));
+
+    let detail = json!({ "index": 2, "label": "second" });
+    assert!(manager.dispatch_custom_event(&tree, first_id, "select", detail.clone()));
+    assert!(manager.dispatch_custom_event(&tree, first_id, "other", Value::Null));
+    assert_eq!(*seen.borrow(), vec![(raw_id(first_id), Some(detail))]);
+}
+
+#[test]
+fn prevented_custom_events_report_it() {
+    let (mut tree, row_id, first_id, _) = row();
+    let mut manager = start(&mut tree);
+    let listener = Closure::new(|event: BasicEvent| event.prevent_default());
+    manager.add_custom_event_listener(row_id, "close", listener);
+
+    assert!(!manager.dispatch_custom_event(&tree, first_id, "close", Value::Null));
+    assert!(manager.dispatch_custom_event(&tree, row_id, "open", Value::Null));
+}
+
+#[test]
+fn custom_events_serialize_their_detail() {
+    let (_, _, first_id, _) = row();
+    let event = BasicEvent::custom("select", raw_id(first_id), json!([1, "two", { "three": 3 }]));
+    let json = serde_json::to_string(&event).unwrap();
+    let parsed: BasicEvent = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.custom_type(), Some(Atom::new("select")));
+    assert_eq!(parsed.detail(), event.detail());
+}
+
+#[test]
+fn dropping_listeners_keeps_them_apart() {
+    let (mut tree, _, first_id, _) = row();
+    let mut node = tree.get_mut(first_id);
+    let listener = Closure::new(|_: BasicEvent| {});
+    node.data.attributes_mut().unwrap().custom_listeners.insert(Atom::new("select"), listener);
+
+    assert_eq!(node.data.drop_event_listeners().unwrap().count(), 0);
+    let dropped: Vec<_> = node.data.drop_custom_event_listeners().unwrap().map(|v| v.0).collect();
+    assert_eq!(dropped, vec![Atom::new("select")]);
+    assert_eq!(node.data.drop_custom_event_listeners().unwrap().count(), 0);
+}
+
+#[test]
+fn names_are_interned_up_to_a_limit() {
+    // The interner is per thread, so start from an empty one.
+    thread::spawn(|| {
+        let names: Vec<_> = (0..)
+            .map(|i| Atom::try_new(&format!("name-{}", i)))
+            .take_while(Option::is_some)
+            .collect();
+        assert_eq!(names.len(), 4096);
+
+        // Known names keep working, whichever constructor is used.
+        assert_eq!(Atom::try_new("name-0"), names[0]);
+        assert_eq!(Atom::new("name-0"), names[0].unwrap());
+        assert_eq!(Atom::from("name-1"), names[1].unwrap());
+        assert!(panic::catch_unwind(|| Atom::new("unknown")).is_err());
+        assert!(panic::catch_unwind(|| Atom::from("unknown")).is_err());
+        assert!(panic::catch_unwind(|| Atom::from("unknown".to_string())).is_err());
+
+        assert!(serde_json::from_str::<Atom>(r#""name-1""#).is_ok());
+        assert!(serde_json::from_str::<Atom>(r#""unknown""#).is_err());
+    })
+    .join()
+    .unwrap();
+}
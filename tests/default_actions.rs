@@ -0,0 +1,95 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::events::{raw_id, BasicEvent, DefaultActionContext, DefaultActionRequest};
+use dom::traits::{TEvent, TEventManager};
+use dom::tree::DOMTree;
+use dom::types::{Closure, EventType, KnownElementName};
+
+// A row holding a link and a checkbox.
+fn tree() -> (DOMTree<BasicEvent>, Id, Id) {
+    let mut tree = DOMTree::default();
+    let row_id = tree.root_mut().append(div(ROW)).id();
+    let link = with_simple_attribute(styled(KnownElementName::A, BOX), "href", "/next");
+    let checkbox = with_simple_attribute(styled(KnownElementName::Input, BOX), "type", "checkbox");
+    let link_id = tree.get_mut(row_id).append(link).id();
+    let checkbox_id = tree.get_mut(row_id).append(checkbox).id();
+    (tree, link_id, checkbox_id)
+}
+
+fn prevent_default() -> Closure<BasicEvent> {
+    Closure::new(|event: BasicEvent| event.prevent_default())
+}
+
+fn navigate_elsewhere(ctx: &mut DefaultActionContext) -> bool {
+    let target = ctx.event.target();
+    let href = "/elsewhere".to_string();
+    ctx.request(DefaultActionRequest::Navigate { target, href });
+    true
+}
+
+#[test]
+fn links_request_navigation_unless_prevented() {
+    let (mut tree, link_id, _) = tree();
+    let mut manager = start(&mut tree);
+
+    click(&mut manager, &mut tree, (5, 5));
+    let navigate = DefaultActionRequest::Navigate {
+        target: raw_id(link_id),
+        href: "/next".to_string(),
+    };
+    assert_eq!(manager.take_requests(), vec![navigate]);
+
+    manager.add_event_listener(link_id, EventType::Click, prevent_default());
+    let events = click(&mut manager, &mut tree, (5, 5));
+    assert!(events.last().unwrap().default_prevented());
+    assert!(manager.take_requests().is_empty());
+}
+
+#[test]
+fn prevented_clicks_leave_checkboxes_alone() {
+    let (mut tree, _, checkbox_id) = tree();
+    let mut manager = start(&mut tree);
+    manager.add_event_listener(checkbox_id, EventType::Click, prevent_default());
+
+    let events = fired(&click(&mut manager, &mut tree, (55, 5)));
+    assert!(!events.contains(&(EventType::Change, checkbox_id)));
+    assert!(!tree.get(checkbox_id).checked());
+}
+
+#[test]
+fn prevented_mouse_down_keeps_the_focus() {
+    let (mut tree, _, checkbox_id) = tree();
+    let mut manager = start(&mut tree);
+    manager.add_event_listener(checkbox_id, EventType::MouseDown, prevent_default());
+
+    click(&mut manager, &mut tree, (55, 5));
+    assert_eq!(tree.active_element(), None);
+}
+
+#[test]
+fn embedders_can_override_and_remove_actions() {
+    let (mut tree, link_id, _) = tree();
+    let mut manager = start(&mut tree);
+
+    manager
+        .default_actions_mut()
+        .set(KnownElementName::A, EventType::Click, navigate_elsewhere);
+    click(&mut manager, &mut tree, (5, 5));
+    let navigate = DefaultActionRequest::Navigate {
+        target: raw_id(link_id),
+        href: "/elsewhere".to_string(),
+    };
+    assert_eq!(manager.take_requests(), vec![navigate]);
+
+    assert!(manager.default_actions_mut().remove(KnownElementName::A, EventType::Click));
+    click(&mut manager, &mut tree, (5, 5));
+    assert!(manager.take_requests().is_empty());
+
+    assert!(manager.default_actions_mut().remove_global(EventType::MouseDown));
+    click(&mut manager, &mut tree, (55, 5));
+    assert_eq!(tree.active_element(), Some(link_id));
+}
@@ -0,0 +1,145 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::events::{BasicEvent, DropEffect, EventManager, MouseButton};
+use dom::traits::{TDragEvent, TEvent, TEventManager};
+use dom::tree::DOMTree;
+use dom::types::{Closure, EventType, KnownAttributeName, VirtualEventType};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const DRAG_EVENTS: &[EventType] = &[
+    EventType::DragStart,
+    EventType::Drag,
+    EventType::DragEnter,
+    EventType::DragOver,
+    EventType::DragLeave,
+    EventType::Drop,
+    EventType::DragEnd,
+];
+
+// A row with a draggable first box and a second box accepting moved text.
+fn tree() -> (DOMTree<BasicEvent>, Id, Id) {
+    let mut tree = DOMTree::default();
+    let row_id = tree.root_mut().append(div(ROW)).id();
+    let source = with_attribute(div(BOX), KnownAttributeName::Draggable, "true");
+    let zone = with_attribute(div(BOX), KnownAttributeName::Dropzone, "move string:text/plain");
+    let source_id = tree.get_mut(row_id).append(source).id();
+    let zone_id = tree.get_mut(row_id).append(zone).id();
+    (tree, source_id, zone_id)
+}
+
+fn set_text() -> Closure<BasicEvent> {
+    Closure::new(|event: BasicEvent| {
+        event.data_transfer().unwrap().set_data("text/plain", "dragged");
+    })
+}
+
+fn prevent_default() -> Closure<BasicEvent> {
+    Closure::new(|event: BasicEvent| event.prevent_default())
+}
+
+fn drag(
+    manager: &mut EventManager,
+    tree: &mut DOMTree<BasicEvent>,
+    ty: VirtualEventType,
+    point: (u32, u32),
+) -> Vec<(EventType, Id)> {
+    let events = mouse(manager, tree, ty, MouseButton::Main, point, 0);
+    fired(&events).into_iter().filter(|v| DRAG_EVENTS.contains(&v.0)).collect()
+}
+
+#[test]
+fn dropzones_accept_matching_data() {
+    let (mut tree, first_id, second_id) = tree();
+    let mut manager = start(&mut tree);
+    manager.add_event_listener(first_id, EventType::DragStart, set_text());
+
+    let dropped = Rc::new(RefCell::new(vec![]));
+    let log = dropped.clone();
+    manager.add_event_listener(second_id, EventType::Drop, Closure::new(move |event: BasicEvent| {
+        let transfer = event.data_transfer().unwrap();
+        log.borrow_mut().push((transfer.get_data("TEXT/PLAIN"), transfer.drop_effect()));
+    }));
+
+    // Presses only become drags once the pointer moved past the click threshold.
+    assert!(drag(&mut manager, &mut tree, VirtualEventType::MouseDown, (5, 5)).is_empty());
+    let events = drag(&mut manager, &mut tree, VirtualEventType::MouseMove, (60, 5));
+    assert_eq!(
+        events,
+        vec![
+            (EventType::DragStart, first_id),
+            (EventType::Drag, first_id),
+            (EventType::DragEnter, second_id),
+            (EventType::DragOver, second_id),
+        ]
+    );
+
+    let events = drag(&mut manager, &mut tree, VirtualEventType::MouseMove, (70, 5));
+    assert_eq!(events, vec![(EventType::Drag, first_id), (EventType::DragOver, second_id)]);
+
+    let events = drag(&mut manager, &mut tree, VirtualEventType::MouseUp, (70, 5));
+    assert_eq!(events, vec![(EventType::Drop, second_id), (EventType::DragEnd, first_id)]);
+    assert_eq!(*dropped.borrow(), vec![(Some("dragged".to_string()), DropEffect::Move)]);
+
+    // The drag is over, so moving no longer drags.
+    assert!(drag(&mut manager, &mut tree, VirtualEventType::MouseMove, (5, 5)).is_empty());
+}
+
+#[test]
+fn leaving_the_dropzone_cancels_the_drop() {
+    let (mut tree, first_id, second_id) = tree();
+    let mut manager = start(&mut tree);
+    manager.add_event_listener(first_id, EventType::DragStart, set_text());
+
+    drag(&mut manager, &mut tree, VirtualEventType::MouseDown, (5, 5));
+    drag(&mut manager, &mut tree, VirtualEventType::MouseMove, (60, 5));
+    let events = drag(&mut manager, &mut tree, VirtualEventType::MouseMove, (5, 5));
+    assert_eq!(
+        events,
+        vec![
+            (EventType::Drag, first_id),
+            (EventType::DragEnter, first_id),
+            (EventType::DragLeave, second_id),
+        ]
+    );
+
+    let events = drag(&mut manager, &mut tree, VirtualEventType::MouseUp, (5, 5));
+    assert_eq!(events, vec![(EventType::DragEnd, first_id)]);
+}
+
+#[test]
+fn dropzones_ignore_other_data() {
+    let (mut tree, first_id, second_id) = tree();
+    let mut manager = start(&mut tree);
+
+    drag(&mut manager, &mut tree, VirtualEventType::MouseDown, (5, 5));
+    let events = drag(&mut manager, &mut tree, VirtualEventType::MouseMove, (60, 5));
+    assert_eq!(
+        events,
+        vec![
+            (EventType::DragStart, first_id),
+            (EventType::Drag, first_id),
+            (EventType::DragEnter, second_id),
+        ]
+    );
+
+    let events = drag(&mut manager, &mut tree, VirtualEventType::MouseUp, (60, 5));
+    assert_eq!(events, vec![(EventType::DragEnd, first_id)]);
+}
+
+#[test]
+fn canceling_drag_enter_makes_any_element_a_drop_target() {
+    let (mut tree, first_id, second_id) = tree();
+    let mut manager = start(&mut tree);
+    manager.add_event_listener(second_id, EventType::DragEnter, prevent_default());
+    manager.add_event_listener(second_id, EventType::DragOver, prevent_default());
+
+    drag(&mut manager, &mut tree, VirtualEventType::MouseDown, (5, 5));
+    drag(&mut manager, &mut tree, VirtualEventType::MouseMove, (60, 5));
+    let events = drag(&mut manager, &mut tree, VirtualEventType::MouseUp, (60, 5));
+    assert_eq!(events, vec![(EventType::Drop, second_id), (EventType::DragEnd, first_id)]);
+}
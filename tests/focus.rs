@@ -0,0 +1,166 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::events::BasicEvent;
+use dom::traits::{TEvent, TEventManager};
+use dom::tree::DOMTree;
+use dom::types::{Closure, EventType, KeyCode, KeyModifiers, KnownAttributeName, KnownElementName};
+
+struct Fixture {
+    tree: DOMTree<BasicEvent>,
+    button_id: Id,
+    disabled_id: Id,
+    plain_id: Id,
+    indexed_id: Id,
+}
+
+// A button, a disabled input, a plain div and a div with a positive tabindex.
+fn fixture() -> Fixture {
+    let mut tree = DOMTree::default();
+    let row_id = tree.root_mut().append(div(ROW)).id();
+    let button = styled(KnownElementName::Button, BOX);
+    let disabled = with_simple_attribute(styled(KnownElementName::Input, BOX), "disabled", true);
+    let indexed = with_attribute(div(BOX), KnownAttributeName::Tabindex, "2");
+    let button_id = tree.get_mut(row_id).append(button).id();
+    let disabled_id = tree.get_mut(row_id).append(disabled).id();
+    let plain_id = tree.get_mut(row_id).append(div(BOX)).id();
+    let indexed_id = tree.get_mut(row_id).append(indexed).id();
+    Fixture {
+        tree,
+        button_id,
+        disabled_id,
+        plain_id,
+        indexed_id,
+    }
+}
+
+fn shift() -> KeyModifiers {
+    KeyModifiers {
+        shift: true,
+        ..KeyModifiers::default()
+    }
+}
+
+#[test]
+fn focus_order_skips_disabled_and_unfocusable_nodes() {
+    let Fixture {
+        mut tree,
+        button_id,
+        disabled_id,
+        plain_id,
+        indexed_id,
+    } = fixture();
+    start(&mut tree);
+
+    // Positive indices come first.
+    assert_eq!(tree.focus_order(), vec![indexed_id, button_id]);
+    assert!(!tree.get(disabled_id).is_focusable());
+    assert!(!tree.get(plain_id).is_focusable());
+}
+
+#[test]
+fn focusing_an_unfocusable_node_keeps_the_focus() {
+    let Fixture {
+        mut tree,
+        button_id,
+        disabled_id,
+        plain_id,
+        ..
+    } = fixture();
+    start(&mut tree);
+
+    tree.focus(Some(button_id));
+    assert_eq!(tree.focus(Some(plain_id)), Some(button_id));
+    assert_eq!(tree.focus(Some(disabled_id)), Some(button_id));
+    assert_eq!(tree.active_element(), Some(button_id));
+
+    assert_eq!(tree.focus(None), Some(button_id));
+    assert_eq!(tree.active_element(), None);
+}
+
+#[test]
+fn manager_focus_fires_blur_and_focus_events() {
+    let Fixture {
+        mut tree,
+        button_id,
+        plain_id,
+        indexed_id,
+        ..
+    } = fixture();
+    let mut manager = start(&mut tree);
+
+    manager.focus(Some(button_id));
+    manager.focus(Some(indexed_id));
+    let events = manager.broadcast_and_collect_events(&mut tree);
+    assert_eq!(
+        fired(&events),
+        vec![
+            (EventType::Focus, button_id),
+            (EventType::FocusIn, button_id),
+            (EventType::Blur, button_id),
+            (EventType::FocusOut, button_id),
+            (EventType::Focus, indexed_id),
+            (EventType::FocusIn, indexed_id),
+        ]
+    );
+
+    manager.focus(Some(plain_id));
+    assert!(manager.broadcast_and_collect_events(&mut tree).is_empty());
+    assert_eq!(tree.active_element(), Some(indexed_id));
+}
+
+#[test]
+fn tab_cycles_through_the_focus_order() {
+    let Fixture {
+        mut tree,
+        button_id,
+        indexed_id,
+        ..
+    } = fixture();
+    let mut manager = start(&mut tree);
+    let document_id = tree.document().id();
+
+    let events = key(&mut manager, &mut tree, KeyCode::Tab, KeyModifiers::default());
+    assert_eq!(
+        fired(&events),
+        vec![
+            (EventType::KeyDown, document_id),
+            (EventType::Focus, indexed_id),
+            (EventType::FocusIn, indexed_id),
+            (EventType::KeyUp, indexed_id),
+        ]
+    );
+
+    key(&mut manager, &mut tree, KeyCode::Tab, KeyModifiers::default());
+    assert_eq!(tree.active_element(), Some(button_id));
+    key(&mut manager, &mut tree, KeyCode::Tab, KeyModifiers::default());
+    assert_eq!(tree.active_element(), Some(indexed_id));
+
+    key(&mut manager, &mut tree, KeyCode::Tab, shift());
+    assert_eq!(tree.active_element(), Some(button_id));
+
+    // Modified tabs are left to the embedder.
+    let ctrl = KeyModifiers {
+        ctrl: true,
+        ..KeyModifiers::default()
+    };
+    key(&mut manager, &mut tree, KeyCode::Tab, ctrl);
+    assert_eq!(tree.active_element(), Some(button_id));
+}
+
+#[test]
+fn prevent_default_cancels_tab_navigation() {
+    let Fixture { mut tree, button_id, .. } = fixture();
+    let mut manager = start(&mut tree);
+    manager.focus(Some(button_id));
+    manager.broadcast_events(&mut tree);
+
+    let listener = Closure::new(|event: BasicEvent| event.prevent_default());
+    manager.add_event_listener(button_id, EventType::KeyDown, listener);
+    let events = key(&mut manager, &mut tree, KeyCode::Tab, KeyModifiers::default());
+    assert_eq!(fired(&events), vec![(EventType::KeyDown, button_id), (EventType::KeyUp, button_id)]);
+    assert_eq!(tree.active_element(), Some(button_id));
+}
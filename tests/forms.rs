@@ -0,0 +1,152 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::events::BasicEvent;
+use dom::tree::DOMTree;
+use dom::types::{EventType, KeyCode, KeyModifiers, KnownAttributeName, KnownElementName};
+
+struct Fixture {
+    tree: DOMTree<BasicEvent>,
+    form_id: Id,
+    label_id: Id,
+    checkbox_id: Id,
+    text_id: Id,
+    submit_id: Id,
+}
+
+// A form row holding a label for a checkbox, the checkbox, a text input and a submit button.
+fn fixture() -> Fixture {
+    let mut tree = DOMTree::default();
+    let form_id = tree.root_mut().append(styled(KnownElementName::Form, ROW)).id();
+
+    let label = with_simple_attribute(styled(KnownElementName::Label, BOX), "for", "agree");
+    let checkbox = styled(KnownElementName::Input, BOX);
+    let checkbox = with_attribute(checkbox, KnownAttributeName::Id, "agree");
+    let checkbox = with_simple_attribute(checkbox, "type", "checkbox");
+    let checkbox = with_simple_attribute(checkbox, "name", "agree");
+    let text = with_simple_attribute(styled(KnownElementName::Input, BOX), "name", "name");
+    let submit = styled(KnownElementName::Button, BOX);
+
+    let label_id = tree.get_mut(form_id).append(label).id();
+    let checkbox_id = tree.get_mut(form_id).append(checkbox).id();
+    let text_id = tree.get_mut(form_id).append(text).id();
+    let submit_id = tree.get_mut(form_id).append(submit).id();
+    Fixture {
+        tree,
+        form_id,
+        label_id,
+        checkbox_id,
+        text_id,
+        submit_id,
+    }
+}
+
+#[test]
+fn clicking_a_checkbox_toggles_it() {
+    let Fixture { mut tree, checkbox_id, .. } = fixture();
+    let mut manager = start(&mut tree);
+
+    let events = fired(&click(&mut manager, &mut tree, (55, 5)));
+    assert_eq!(
+        events[events.len() - 3..].to_vec(),
+        vec![
+            (EventType::Click, checkbox_id),
+            (EventType::Input, checkbox_id),
+            (EventType::Change, checkbox_id),
+        ]
+    );
+    assert!(tree.get(checkbox_id).checked());
+
+    click(&mut manager, &mut tree, (55, 5));
+    assert!(!tree.get(checkbox_id).checked());
+}
+
+#[test]
+fn label_clicks_are_forwarded_to_the_control() {
+    let Fixture {
+        mut tree,
+        label_id,
+        checkbox_id,
+        ..
+    } = fixture();
+    let mut manager = start(&mut tree);
+
+    let events = fired(&click(&mut manager, &mut tree, (5, 5)));
+    assert_eq!(
+        events[events.len() - 4..].to_vec(),
+        vec![
+            (EventType::Click, label_id),
+            (EventType::Click, checkbox_id),
+            (EventType::Input, checkbox_id),
+            (EventType::Change, checkbox_id),
+        ]
+    );
+    assert!(tree.get(checkbox_id).checked());
+}
+
+#[test]
+fn enter_commits_the_text_and_submits_the_form() {
+    let Fixture {
+        mut tree,
+        form_id,
+        checkbox_id,
+        text_id,
+        ..
+    } = fixture();
+    let mut manager = start(&mut tree);
+
+    click(&mut manager, &mut tree, (105, 5));
+    assert_eq!(tree.active_element(), Some(text_id));
+    let events = fired(&key(&mut manager, &mut tree, KeyCode::KeyA, KeyModifiers::default()));
+    assert_eq!(
+        events,
+        vec![
+            (EventType::KeyDown, text_id),
+            (EventType::Input, text_id),
+            (EventType::KeyUp, text_id),
+        ]
+    );
+    assert_eq!(tree.get(text_id).value(), "a");
+
+    let events = fired(&key(&mut manager, &mut tree, KeyCode::Enter, KeyModifiers::default()));
+    assert_eq!(
+        events,
+        vec![
+            (EventType::KeyDown, text_id),
+            (EventType::Change, text_id),
+            (EventType::Submit, form_id),
+            (EventType::KeyUp, text_id),
+        ]
+    );
+
+    tree.get_mut(checkbox_id).set_checked(true);
+    let data = vec![
+        ("agree".to_string(), "on".to_string()),
+        ("name".to_string(), "a".to_string()),
+    ];
+    assert_eq!(tree.form_data(form_id), Some(data));
+}
+
+#[test]
+fn submit_buttons_commit_pending_changes_first() {
+    let Fixture {
+        mut tree,
+        form_id,
+        text_id,
+        submit_id,
+        ..
+    } = fixture();
+    let mut manager = start(&mut tree);
+
+    click(&mut manager, &mut tree, (105, 5));
+    key(&mut manager, &mut tree, KeyCode::KeyA, KeyModifiers::default());
+    let events = fired(&click(&mut manager, &mut tree, (155, 5)));
+
+    // Focus moves to the button first, which commits the change.
+    let position = |event| events.iter().position(|&v| v == event).unwrap();
+    assert!(position((EventType::Change, text_id)) < position((EventType::Blur, text_id)));
+    assert!(position((EventType::Click, submit_id)) < position((EventType::Submit, form_id)));
+}
@@ -0,0 +1,100 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::events::{headless_channel, raw_id, MouseButton};
+use dom::mutation::DOMMutation;
+use dom::traits::{TEvent, TEventManager};
+use dom::tree::DOMTree;
+use dom::types::{Closure, EventType, KnownElementName, VirtualEventType};
+use std::thread;
+
+#[test]
+fn remote_listeners_answer_with_mutations() {
+    let mut tree = DOMTree::default();
+    let row_id = tree.root_mut().append(div(ROW)).id();
+    let button_id = tree.get_mut(row_id).append(styled(KnownElementName::Button, BOX)).id();
+    let second_id = tree.get_mut(row_id).append(div(BOX)).id();
+    let mut manager = start(&mut tree);
+    let (bridge, remote) = headless_channel();
+
+    manager.add_event_listener(button_id, EventType::Click, Closure::headless(7));
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, MouseButton::Main, (5, 5), 0);
+    click(&mut manager, &mut tree, (5, 5));
+    assert_eq!(tree.active_element(), Some(button_id));
+    assert_eq!(manager.hovered(), Some(button_id));
+
+    assert!(bridge.flush(manager.headless_queue()));
+    let invocations = remote.try_recv().unwrap();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(invocations[0].0, 7);
+    assert_eq!(invocations[0].1.ty(), EventType::Click);
+    assert_eq!(invocations[0].1.target(), raw_id(button_id));
+
+    // The remote side removes the button it was told about.
+    let node = invocations[0].1.target();
+    assert!(remote.send(vec![DOMMutation::Remove { node }]));
+    assert_eq!(bridge.apply_mutations(&mut tree), vec![Ok(None)]);
+    assert!(!tree.is_connected(button_id));
+    assert_eq!(tree.active_element(), None);
+
+    // The removed node isn't hovered anymore, so it gets no out or leave events.
+    tree.layout_viewport();
+    let root_id = tree.root().id();
+    let events = mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, MouseButton::Main, (5, 5), 0);
+    assert_eq!(manager.hovered(), Some(second_id));
+    assert_eq!(
+        fired(&events),
+        vec![
+            (EventType::MouseOver, second_id),
+            (EventType::MouseEnter, root_id),
+            (EventType::MouseEnter, row_id),
+            (EventType::MouseEnter, second_id),
+            (EventType::MouseMove, second_id),
+        ]
+    );
+}
+
+#[test]
+fn detaching_directly_clears_the_manager_targets() {
+    let (mut tree, _, first_id, _) = row();
+    let mut manager = start(&mut tree);
+
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, MouseButton::Main, (5, 5), 0);
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseDown, MouseButton::Main, (5, 5), 0);
+    assert!(tree.get(first_id).element_state().active);
+
+    tree.get_mut(first_id).detach();
+    manager.broadcast_events(&mut tree);
+    assert_eq!(manager.hovered(), None);
+
+    // The press was forgotten, so releasing elsewhere doesn't click.
+    tree.layout_viewport();
+    let events = mouse(&mut manager, &mut tree, VirtualEventType::MouseUp, MouseButton::Main, (5, 5), 0);
+    assert!(fired(&events).iter().all(|&(ty, _)| ty != EventType::Click));
+}
+
+#[test]
+fn remotes_run_on_another_thread() {
+    let (mut tree, _, first_id, _) = row();
+    let mut manager = start(&mut tree);
+    let (bridge, remote) = headless_channel();
+    manager.add_event_listener(first_id, EventType::Click, Closure::headless(1));
+
+    let handle = thread::spawn(move || {
+        let invocations = remote.recv().unwrap();
+        let mutations = invocations
+            .into_iter()
+            .map(|(_, event)| DOMMutation::Remove { node: event.target() })
+            .collect();
+        assert!(remote.send(mutations));
+    });
+
+    click(&mut manager, &mut tree, (5, 5));
+    assert!(bridge.flush(manager.headless_queue()));
+    handle.join().unwrap();
+    assert_eq!(bridge.apply_mutations(&mut tree), vec![Ok(None)]);
+    assert!(!tree.is_connected(first_id));
+}
@@ -0,0 +1,83 @@
+extern crate dom;
+extern crate jss;
+extern crate serde_json;
+
+mod common;
+
+use common::element;
+use dom::events::BasicEvent;
+use dom::layout::MonospaceTextMeasurer;
+use dom::node::{DOMAttributeValue, DOMNode};
+use dom::style::StyleSheet;
+use dom::tree::DOMTree;
+use dom::types::{KnownAttributeName, KnownElementName, TextFont};
+use serde_json::Value;
+
+const SHEET: &str = r#"{
+    "#outer": { "color": "red", "width": "100px", "font-size": "20px", "line-height": 1.5 },
+    ".small": { "font-size": "50%" },
+    ".initial": { "color": "initial" },
+    ".inherit": { "width": "inherit" },
+    ".unset": { "color": "unset", "width": "unset" }
+}"#;
+
+fn tree() -> DOMTree<BasicEvent> {
+    let mut tree = DOMTree::default();
+    let font = TextFont {
+        family: None,
+        size: 10.0,
+        line_height: None,
+    };
+    tree.set_text_measurer(MonospaceTextMeasurer::default(), font);
+    tree.add_stylesheet(StyleSheet::parse_from_str(SHEET).unwrap());
+    tree
+}
+
+#[test]
+fn keywords_resolve_against_the_parent() {
+    let mut tree = tree();
+    let outer_id = tree.root_mut().append(element(KnownElementName::Div, KnownAttributeName::Id, "outer")).id();
+    let initial = element(KnownElementName::Div, KnownAttributeName::Class, "initial");
+    let initial_id = tree.get_mut(outer_id).append(initial).id();
+    let inherit = element(KnownElementName::Div, KnownAttributeName::Class, "inherit");
+    let inherit_id = tree.get_mut(outer_id).append(inherit).id();
+    let unset = element(KnownElementName::Div, KnownAttributeName::Class, "unset");
+    let unset_id = tree.get_mut(outer_id).append(unset).id();
+    tree.generate_layout_tree();
+    tree.resize(200.0, 100.0);
+
+    let color = |id| tree.get(id).computed_declarations().get("color").cloned();
+    let red = Some(Value::from("red"));
+    assert_eq!(color(initial_id), None);
+    assert_eq!(color(inherit_id), red);
+    assert_eq!(color(unset_id), red);
+
+    // Non-inherited properties are only taken from the parent when asked to.
+    assert_eq!(tree.client_rect(inherit_id).size.width, 100.0);
+    assert_eq!(tree.client_rect(unset_id).size.width, 100.0);
+    assert!(tree.get(unset_id).computed_declarations().get("width").is_none());
+}
+
+#[test]
+fn fonts_inherit_into_text_nodes() {
+    let mut tree = tree();
+    let outer_id = tree.root_mut().append(element(KnownElementName::Div, KnownAttributeName::Id, "outer")).id();
+    let outer_text_id = tree.get_mut(outer_id).append(DOMNode::from("hello")).id();
+    let small = element(KnownElementName::Div, KnownAttributeName::Class, "small");
+    let small_id = tree.get_mut(outer_id).append(small).id();
+    let small_text_id = tree.get_mut(small_id).append(DOMNode::from("hello")).id();
+    tree.generate_layout_tree();
+    tree.resize(200.0, 100.0);
+
+    // Unitless line heights are inherited as such, and scale with the font size of the child.
+    assert_eq!(tree.client_rect(outer_text_id).size.height, 30.0);
+    assert_eq!(tree.client_rect(small_text_id).size.height, 15.0);
+    let font_size = tree.get(small_text_id).computed_declarations().get("font-size").cloned();
+    assert_eq!(font_size, Some(Value::from("10px")));
+
+    // Changes of the parent reach the text nodes on the next relayout.
+    tree.get_mut(small_id)
+        .set_attribute(KnownAttributeName::Class, DOMAttributeValue::from("other"));
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(small_text_id).size.height, 30.0);
+}
@@ -0,0 +1,87 @@
+extern crate dom;
+
+use dom::events::{BasicEvent, KeyboardEvent, MouseEvent};
+use dom::traits::TKeyboardEvent;
+use dom::types::{EventType, KeyCode, KeyModifier, KeyModifiers, KeyboardLayout};
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> &'static str {
+    KeyboardLayout::UsQwerty.key(code, &modifiers)
+}
+
+fn modifiers(modifier: KeyModifier) -> KeyModifiers {
+    let mut modifiers = KeyModifiers::default();
+    modifiers.set(modifier, true);
+    modifiers
+}
+
+#[test]
+fn letters_follow_shift_and_caps_lock() {
+    let mut both = modifiers(KeyModifier::Shift);
+    both.caps_lock = true;
+
+    assert_eq!(key(KeyCode::KeyA, KeyModifiers::default()), "a");
+    assert_eq!(key(KeyCode::KeyA, modifiers(KeyModifier::Shift)), "A");
+    assert_eq!(key(KeyCode::KeyZ, modifiers(KeyModifier::CapsLock)), "Z");
+    assert_eq!(key(KeyCode::KeyZ, both), "z");
+}
+
+#[test]
+fn shift_changes_digits_and_punctuation() {
+    let shift = modifiers(KeyModifier::Shift);
+
+    assert_eq!(key(KeyCode::Digit1, KeyModifiers::default()), "1");
+    assert_eq!(key(KeyCode::Digit1, shift), "!");
+    assert_eq!(key(KeyCode::Digit0, shift), ")");
+    assert_eq!(key(KeyCode::Slash, KeyModifiers::default()), "/");
+    assert_eq!(key(KeyCode::Slash, shift), "?");
+    assert_eq!(key(KeyCode::Quote, shift), "\"");
+
+    // Caps lock only applies to letters.
+    assert_eq!(key(KeyCode::Digit2, modifiers(KeyModifier::CapsLock)), "2");
+    assert_eq!(key(KeyCode::Semicolon, modifiers(KeyModifier::CapsLock)), ";");
+}
+
+#[test]
+fn numpad_keys_depend_on_num_lock() {
+    let num_lock = modifiers(KeyModifier::NumLock);
+
+    assert_eq!(key(KeyCode::Numpad7, num_lock), "7");
+    assert_eq!(key(KeyCode::Numpad7, KeyModifiers::default()), "Home");
+    assert_eq!(key(KeyCode::Numpad5, num_lock), "5");
+    assert_eq!(key(KeyCode::Numpad5, KeyModifiers::default()), "Clear");
+    assert_eq!(key(KeyCode::NumpadDecimal, num_lock), ".");
+    assert_eq!(key(KeyCode::NumpadDecimal, KeyModifiers::default()), "Delete");
+
+    // Operators are the same either way.
+    assert_eq!(key(KeyCode::NumpadAdd, num_lock), "+");
+    assert_eq!(key(KeyCode::NumpadAdd, KeyModifiers::default()), "+");
+}
+
+#[test]
+fn modifier_state_is_read_for_each_modifier() {
+    let all = [
+        KeyModifier::Alt,
+        KeyModifier::CapsLock,
+        KeyModifier::Control,
+        KeyModifier::Meta,
+        KeyModifier::NumLock,
+        KeyModifier::ScrollLock,
+        KeyModifier::Shift,
+    ];
+
+    for &modifier in &all {
+        let keyboard = KeyboardEvent::new(KeyCode::KeyA, modifiers(modifier), false);
+        let event = BasicEvent::new(EventType::KeyDown, 0, keyboard, MouseEvent::default());
+        for &other in &all {
+            assert_eq!(event.get_modifier_state(other), other == modifier, "{:?} with {:?}", other, modifier);
+        }
+    }
+}
+
+#[test]
+fn modifier_keys_map_to_their_modifier() {
+    assert_eq!(KeyCode::AltRight.modifier(), Some(KeyModifier::Alt));
+    assert_eq!(key(KeyCode::AltRight, KeyModifiers::default()), "Alt");
+    assert_eq!(KeyCode::ShiftLeft.modifier(), Some(KeyModifier::Shift));
+    assert_eq!(KeyCode::KeyA.modifier(), None);
+}
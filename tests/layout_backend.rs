@@ -0,0 +1,201 @@
+// Trees can run on any layout backend, here one recording what the tree asks of it.
+
+extern crate dom;
+#[macro_use]
+extern crate serde_json;
+extern crate yoga;
+
+use dom::events::{BasicEvent, EventManager};
+use dom::layout::TextMeasure;
+use dom::node::{DOMNode, DOMTagName};
+use dom::traits::{TDOMNode, TLayoutNode};
+use dom::tree::DOMTree;
+use dom::types::{Align, ClientEdges, Closure, FlexStyle, KnownElementName, Layout, StyleUnit};
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(Debug, Default, PartialEq)]
+struct RecordingLayoutNode {
+    children: u32,
+    styled: u32,
+    reflows: Vec<(u32, u32)>,
+    tainted: bool,
+    tainted_descendants: bool,
+}
+
+impl TLayoutNode for RecordingLayoutNode {
+    type TextMeasureMetadata = TextMeasure;
+    type ReflowDirection = ();
+
+    fn is_tainted(&self) -> bool {
+        self.tainted
+    }
+
+    fn has_tainted_descendants(&self) -> bool {
+        self.tainted_descendants
+    }
+
+    fn taint(&mut self) {
+        self.tainted = true;
+    }
+
+    fn taint_descendants(&mut self) {
+        self.tainted_descendants = true;
+    }
+
+    fn untaint(&mut self) {
+        self.tainted = false;
+        self.tainted_descendants = false;
+    }
+
+    fn insert_child(&mut self, _: &mut Self, _: u32) {
+        self.children += 1;
+    }
+
+    fn append_child(&mut self, _: &mut Self) {
+        self.children += 1;
+    }
+
+    fn remove_child(&mut self, _: &mut Self) {
+        self.children -= 1;
+    }
+
+    fn reflow_subtree(&mut self, width: u32, height: u32, _: ()) {
+        self.reflows.push((width, height));
+    }
+
+    fn child_count(&self) -> u32 {
+        self.children
+    }
+
+    fn get_layout(&self) -> Layout {
+        Layout::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.0)
+    }
+
+    fn get_padding(&self) -> ClientEdges {
+        ClientEdges::default()
+    }
+
+    fn get_border(&self) -> ClientEdges {
+        ClientEdges::default()
+    }
+
+    fn get_margin(&self) -> ClientEdges {
+        ClientEdges::default()
+    }
+
+    fn reset(&mut self) {
+        *self = RecordingLayoutNode::default();
+    }
+
+    fn apply_styles(&mut self, _: &[FlexStyle]) {
+        self.styled += 1;
+    }
+
+    fn set_text_measure(&mut self, _: Option<TextMeasure>) {}
+}
+
+type RecordingTree = DOMTree<BasicEvent, RecordingLayoutNode>;
+
+fn div() -> DOMNode<BasicEvent, RecordingLayoutNode> {
+    DOMNode::from(DOMTagName::from(KnownElementName::Div))
+}
+
+#[test]
+fn builds_and_reflows_through_the_backend() {
+    let mut tree = RecordingTree::default();
+    let parent_id = tree.root_mut().append(div()).id();
+    tree.get_mut(parent_id).append(div());
+    let child_id = tree.get_mut(parent_id).append(div()).id();
+
+    tree.generate_layout_tree();
+    tree.root_mut().reflow_subtree(100, 50, ());
+
+    assert_eq!(tree.root().layout_node.child_count(), 1);
+    assert_eq!(tree.get(parent_id).layout_node.child_count(), 2);
+    assert_eq!(tree.get(child_id).layout_node.styled, 1);
+    assert_eq!(tree.root().layout_node.reflows, vec![(100, 50)]);
+}
+
+#[test]
+fn relayout_only_restyles_dirty_nodes() {
+    let mut tree = RecordingTree::default();
+    let first_id = tree.root_mut().append(div()).id();
+    let second_id = tree.root_mut().append(div()).id();
+    tree.generate_layout_tree();
+
+    tree.get_mut(first_id).detach();
+    tree.get_mut(second_id).mark_dirty();
+    tree.relayout(200, 100, ());
+
+    assert_eq!(tree.root().layout_node.child_count(), 1);
+    assert_eq!(tree.root().layout_node.reflows, vec![(200, 100)]);
+    assert_eq!(tree.get(second_id).layout_node.styled, 2);
+}
+
+#[test]
+fn regenerating_the_layout_tree_keeps_children_once() {
+    let mut tree = RecordingTree::default();
+    let parent_id = tree.root_mut().append(div()).id();
+    tree.get_mut(parent_id).append(div());
+    tree.generate_layout_tree();
+
+    let child_id = tree.alloc(div());
+    tree.get_mut(parent_id).append_with_layout(child_id).unwrap();
+    tree.generate_layout_tree();
+    tree.generate_layout_tree();
+
+    assert_eq!(tree.root().layout_node.child_count(), 1);
+    assert_eq!(tree.get(parent_id).layout_node.child_count(), 2);
+}
+
+#[test]
+fn moved_children_leave_their_previous_layout_parent() {
+    let mut tree = RecordingTree::default();
+    let first_id = tree.root_mut().append(div()).id();
+    let second_id = tree.root_mut().append(div()).id();
+    let child_id = tree.get_mut(first_id).append(div()).id();
+    tree.generate_layout_tree();
+
+    tree.get_mut(child_id).detach();
+    tree.get_mut(second_id).append_with_layout(child_id).unwrap();
+    assert_eq!(tree.get(first_id).layout_node.child_count(), 0);
+    assert_eq!(tree.get(second_id).layout_node.child_count(), 1);
+
+    tree.relayout(200, 100, ());
+    assert_eq!(tree.get(first_id).layout_node.child_count(), 0);
+    assert_eq!(tree.get(second_id).layout_node.child_count(), 1);
+    assert_eq!(tree.root().layout_node.child_count(), 2);
+}
+
+#[test]
+fn flex_styles_convert_to_and_from_yoga() {
+    let styles = [
+        FlexStyle::Width(StyleUnit::Point(10.0)),
+        FlexStyle::MarginLeft(StyleUnit::Percent(50.0)),
+        FlexStyle::AlignItems(Align::FlexStart),
+        FlexStyle::FlexGrow(2.0),
+    ];
+    for &style in &styles {
+        assert_eq!(FlexStyle::from(yoga::FlexStyle::from(style)), style);
+    }
+    assert_eq!(yoga::FlexStyle::from(styles[3]), yoga::FlexStyle::FlexGrow(2.0f32.into()));
+
+    let layout = Layout::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+    assert_eq!(Layout::from(yoga::Layout::from(layout)), layout);
+}
+
+#[test]
+fn event_managers_dispatch_through_any_backend() {
+    let mut tree = RecordingTree::default();
+    let parent_id = tree.root_mut().append(div()).id();
+    let child_id = tree.get_mut(parent_id).append(div()).id();
+
+    let reached = Rc::new(Cell::new(false));
+    let flag = reached.clone();
+    let mut manager = EventManager::<RecordingLayoutNode>::default();
+    manager.add_custom_event_listener(parent_id, "ping", Closure::new(move |_: BasicEvent| flag.set(true)));
+
+    assert!(manager.dispatch_custom_event(&tree, child_id, "ping", json!(null)));
+    assert!(reached.get());
+}
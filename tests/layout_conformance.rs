@@ -0,0 +1,257 @@
+// Runs the same layouts through every layout backend, they're expected to agree to the pixel.
+
+extern crate dom;
+
+use dom::layout::{MonospaceTextMeasurer, TextMeasure, TextMeasurement};
+use dom::traits::TLayoutNode;
+use dom::types::{
+    Align, Direction, Display, FlexDirection, FlexStyle, Justify, PositionType, StyleUnit, TextFont, Wrap,
+};
+use std::rc::Rc;
+
+trait Backend: TLayoutNode<ReflowDirection = Direction, TextMeasureMetadata = TextMeasure> {}
+
+impl<L> Backend for L where L: TLayoutNode<ReflowDirection = Direction, TextMeasureMetadata = TextMeasure> {}
+
+type Rect = (f32, f32, f32, f32);
+
+fn points(value: f32) -> StyleUnit {
+    StyleUnit::Point(value)
+}
+
+fn percent(value: f32) -> StyleUnit {
+    StyleUnit::Percent(value)
+}
+
+fn sized(width: f32, height: f32) -> Vec<FlexStyle> {
+    vec![FlexStyle::Width(points(width)), FlexStyle::Height(points(height))]
+}
+
+fn node<L: Backend>(styles: &[FlexStyle]) -> L {
+    let mut node = L::default();
+    node.apply_styles(styles);
+    node
+}
+
+fn rect<L: Backend>(node: &L) -> Rect {
+    let layout = node.get_layout();
+    (layout.left(), layout.top(), layout.width(), layout.height())
+}
+
+// Lays out a root with the given children, returning the (left, top, width, height) of every child.
+fn layout<L: Backend>(root: &[FlexStyle], children: &[Vec<FlexStyle>], size: (u32, u32)) -> Vec<Rect> {
+    let mut root = node::<L>(root);
+    let mut children = children.iter().map(|styles| node::<L>(styles)).collect::<Vec<_>>();
+    for child in &mut children {
+        root.append_child(child);
+    }
+    root.reflow_subtree(size.0, size.1, Direction::LTR);
+    children.iter().map(rect).collect()
+}
+
+fn column_stretches_children<L: Backend>() {
+    let children = [vec![FlexStyle::Height(points(10.0))], vec![FlexStyle::Height(points(20.0))]];
+    let layouts = layout::<L>(&[], &children, (100, 100));
+    assert_eq!(layouts, vec![(0.0, 0.0, 100.0, 10.0), (0.0, 10.0, 100.0, 20.0)]);
+}
+
+fn row_justify_space_between<L: Backend>() {
+    let root = [
+        FlexStyle::FlexDirection(FlexDirection::Row),
+        FlexStyle::JustifyContent(Justify::SpaceBetween),
+    ];
+    let children = [sized(10.0, 10.0), sized(10.0, 10.0), sized(10.0, 10.0)];
+    let layouts = layout::<L>(&root, &children, (100, 100));
+    assert_eq!(
+        layouts,
+        vec![(0.0, 0.0, 10.0, 10.0), (45.0, 0.0, 10.0, 10.0), (90.0, 0.0, 10.0, 10.0)]
+    );
+}
+
+fn row_reverse<L: Backend>() {
+    let root = [FlexStyle::FlexDirection(FlexDirection::RowReverse)];
+    let children = [sized(10.0, 10.0), sized(20.0, 10.0)];
+    let layouts = layout::<L>(&root, &children, (100, 100));
+    assert_eq!(layouts, vec![(90.0, 0.0, 10.0, 10.0), (70.0, 0.0, 20.0, 10.0)]);
+}
+
+fn flex_grow_distributes_free_space<L: Backend>() {
+    let root = [FlexStyle::FlexDirection(FlexDirection::Row)];
+    let children = [
+        vec![FlexStyle::FlexGrow(1.0)],
+        vec![FlexStyle::FlexGrow(2.0)],
+    ];
+    let layouts = layout::<L>(&root, &children, (90, 50));
+    assert_eq!(layouts, vec![(0.0, 0.0, 30.0, 50.0), (30.0, 0.0, 60.0, 50.0)]);
+}
+
+fn flex_shrink_scales_with_basis<L: Backend>() {
+    let root = [FlexStyle::FlexDirection(FlexDirection::Row)];
+    let child = vec![FlexStyle::Width(points(80.0)), FlexStyle::FlexShrink(1.0)];
+    let layouts = layout::<L>(&root, &[child.clone(), child], (100, 100));
+    assert_eq!(layouts, vec![(0.0, 0.0, 50.0, 100.0), (50.0, 0.0, 50.0, 100.0)]);
+}
+
+fn max_width_limits_growth<L: Backend>() {
+    let root = [FlexStyle::FlexDirection(FlexDirection::Row)];
+    let children = [vec![FlexStyle::FlexGrow(1.0), FlexStyle::MaxWidth(points(30.0))]];
+    let layouts = layout::<L>(&root, &children, (100, 100));
+    assert_eq!(layouts, vec![(0.0, 0.0, 30.0, 100.0)]);
+}
+
+fn percentages_resolve_against_parent<L: Backend>() {
+    let children = [vec![FlexStyle::Width(percent(50.0)), FlexStyle::Height(percent(25.0))]];
+    let layouts = layout::<L>(&[], &children, (200, 100));
+    assert_eq!(layouts, vec![(0.0, 0.0, 100.0, 25.0)]);
+}
+
+fn padding_and_margin_offset_children<L: Backend>() {
+    let root = [FlexStyle::Padding(points(10.0))];
+    let mut child = sized(20.0, 20.0);
+    child.push(FlexStyle::Margin(points(5.0)));
+    let layouts = layout::<L>(&root, &[child], (100, 100));
+    assert_eq!(layouts, vec![(15.0, 15.0, 20.0, 20.0)]);
+}
+
+fn align_items_center<L: Backend>() {
+    let root = [
+        FlexStyle::FlexDirection(FlexDirection::Row),
+        FlexStyle::AlignItems(Align::Center),
+    ];
+    let layouts = layout::<L>(&root, &[sized(20.0, 20.0)], (100, 100));
+    assert_eq!(layouts, vec![(0.0, 40.0, 20.0, 20.0)]);
+}
+
+fn align_self_overrides_align_items<L: Backend>() {
+    let root = [FlexStyle::FlexDirection(FlexDirection::Row)];
+    let mut child = sized(20.0, 20.0);
+    child.push(FlexStyle::AlignSelf(Align::FlexEnd));
+    let layouts = layout::<L>(&root, &[child], (100, 100));
+    assert_eq!(layouts, vec![(0.0, 80.0, 20.0, 20.0)]);
+}
+
+fn wrap_breaks_lines<L: Backend>() {
+    let root = [
+        FlexStyle::FlexDirection(FlexDirection::Row),
+        FlexStyle::FlexWrap(Wrap::Wrap),
+    ];
+    let children = [sized(40.0, 10.0), sized(40.0, 10.0), sized(40.0, 10.0)];
+    let layouts = layout::<L>(&root, &children, (100, 100));
+    assert_eq!(
+        layouts,
+        vec![(0.0, 0.0, 40.0, 10.0), (40.0, 0.0, 40.0, 10.0), (0.0, 10.0, 40.0, 10.0)]
+    );
+}
+
+fn absolute_position_from_edges<L: Backend>() {
+    let mut child = sized(20.0, 20.0);
+    child.push(FlexStyle::Position(PositionType::Absolute));
+    child.push(FlexStyle::Right(points(10.0)));
+    child.push(FlexStyle::Bottom(points(10.0)));
+    let layouts = layout::<L>(&[], &[child], (100, 100));
+    assert_eq!(layouts, vec![(70.0, 70.0, 20.0, 20.0)]);
+}
+
+fn absolute_size_from_insets<L: Backend>() {
+    let child = vec![
+        FlexStyle::Position(PositionType::Absolute),
+        FlexStyle::Left(points(10.0)),
+        FlexStyle::Right(points(20.0)),
+        FlexStyle::Top(points(5.0)),
+        FlexStyle::Height(points(10.0)),
+    ];
+    let layouts = layout::<L>(&[], &[child], (100, 100));
+    assert_eq!(layouts, vec![(10.0, 5.0, 70.0, 10.0)]);
+}
+
+fn display_none_takes_no_space<L: Backend>() {
+    let root = [FlexStyle::FlexDirection(FlexDirection::Row)];
+    let mut hidden = sized(50.0, 10.0);
+    hidden.push(FlexStyle::Display(Display::None));
+    let layouts = layout::<L>(&root, &[hidden, sized(20.0, 10.0)], (100, 100));
+    assert_eq!(layouts, vec![(0.0, 0.0, 0.0, 0.0), (0.0, 0.0, 20.0, 10.0)]);
+}
+
+fn fractional_sizes_snap_without_gaps<L: Backend>() {
+    let root = [FlexStyle::FlexDirection(FlexDirection::Row)];
+    let children = [sized(10.3, 10.0), sized(10.3, 10.0), sized(10.3, 10.0)];
+    let layouts = layout::<L>(&root, &children, (100, 100));
+    assert_eq!(
+        layouts,
+        vec![(0.0, 0.0, 10.0, 10.0), (10.0, 0.0, 11.0, 10.0), (21.0, 0.0, 10.0, 10.0)]
+    );
+}
+
+fn content_sizes_parent<L: Backend>() {
+    let mut root = node::<L>(&[]);
+    let mut parent = node::<L>(&[FlexStyle::AlignSelf(Align::FlexStart), FlexStyle::Padding(points(5.0))]);
+    let mut child = node::<L>(&sized(30.0, 20.0));
+    parent.append_child(&mut child);
+    root.append_child(&mut parent);
+    root.reflow_subtree(100, 100, Direction::LTR);
+    assert_eq!(rect(&parent), (0.0, 0.0, 40.0, 30.0));
+    assert_eq!(rect(&child), (5.0, 5.0, 30.0, 20.0));
+}
+
+fn text_wraps_to_width<L: Backend>() {
+    let measurement = TextMeasurement {
+        measurer: Rc::new(MonospaceTextMeasurer::default()),
+        font: TextFont {
+            family: None,
+            size: 10.0,
+            line_height: Some(12.0),
+        },
+    };
+    let mut root = node::<L>(&[]);
+    let mut text = node::<L>(&[]);
+    text.set_text_measure(Some(TextMeasure {
+        measurement,
+        text: "hello world".to_string(),
+    }));
+    root.append_child(&mut text);
+    root.reflow_subtree(40, 100, Direction::LTR);
+    assert_eq!(rect(&text), (0.0, 0.0, 40.0, 24.0));
+}
+
+macro_rules! conformance_tests {
+    ($($name:ident),*) => {
+        #[cfg(feature = "yoga-layout")]
+        mod yoga_backend {
+            $(
+                #[test]
+                fn $name() {
+                    super::$name::<::dom::layout::YogaLayoutNode>();
+                }
+            )*
+        }
+
+        mod flex_backend {
+            $(
+                #[test]
+                fn $name() {
+                    super::$name::<::dom::layout::FlexLayoutNode>();
+                }
+            )*
+        }
+    };
+}
+
+conformance_tests!(
+    column_stretches_children,
+    row_justify_space_between,
+    row_reverse,
+    flex_grow_distributes_free_space,
+    flex_shrink_scales_with_basis,
+    max_width_limits_growth,
+    percentages_resolve_against_parent,
+    padding_and_margin_offset_children,
+    align_items_center,
+    align_self_overrides_align_items,
+    wrap_breaks_lines,
+    absolute_position_from_edges,
+    absolute_size_from_insets,
+    display_none_takes_no_space,
+    fractional_sizes_snap_without_gaps,
+    content_sizes_parent,
+    text_wraps_to_width
+);
@@ -0,0 +1,55 @@
+extern crate dom;
+extern crate jss;
+extern crate serde_json;
+
+mod common;
+
+use common::*;
+use dom::events::raw_id;
+use dom::snapshot::LayoutSnapshot;
+use dom::tree::DOMTree;
+use dom::types::KnownElementName;
+
+const NONE: &str = "padding(0 0 0 0) border(0 0 0 0) margin(0 0 0 0)";
+
+#[test]
+fn snapshots_print_one_line_per_node() {
+    let (mut tree, row_id, first_id, second_id) = row();
+    let root_id = tree.root().id();
+    start(&mut tree);
+
+    let golden = vec![
+        format!("Fragment #{} (0, 0, 200x100) {}", raw_id(root_id), NONE),
+        format!("  Div #{} (0, 0, 200x50) {}", raw_id(row_id), NONE),
+        format!("    Div #{} (0, 0, 50x50) {}", raw_id(first_id), NONE),
+        format!("    Div #{} (50, 0, 50x50) {}", raw_id(second_id), NONE),
+    ];
+    let snapshot = tree.layout_snapshot();
+    assert_eq!(snapshot.to_string(), golden.join("\n") + "\n");
+
+    let json = snapshot.to_json();
+    assert_eq!(serde_json::from_str::<LayoutSnapshot>(&json).unwrap(), snapshot);
+}
+
+#[test]
+fn snapshots_follow_scroll_offsets() {
+    let mut tree = DOMTree::default();
+    let scroll = styled(KnownElementName::ScrollView, r#"{ "width": "100px", "height": "50px" }"#);
+    let scroll_id = tree.root_mut().append(scroll).id();
+    let item = r#"{ "width": "100px", "height": "100px" }"#;
+    let first_id = tree.get_mut(scroll_id).append(div(item)).id();
+    let second_id = tree.get_mut(scroll_id).append(div(item)).id();
+    start(&mut tree);
+    tree.get_mut(scroll_id).scroll_to(0.0, 30.0);
+
+    // The scroll container stays in place, its contents move up.
+    let golden = vec![
+        format!("ScrollView #{} (0, 0, 100x50) {}", raw_id(scroll_id), NONE),
+        format!("  Div #{} (0, -30, 100x100) {}", raw_id(first_id), NONE),
+        format!("  Div #{} (0, 70, 100x100) {}", raw_id(second_id), NONE),
+    ];
+    assert_eq!(tree.layout_snapshot_of(scroll_id).to_string(), golden.join("\n") + "\n");
+
+    let snapshot = tree.layout_snapshot_of(second_id);
+    assert_eq!(snapshot.rect, tree.client_rect(second_id));
+}
@@ -0,0 +1,118 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::{element, Id};
+use dom::events::BasicEvent;
+use dom::style::{MediaQueryList, StyleSheet, StyleSheetError};
+use dom::tree::DOMTree;
+use dom::types::{ColorScheme, KnownAttributeName, KnownElementName, Viewport};
+use std::collections::HashSet;
+
+const SHEET: &str = r#"{
+    ".item": { "width": "100px", "height": "10px" },
+    "@media (min-width: 600px)": {
+        ".item": { "width": "200px" },
+        "@media (prefers-color-scheme: dark)": { ".item": { "width": "300px" } }
+    },
+    "@media screen and (orientation: portrait), (max-width: 300px)": { ".item": { "height": "20px" } },
+    ".other": { "width": "10px", "height": "10px" },
+    "@media (min-resolution: 2dppx)": { ".other": { "width": "20px" } }
+}"#;
+
+fn tree() -> (DOMTree<BasicEvent>, Id, Id) {
+    let mut tree = DOMTree::default();
+    tree.add_stylesheet(StyleSheet::parse_from_str(SHEET).unwrap());
+    let div = |class| element(KnownElementName::Div, KnownAttributeName::Class, class);
+    let item_id = tree.root_mut().append(div("item")).id();
+    let other_id = tree.root_mut().append(div("other")).id();
+    tree.generate_layout_tree();
+    tree.resize(400.0, 300.0);
+    (tree, item_id, other_id)
+}
+
+#[test]
+fn rules_follow_the_viewport() {
+    let (mut tree, item_id, _) = tree();
+    let size = |tree: &DOMTree<BasicEvent>| {
+        let size = tree.client_rect(item_id).size;
+        (size.width, size.height)
+    };
+    assert_eq!(size(&tree), (100.0, 10.0));
+
+    tree.resize(800.0, 300.0);
+    assert_eq!(size(&tree), (200.0, 10.0));
+
+    let dark = Viewport {
+        color_scheme: ColorScheme::Dark,
+        ..tree.viewport()
+    };
+    tree.set_viewport(dark);
+    assert_eq!(size(&tree), (300.0, 10.0));
+
+    tree.resize(400.0, 800.0);
+    assert_eq!(size(&tree), (100.0, 20.0));
+}
+
+#[test]
+fn only_flipped_rules_restyle() {
+    let (mut tree, _, other_id) = tree();
+
+    let viewport = Viewport {
+        device_pixel_ratio: 1.5,
+        ..tree.viewport()
+    };
+    assert_eq!(tree.set_viewport(viewport), vec![]);
+
+    let viewport = Viewport {
+        device_pixel_ratio: 2.0,
+        ..tree.viewport()
+    };
+    let changed = tree.set_viewport(viewport).into_iter().collect::<HashSet<_>>();
+    assert!(changed.contains(&other_id));
+    assert_eq!(tree.client_rect(other_id).size.width, 20.0);
+}
+
+#[test]
+fn properties_only_set_by_a_media_rule_are_dropped_when_it_stops_matching() {
+    let sheet = r#"{
+        ".item": { "width": "100px", "height": "10px" },
+        "@media (max-width: 300px)": { ".item": { "margin-top": "5px" } }
+    }"#;
+    let mut tree: DOMTree<BasicEvent> = DOMTree::default();
+    tree.add_stylesheet(StyleSheet::parse_from_str(sheet).unwrap());
+    let item = element(KnownElementName::Div, KnownAttributeName::Class, "item");
+    let item_id = tree.root_mut().append(item).id();
+    tree.generate_layout_tree();
+
+    tree.resize(200.0, 300.0);
+    assert_eq!(tree.client_rect(item_id).position.top, 5.0);
+    tree.resize(400.0, 300.0);
+    assert_eq!(tree.client_rect(item_id).position.top, 0.0);
+}
+
+#[test]
+fn media_query_syntax() {
+    let matches = |query: &str, width: f32, height: f32| {
+        let viewport = Viewport {
+            device_pixel_ratio: 2.0,
+            ..Viewport::new(width, height)
+        };
+        MediaQueryList::parse(query).unwrap().matches(&viewport)
+    };
+    assert!(matches("screen and (400px <= width < 800px)", 500.0, 100.0));
+    assert!(!matches("screen and (400px <= width < 800px)", 800.0, 100.0));
+    assert!(matches("(height > 50px) and (min-width: 40em)", 640.0, 100.0));
+    assert!(!matches("only screen and (min-width: 40em)", 639.0, 100.0));
+    assert!(matches("(resolution: 192dpi)", 10.0, 10.0));
+    assert!(matches("print, (orientation: landscape)", 20.0, 10.0));
+    assert!(!matches("print", 20.0, 10.0));
+    assert!(matches("not print", 20.0, 10.0));
+    assert!(matches("", 20.0, 10.0));
+
+    let error = MediaQueryList::parse("(min-width 600px)");
+    assert_eq!(error, Err(StyleSheetError::InvalidMediaQuery("(min-width 600px)".to_string())));
+    let error = StyleSheet::parse_from_str(r#"{ "@media tv": { ".item": {} } }"#);
+    assert_eq!(error, Err(StyleSheetError::InvalidMediaQuery("tv".to_string())));
+}
@@ -0,0 +1,129 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::events::{raw_id, BasicEvent, ClickThreshold, MouseButton};
+use dom::traits::{TEvent, TEventManager, TMouseEvent};
+use dom::types::{Closure, EventType, VirtualEventType};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn moving_in_fires_over_and_enter_down_from_the_root() {
+    let (mut tree, row_id, first_id, second_id) = row();
+    let root_id = tree.root().id();
+    let mut manager = start(&mut tree);
+
+    let events = mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, MouseButton::Main, (5, 5), 0);
+    assert_eq!(
+        fired(&events),
+        vec![
+            (EventType::MouseOver, first_id),
+            (EventType::MouseEnter, root_id),
+            (EventType::MouseEnter, row_id),
+            (EventType::MouseEnter, first_id),
+            (EventType::MouseMove, first_id),
+        ]
+    );
+    assert_eq!(manager.hovered(), Some(first_id));
+
+    // Siblings share their ancestors, so only the boxes themselves are left and entered.
+    let events = mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, MouseButton::Main, (60, 5), 0);
+    assert_eq!(
+        fired(&events),
+        vec![
+            (EventType::MouseOut, first_id),
+            (EventType::MouseLeave, first_id),
+            (EventType::MouseOver, second_id),
+            (EventType::MouseEnter, second_id),
+            (EventType::MouseMove, second_id),
+        ]
+    );
+    assert_eq!(events[0].related_target(), Some(raw_id(second_id)));
+    assert_eq!(events[2].related_target(), Some(raw_id(first_id)));
+
+    // Moving within the same node only fires the move.
+    let events = mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, MouseButton::Main, (70, 5), 0);
+    assert_eq!(fired(&events), vec![(EventType::MouseMove, second_id)]);
+}
+
+#[test]
+fn press_and_release_fire_a_click() {
+    let (mut tree, _, first_id, _) = row();
+    let mut manager = start(&mut tree);
+
+    let events = click(&mut manager, &mut tree, (5, 5));
+    assert_eq!(
+        fired(&events),
+        vec![
+            (EventType::MouseDown, first_id),
+            (EventType::MouseUp, first_id),
+            (EventType::Click, first_id),
+        ]
+    );
+}
+
+#[test]
+fn click_across_nodes_targets_the_common_ancestor() {
+    let (mut tree, row_id, _, second_id) = row();
+    let mut manager = start(&mut tree);
+
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseDown, MouseButton::Main, (5, 5), 0);
+    let events = mouse(&mut manager, &mut tree, VirtualEventType::MouseUp, MouseButton::Main, (60, 5), 0);
+    assert_eq!(fired(&events), vec![(EventType::MouseUp, second_id), (EventType::Click, row_id)]);
+}
+
+#[test]
+fn double_click_within_the_threshold() {
+    let (mut tree, _, first_id, _) = row();
+    let mut manager = start(&mut tree);
+    manager.set_click_threshold(ClickThreshold { time: 500, distance: 0 });
+
+    let mut clicks = vec![];
+    // A click too late to pair, a double click, a click starting over and one too far away to pair.
+    for &(point, timestamp) in &[((5, 5), 0), ((5, 5), 1000), ((5, 5), 1200), ((5, 5), 1400), ((6, 6), 1500)] {
+        let main = MouseButton::Main;
+        mouse(&mut manager, &mut tree, VirtualEventType::MouseDown, main, point, timestamp);
+        let events = mouse(&mut manager, &mut tree, VirtualEventType::MouseUp, main, point, timestamp);
+        clicks.push(fired(&events[1..]));
+    }
+    let single = vec![(EventType::Click, first_id)];
+    let double = vec![(EventType::Click, first_id), (EventType::DblClick, first_id)];
+    assert_eq!(clicks, vec![single.clone(), single.clone(), double, single.clone(), single]);
+}
+
+#[test]
+fn secondary_button_fires_aux_click_and_context_menu() {
+    let (mut tree, _, first_id, _) = row();
+    let mut manager = start(&mut tree);
+    let secondary = MouseButton::Secondary;
+
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseDown, secondary, (5, 5), 0);
+    let events = mouse(&mut manager, &mut tree, VirtualEventType::MouseUp, secondary, (5, 5), 0);
+    assert_eq!(
+        fired(&events),
+        vec![
+            (EventType::MouseUp, first_id),
+            (EventType::AuxClick, first_id),
+            (EventType::ContextMenu, first_id),
+        ]
+    );
+    assert_eq!(manager.take_requests().len(), 1);
+}
+
+#[test]
+fn clicks_bubble_to_ancestor_listeners() {
+    let (mut tree, row_id, first_id, _) = row();
+    let mut manager = start(&mut tree);
+    let targets = Rc::new(RefCell::new(vec![]));
+
+    let seen = targets.clone();
+    manager.add_event_listener(row_id, EventType::Click, Closure::new(move |event: BasicEvent| {
+        seen.borrow_mut().push(event.target());
+    }));
+
+    click(&mut manager, &mut tree, (5, 5));
+    assert_eq!(*targets.borrow(), vec![raw_id(first_id)]);
+}
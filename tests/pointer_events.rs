@@ -0,0 +1,108 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::events::{BasicEvent, EventManager, MouseButton, MouseEvent, PointerEvent, PointerType};
+use dom::traits::TEventManager;
+use dom::tree::DOMTree;
+use dom::types::{EventType, VirtualEventType};
+
+fn touch(
+    manager: &mut EventManager,
+    tree: &mut DOMTree<BasicEvent>,
+    ty: VirtualEventType,
+    pointer_id: u32,
+    point: (u32, u32),
+) -> Vec<(EventType, Id)> {
+    let pointer = PointerEvent::new(pointer_id, PointerType::Touch, false);
+    manager.receive_pointer_event(ty, (pointer, MouseEvent::new(MouseButton::Main, point, 0)));
+    fired(&manager.broadcast_and_collect_events(tree))
+}
+
+#[test]
+fn captured_pointers_are_retargeted() {
+    let (mut tree, row_id, first_id, _) = row();
+    let root_id = tree.root().id();
+    let mut manager = start(&mut tree);
+
+    // Capture can only be set while the pointer is pressed.
+    assert!(!manager.set_pointer_capture(first_id, 2));
+
+    let events = touch(&mut manager, &mut tree, VirtualEventType::PointerDown, 2, (5, 5));
+    assert_eq!(
+        events,
+        vec![
+            (EventType::PointerOver, first_id),
+            (EventType::PointerEnter, root_id),
+            (EventType::PointerEnter, row_id),
+            (EventType::PointerEnter, first_id),
+            (EventType::PointerDown, first_id),
+            (EventType::TouchStart, first_id),
+        ]
+    );
+
+    assert!(manager.set_pointer_capture(first_id, 2));
+    assert!(manager.has_pointer_capture(first_id, 2));
+    let events = touch(&mut manager, &mut tree, VirtualEventType::PointerMove, 2, (60, 5));
+    assert_eq!(
+        events,
+        vec![
+            (EventType::GotPointerCapture, first_id),
+            (EventType::PointerMove, first_id),
+            (EventType::TouchMove, first_id),
+        ]
+    );
+
+    // Lifting a touch releases the capture and leaves the surface.
+    let events = touch(&mut manager, &mut tree, VirtualEventType::PointerUp, 2, (60, 5));
+    assert_eq!(
+        events,
+        vec![
+            (EventType::PointerUp, first_id),
+            (EventType::TouchEnd, first_id),
+            (EventType::LostPointerCapture, first_id),
+            (EventType::PointerOut, first_id),
+            (EventType::PointerLeave, first_id),
+            (EventType::PointerLeave, row_id),
+            (EventType::PointerLeave, root_id),
+        ]
+    );
+    assert!(!manager.has_pointer_capture(first_id, 2));
+}
+
+#[test]
+fn pointers_are_tracked_independently() {
+    let (mut tree, _, first_id, second_id) = row();
+    let mut manager = start(&mut tree);
+
+    touch(&mut manager, &mut tree, VirtualEventType::PointerDown, 2, (5, 5));
+    let events = touch(&mut manager, &mut tree, VirtualEventType::PointerDown, 3, (60, 5));
+    assert_eq!(events[0], (EventType::PointerOver, second_id));
+    assert!(!events.iter().any(|&(_, id)| id == first_id));
+
+    let events = touch(&mut manager, &mut tree, VirtualEventType::PointerMove, 2, (10, 5));
+    assert_eq!(events, vec![(EventType::PointerMove, first_id), (EventType::TouchMove, first_id)]);
+
+    // Touch events keep going to where the touch started.
+    let events = touch(&mut manager, &mut tree, VirtualEventType::PointerMove, 3, (5, 5));
+    assert!(events.contains(&(EventType::TouchMove, second_id)));
+    assert!(events.contains(&(EventType::PointerMove, first_id)));
+}
+
+#[test]
+fn primary_pointers_fire_compatibility_mouse_events() {
+    let (mut tree, _, first_id, _) = row();
+    let mut manager = start(&mut tree);
+    let pointer = PointerEvent::new(1, PointerType::Mouse, true);
+    let data = MouseEvent::new(MouseButton::Main, (5, 5), 0);
+
+    manager.receive_pointer_event(VirtualEventType::PointerDown, (pointer.clone(), data.clone()));
+    manager.receive_pointer_event(VirtualEventType::PointerUp, (pointer, data));
+    let events = fired(&manager.broadcast_and_collect_events(&mut tree));
+    let position = |ty| events.iter().position(|&event| event == (ty, first_id)).unwrap();
+    assert!(position(EventType::PointerDown) < position(EventType::MouseDown));
+    assert!(position(EventType::PointerUp) < position(EventType::MouseUp));
+    assert!(position(EventType::MouseUp) < position(EventType::Click));
+}
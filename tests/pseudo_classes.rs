@@ -0,0 +1,130 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::{element, with_simple_attribute, Id};
+use dom::events::{BasicEvent, EventManager, MouseButton, MouseEvent};
+use dom::style::StyleSheet;
+use dom::traits::{TEventManager, TLayoutNode};
+use dom::tree::DOMTree;
+use dom::types::{KnownAttributeName, KnownElementName, VirtualEventType};
+
+const SHEET: &str = r#"{
+    ".row": { "flex-direction": "row", "align-items": "flex-start" },
+    ".button": { "width": "50px", "height": "50px" },
+    ".button:hover": { "width": "60px" },
+    ".button:active": { "width": "70px" },
+    "input": { "width": "10px", "height": "10px" },
+    "input:checked": { "height": "20px" },
+    ".row:focus-within input": { "width": "30px" }
+}"#;
+
+// A row holding a button and a checkbox.
+fn tree(sheet: &str) -> (DOMTree<BasicEvent>, Id, Id) {
+    let mut tree = DOMTree::default();
+    tree.add_stylesheet(StyleSheet::parse_from_str(sheet).unwrap());
+    let class = |tag, class| element(tag, KnownAttributeName::Class, class);
+    let row_id = tree.root_mut().append(class(KnownElementName::Div, "row")).id();
+    let button_id = tree.get_mut(row_id).append(class(KnownElementName::Div, "button")).id();
+    let input = with_simple_attribute(class(KnownElementName::Input, ""), "type", "checkbox");
+    let input_id = tree.get_mut(row_id).append(input).id();
+    tree.generate_layout_tree();
+    tree.resize(200.0, 100.0);
+    (tree, button_id, input_id)
+}
+
+fn mouse(manager: &mut EventManager, tree: &mut DOMTree<BasicEvent>, ty: VirtualEventType, point: (u32, u32)) {
+    manager.receive_mouse_event(ty, MouseEvent::new(MouseButton::Main, point, 0));
+    manager.broadcast_events(tree);
+    tree.layout_viewport();
+}
+
+#[test]
+fn hover_and_active_restyle() {
+    let (mut tree, button_id, _) = tree(SHEET);
+    let mut manager = EventManager::default();
+    let width = |tree: &DOMTree<BasicEvent>| tree.client_rect(button_id).size.width;
+    assert_eq!(width(&tree), 50.0);
+
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, (5, 5));
+    assert!(tree.get(button_id).element_state().hovered);
+    assert_eq!(width(&tree), 60.0);
+
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseDown, (5, 5));
+    assert!(tree.get(button_id).element_state().active);
+    assert_eq!(width(&tree), 70.0);
+
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseUp, (5, 5));
+    assert_eq!(width(&tree), 60.0);
+
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, (150, 90));
+    assert!(!tree.get(button_id).element_state().hovered);
+    assert_eq!(width(&tree), 50.0);
+}
+
+#[test]
+fn properties_only_set_while_hovered_are_dropped() {
+    let sheet = r#"{
+        ".row": { "flex-direction": "row", "align-items": "flex-start" },
+        ".button": { "width": "50px", "height": "50px" },
+        ".button:hover": { "margin-top": "15px" }
+    }"#;
+    let (mut tree, button_id, _) = tree(sheet);
+    let mut manager = EventManager::default();
+    let top = |tree: &DOMTree<BasicEvent>| tree.client_rect(button_id).position.top;
+
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, (5, 5));
+    assert_eq!(top(&tree), 15.0);
+
+    mouse(&mut manager, &mut tree, VirtualEventType::MouseMove, (150, 90));
+    assert_eq!(top(&tree), 0.0);
+}
+
+#[test]
+fn focus_and_checked_restyle() {
+    let (mut tree, _, input_id) = tree(SHEET);
+    let mut manager = EventManager::default();
+    let row_id = tree.get(input_id).parent_id().unwrap();
+
+    manager.focus(Some(input_id));
+    manager.broadcast_events(&mut tree);
+    tree.layout_viewport();
+    assert!(tree.get(input_id).element_state().focused);
+    assert!(tree.get(row_id).element_state().focus_within);
+    assert!(!tree.get(row_id).element_state().focused);
+    assert_eq!(tree.client_rect(input_id).size.width, 30.0);
+
+    // States changed by the application are taken from the control, without waiting for a broadcast.
+    tree.get_mut(input_id).set_checked(true);
+    tree.get_mut(input_id).set_disabled(true);
+    tree.layout_viewport();
+    assert!(tree.get(input_id).element_state().checked);
+    assert!(tree.get(input_id).element_state().disabled);
+    assert_eq!(tree.client_rect(input_id).size.height, 20.0);
+}
+
+#[test]
+fn state_changes_only_restyle_when_selectors_depend_on_them() {
+    let (mut tree, button_id, _) = tree(r#"{ ".button": { "width": "50px", "height": "50px" } }"#);
+    let mut manager = EventManager::default();
+
+    manager.receive_mouse_event(VirtualEventType::MouseMove, MouseEvent::new(MouseButton::Main, (5, 5), 0));
+    manager.broadcast_events(&mut tree);
+    assert!(tree.get(button_id).element_state().hovered);
+    assert!(!tree.root().layout_node.has_tainted_descendants());
+}
+
+#[test]
+fn state_changes_only_restyle_the_nodes_flipped_rules_match() {
+    let (mut tree, button_id, input_id) = tree(SHEET);
+    let row_id = tree.get(input_id).parent_id().unwrap();
+    let mut manager = EventManager::default();
+
+    manager.receive_mouse_event(VirtualEventType::MouseMove, MouseEvent::new(MouseButton::Main, (5, 5), 0));
+    manager.broadcast_events(&mut tree);
+    assert!(tree.get(row_id).element_state().hovered);
+    assert!(tree.get(button_id).layout_node.is_tainted());
+    assert!(!tree.get(row_id).layout_node.is_tainted());
+    assert!(!tree.get(input_id).layout_node.is_tainted());
+}
@@ -0,0 +1,77 @@
+extern crate dom;
+extern crate jss;
+extern crate serde_json;
+
+mod common;
+
+use common::*;
+use dom::events::{BasicEvent, EventManager, EventRecording, MouseButton, MouseEvent, PendingInput};
+use dom::node::DOMAttributeValue;
+use dom::style::StyleSheet;
+use dom::tree::DOMTree;
+use dom::types::{ClientSize, EventType, KnownAttributeName, Viewport, VirtualEventType};
+
+const SHEET: &str = r#"{
+    "@keyframes fade": { "from": { "color": "#000000" }, "to": { "color": "#ffffff" } },
+    ".fade": { "animation": "fade 1s linear" }
+}"#;
+
+// The second box of the row is animated, the first one is clicked.
+fn tree() -> (DOMTree<BasicEvent>, Id, Id) {
+    let (mut tree, _, first_id, second_id) = row();
+    tree.add_stylesheet(StyleSheet::parse_from_str(SHEET).unwrap());
+    tree.get_mut(second_id)
+        .set_attribute(KnownAttributeName::Class, DOMAttributeValue::from("fade"));
+    tree.generate_layout_tree();
+    (tree, first_id, second_id)
+}
+
+fn inputs() -> Vec<PendingInput> {
+    let viewport = Viewport {
+        size: ClientSize {
+            width: 300.0,
+            height: 100.0,
+        },
+        ..Viewport::default()
+    };
+    let mouse = |ty, timestamp| PendingInput::Mouse(ty, MouseEvent::new(MouseButton::Main, (5, 5), timestamp));
+    vec![
+        PendingInput::Viewport(viewport),
+        PendingInput::Tick(0),
+        mouse(VirtualEventType::MouseMove, 10),
+        mouse(VirtualEventType::MouseDown, 20),
+        mouse(VirtualEventType::MouseUp, 30),
+        PendingInput::Tick(1000),
+    ]
+}
+
+#[test]
+fn replay_reproduces_viewport_changes_and_ticks() {
+    let (mut tree, first_id, second_id) = tree();
+    let mut manager = EventManager::default();
+    manager.start_recording();
+
+    let mut recorded = vec![];
+    for input in inputs() {
+        manager.receive_input(input);
+        recorded.push(fired(&manager.broadcast_and_collect_events(&mut tree)));
+        tree.layout_viewport();
+    }
+    let recording = manager.stop_recording().unwrap();
+    assert_eq!(recording.len(), 6);
+    assert_eq!(recording.inputs()[5].timestamp, 1000);
+    assert_eq!(recorded[0], vec![(EventType::Resize, tree.document().id())]);
+    assert_eq!(recorded[5], vec![(EventType::AnimationEnd, second_id)]);
+    assert!(recorded[4].contains(&(EventType::Click, first_id)));
+
+    let json = serde_json::to_string(&recording).unwrap();
+    let recording: EventRecording = serde_json::from_str(&json).unwrap();
+
+    let (mut tree, _, _) = tree();
+    let mut replayed = vec![];
+    recording.replay(&mut EventManager::default(), &mut tree, |_, events, tree| {
+        replayed.push(fired(events));
+        assert_eq!(tree.viewport().size.width, 300.0);
+    });
+    assert_eq!(replayed, recorded);
+}
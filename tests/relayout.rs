@@ -0,0 +1,49 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::node::DOMNode;
+
+#[test]
+fn relayout_reports_the_nodes_moved_by_dirty_ones() {
+    let (mut tree, row_id, first_id, second_id) = row();
+    let inner_id = tree.get_mut(second_id).append(div(BOX)).id();
+    tree.get_mut(inner_id).append(DOMNode::from("inner"));
+    tree.generate_layout_tree();
+    tree.resize(200.0, 100.0);
+
+    // The second box moves over, its contents are laid out relative to it and don't.
+    tree.get_mut(first_id)
+        .set_styles(style(r#"{ "width": "80px", "height": "50px" }"#));
+    let changed = tree.layout_viewport();
+    assert_eq!(changed.len(), 2);
+    assert!(changed.contains(&first_id) && changed.contains(&second_id));
+    assert_eq!(tree.client_rect(second_id).left(), 80.0);
+    assert_eq!(tree.client_rect(inner_id).left(), 80.0);
+
+    // Growing taller resizes the row too.
+    tree.get_mut(first_id)
+        .set_styles(style(r#"{ "width": "80px", "height": "60px" }"#));
+    let changed = tree.layout_viewport();
+    assert_eq!(changed.len(), 2);
+    assert!(changed.contains(&first_id) && changed.contains(&row_id));
+
+    assert_eq!(tree.layout_viewport(), vec![]);
+}
+
+#[test]
+fn reordered_children_are_laid_out_in_their_new_order() {
+    let (mut tree, row_id, first_id, second_id) = row();
+    tree.generate_layout_tree();
+    tree.resize(200.0, 100.0);
+
+    tree.get_mut(first_id).detach();
+    tree.get_mut(row_id).append_with_layout(first_id).unwrap();
+    let changed = tree.layout_viewport();
+    assert!(changed.contains(&first_id) && changed.contains(&second_id));
+    assert_eq!(tree.client_rect(second_id).left(), 0.0);
+    assert_eq!(tree.client_rect(first_id).left(), 50.0);
+    assert_eq!(tree.hit_test((5, 5)), Some(second_id));
+}
@@ -0,0 +1,75 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::events::{BasicEvent, EventManager, MouseButton, MouseEvent, WheelDeltaMode, WheelEvent};
+use dom::traits::{TEvent, TEventManager};
+use dom::tree::DOMTree;
+use dom::types::{Closure, EventType, KnownElementName};
+
+const ITEM: &str = r#"{ "width": "100px", "height": "100px" }"#;
+
+// A 100x50 scroll view holding two 100x100 items.
+fn tree() -> (DOMTree<BasicEvent>, Id, Id, Id) {
+    let mut tree = DOMTree::default();
+    let scroll = styled(KnownElementName::ScrollView, r#"{ "width": "100px", "height": "50px" }"#);
+    let scroll_id = tree.root_mut().append(scroll).id();
+    let first_id = tree.get_mut(scroll_id).append(div(ITEM)).id();
+    let second_id = tree.get_mut(scroll_id).append(div(ITEM)).id();
+    (tree, scroll_id, first_id, second_id)
+}
+
+fn wheel(manager: &mut EventManager, tree: &mut DOMTree<BasicEvent>, dy: f64) -> Vec<(EventType, Id)> {
+    let wheel = WheelEvent::new(0.0, dy, WheelDeltaMode::Pixel);
+    manager.receive_wheel_event((wheel, MouseEvent::new(MouseButton::Main, (5, 5), 0)));
+    let events = fired(&manager.broadcast_and_collect_events(tree));
+    tree.layout_viewport();
+    events
+}
+
+#[test]
+fn wheel_scrolls_the_scroll_view_and_fires_scroll() {
+    let (mut tree, scroll_id, first_id, second_id) = tree();
+    let mut manager = start(&mut tree);
+
+    let events = wheel(&mut manager, &mut tree, 30.0);
+    assert_eq!(events, vec![(EventType::Wheel, first_id), (EventType::Scroll, scroll_id)]);
+    assert_eq!(tree.get(scroll_id).scroll.offset().top, 30.0);
+    assert_eq!(tree.client_rect(first_id).top(), -30.0);
+
+    // Offsets are clamped to the scrolling area.
+    wheel(&mut manager, &mut tree, 500.0);
+    assert_eq!(tree.get(scroll_id).scroll.offset().top, 150.0);
+    assert_eq!(tree.hit_test((5, 5)), Some(second_id));
+
+    // Nothing left to scroll, so nothing fires.
+    let events = wheel(&mut manager, &mut tree, 10.0);
+    assert_eq!(events, vec![(EventType::Wheel, second_id)]);
+}
+
+#[test]
+fn prevent_default_cancels_wheel_scrolling() {
+    let (mut tree, scroll_id, first_id, _) = tree();
+    let mut manager = start(&mut tree);
+    let listener = Closure::new(|event: BasicEvent| event.prevent_default());
+    manager.add_event_listener(first_id, EventType::Wheel, listener);
+
+    let events = wheel(&mut manager, &mut tree, 30.0);
+    assert_eq!(events, vec![(EventType::Wheel, first_id)]);
+    assert_eq!(tree.get(scroll_id).scroll.offset().top, 0.0);
+}
+
+#[test]
+fn programmatic_scrolls_fire_once_per_broadcast() {
+    let (mut tree, scroll_id, _, _) = tree();
+    let mut manager = start(&mut tree);
+
+    assert!(tree.get_mut(scroll_id).scroll_to(0.0, 20.0));
+    assert!(tree.get_mut(scroll_id).scroll_by(0.0, 20.0));
+    assert!(!tree.get_mut(scroll_id).scroll_by(0.0, 0.0));
+    let events = fired(&manager.broadcast_and_collect_events(&mut tree));
+    assert_eq!(events, vec![(EventType::Scroll, scroll_id)]);
+    assert!(manager.broadcast_and_collect_events(&mut tree).is_empty());
+}
@@ -0,0 +1,199 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::{element, style, Id};
+use dom::events::BasicEvent;
+use dom::node::{DOMAttributeValue, DOMNode, DOMTagName};
+use dom::style::{Selector, SimpleSelector, StyleSheet, StyleSheetError};
+use dom::tree::DOMTree;
+use dom::types::{KnownAttributeName, KnownElementName};
+
+const SHEET: &str = r#"{
+    ".item": { "width": "20px", "height": "10px" },
+    "div": { "width": "10px", "height": "10px" },
+    "#main > .item": { "width": "30px" },
+    ".wide .item": { "width": "40px" },
+    ".hidden": { "visibility": "hidden" },
+    ".tall": { "height": "25px" }
+}"#;
+
+// A `#main` column holding a plain div and an `.item`.
+fn tree() -> (DOMTree<BasicEvent>, Id, Id, Id) {
+    let mut tree = DOMTree::default();
+    tree.add_stylesheet(StyleSheet::parse_from_str(SHEET).unwrap());
+    let div = |name, value| element(KnownElementName::Div, name, value);
+    let main_id = tree.root_mut().append(div(KnownAttributeName::Id, "main")).id();
+    let plain_id = tree.get_mut(main_id).append(DOMNode::from(DOMTagName::from(KnownElementName::Div))).id();
+    let item_id = tree.get_mut(main_id).append(div(KnownAttributeName::Class, "item")).id();
+    tree.generate_layout_tree();
+    tree.resize(200.0, 100.0);
+    (tree, main_id, plain_id, item_id)
+}
+
+#[test]
+fn rules_cascade_by_specificity_then_source_order() {
+    let (tree, _, plain_id, item_id) = tree();
+    assert_eq!(tree.client_rect(plain_id).size.width, 10.0);
+    assert_eq!(tree.client_rect(item_id).size.width, 30.0);
+    assert_eq!(tree.client_rect(item_id).size.height, 10.0);
+    assert_eq!(tree.get(item_id).declarations().len(), 2);
+}
+
+#[test]
+fn inline_style_wins_over_rules() {
+    let (mut tree, _, plain_id, _) = tree();
+    tree.get_mut(plain_id)
+        .set_attribute(KnownAttributeName::Style, DOMAttributeValue::from(r#"{ "width": "50px" }"#));
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(plain_id).size.width, 50.0);
+    assert_eq!(tree.client_rect(plain_id).size.height, 10.0);
+}
+
+#[test]
+fn inline_jss_styles_win_over_rules() {
+    let (mut tree, _, plain_id, _) = tree();
+    tree.get_mut(plain_id)
+        .set_attribute(KnownAttributeName::Style, DOMAttributeValue::Styles(style(r#"{ "width": "50px" }"#)));
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(plain_id).size.width, 50.0);
+    assert_eq!(tree.client_rect(plain_id).size.height, 10.0);
+}
+
+#[test]
+fn rules_only_override_the_authored_properties_they_set() {
+    let (mut tree, _, _, item_id) = tree();
+    tree.get_mut(item_id).set_styles(style(r#"{ "width": "60px", "max-width": "15px" }"#));
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(item_id).size.width, 15.0);
+
+    assert!(tree.remove_stylesheet(0).is_some());
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(item_id).size.width, 15.0);
+    assert_eq!(tree.client_rect(item_id).size.height, 0.0);
+}
+
+#[test]
+fn class_changes_restyle_descendants() {
+    let (mut tree, main_id, _, item_id) = tree();
+    tree.get_mut(main_id)
+        .set_attribute(KnownAttributeName::Class, DOMAttributeValue::from("wide"));
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(item_id).size.width, 30.0);
+
+    tree.get_mut(main_id).remove_attribute(KnownAttributeName::Id);
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(item_id).size.width, 40.0);
+
+    tree.get_mut(main_id).remove_attribute(KnownAttributeName::Class);
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(item_id).size.width, 20.0);
+}
+
+#[test]
+fn removing_a_class_drops_the_layout_properties_it_set() {
+    let (mut tree, main_id, _, _) = tree();
+    let span = element(KnownElementName::Span, KnownAttributeName::Class, "tall");
+    let span_id = tree.get_mut(main_id).append(span).id();
+    tree.generate_layout_tree();
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(span_id).size.height, 25.0);
+
+    tree.get_mut(span_id).remove_attribute(KnownAttributeName::Class);
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(span_id).size.height, 0.0);
+}
+
+#[test]
+fn removing_stylesheets_restores_authored_styles() {
+    let (mut tree, _, _, item_id) = tree();
+    tree.get_mut(item_id)
+        .set_attribute(KnownAttributeName::Class, DOMAttributeValue::from("item hidden"));
+    tree.layout_viewport();
+    assert!(!tree.is_visible(item_id));
+
+    assert!(tree.remove_stylesheet(0).is_some());
+    tree.layout_viewport();
+    assert!(tree.is_visible(item_id));
+    assert!(tree.get(item_id).declarations().is_empty());
+    assert_eq!(tree.client_rect(item_id).size.height, 0.0);
+}
+
+#[test]
+fn selectors_parse_with_specificity() {
+    let selectors = Selector::parse_list("div.item[data-kind='a'] > span, #main *").unwrap();
+    assert_eq!(selectors.len(), 2);
+    assert_eq!(selectors[0].specificity(), (0, 2, 2));
+    assert_eq!(selectors[1].specificity(), (1, 0, 0));
+
+    let error = StyleSheet::parse_from_str(r#"{ "div >": {} }"#).unwrap_err();
+    assert_eq!(error, StyleSheetError::InvalidSelector("div >".to_string()));
+    assert!(StyleSheet::parse_from_str("[]").is_err());
+}
+
+#[test]
+fn unterminated_attribute_values_are_invalid() {
+    assert!(Selector::parse_list(r#"[data-kind="a"]"#).is_ok());
+    assert!(Selector::parse_list(r#"[data-kind="a"#).is_err());
+
+    let error = StyleSheet::parse_from_str(r#"{ "[data-kind='a": {} }"#).unwrap_err();
+    assert_eq!(error, StyleSheetError::InvalidSelector("[data-kind='a".to_string()));
+}
+
+#[test]
+fn commas_within_attribute_values_dont_split_selectors() {
+    let selectors = Selector::parse_list(r#"[title="a,b"], span[title='c,d'] , div"#).unwrap();
+    assert_eq!(selectors.len(), 3);
+    let title = SimpleSelector::Attribute("title".to_string(), Some("a,b".to_string()));
+    assert_eq!(selectors[0].first.simple, vec![title]);
+    assert_eq!(selectors[2].first.simple, vec![SimpleSelector::Type("div".to_string())]);
+
+    assert!(Selector::parse_list(r#"[title="a,b], div"#).is_err());
+}
+
+#[test]
+fn names_match_through_the_name_table() {
+    let (mut tree, _, _, item_id) = tree();
+    tree.get_mut(item_id)
+        .set_attribute(KnownAttributeName::Title, DOMAttributeValue::from("a,b"));
+
+    let matches = |tree: &DOMTree<BasicEvent>, source: &str| {
+        let selector = Selector::parse(source).unwrap();
+        selector.matches(tree, item_id)
+    };
+    assert!(matches(&tree, "DIV"));
+    assert!(matches(&tree, r#"[title="a,b"]"#));
+    assert!(matches(&tree, r#"[TITLE="a,b"]"#));
+    assert!(!matches(&tree, "d-iv"));
+    assert!(!matches(&tree, r#"[ti-tle="a,b"]"#));
+}
+
+#[test]
+fn shorthands_cascade_side_by_side() {
+    let sheet = r#"{
+        "div": { "margin-left": "5px", "width": "10px", "height": "10px" },
+        ".spaced": { "margin": "20px 30px" },
+        ".spaced.left": { "margin-left": "1px" }
+    }"#;
+    let mut tree: DOMTree<BasicEvent> = DOMTree::default();
+    tree.add_stylesheet(StyleSheet::parse_from_str(sheet).unwrap());
+    let spaced = element(KnownElementName::Div, KnownAttributeName::Class, "spaced");
+    let spaced_id = tree.root_mut().append(spaced).id();
+    let left = element(KnownElementName::Div, KnownAttributeName::Class, "spaced left");
+    let left_id = tree.root_mut().append(left).id();
+    tree.generate_layout_tree();
+    tree.resize(200.0, 200.0);
+
+    // The more specific shorthand wins over the longhand, and a later longhand over one side of it.
+    assert_eq!(tree.get(spaced_id).declarations().get("margin-left"), Some(&"30px".into()));
+    assert_eq!(tree.client_rect(spaced_id).position.left, 30.0);
+    assert_eq!(tree.client_rect(left_id).position.left, 1.0);
+    assert_eq!(tree.client_rect(left_id).position.top, 70.0);
+
+    let keys = tree.get(left_id).declarations().keys().cloned().collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        vec!["margin-left", "width", "height", "margin-top", "margin-right", "margin-bottom"]
+    );
+}
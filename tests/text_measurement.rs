@@ -0,0 +1,73 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::*;
+use dom::events::BasicEvent;
+use dom::layout::MonospaceTextMeasurer;
+use dom::node::DOMNode;
+use dom::traits::TTextMeasurer;
+use dom::tree::DOMTree;
+use dom::types::{TextFont, TextMetrics};
+
+fn font(size: f32) -> TextFont {
+    TextFont {
+        size,
+        ..TextFont::default()
+    }
+}
+
+// A text node in a 50px wide column which doesn't stretch it.
+fn tree() -> (DOMTree<BasicEvent>, Id) {
+    let mut tree = DOMTree::default();
+    let column_id = tree
+        .root_mut()
+        .append(div(r#"{ "width": "50px", "align-items": "flex-start" }"#))
+        .id();
+    let text_id = tree.get_mut(column_id).append(DOMNode::from("hello world")).id();
+    tree.generate_layout_tree();
+    tree.resize(200.0, 100.0);
+    (tree, text_id)
+}
+
+#[test]
+fn monospace_text_advances_evenly() {
+    let measurer = MonospaceTextMeasurer::default();
+    let metrics = TextMetrics {
+        width: 66.0,
+        height: 12.0,
+        baseline: 9.0,
+    };
+    assert_eq!(measurer.measure("hello world", &font(10.0), None), metrics);
+    assert_eq!(measurer.measure("  \n ", &font(10.0), None), TextMetrics::default());
+}
+
+#[test]
+fn monospace_text_wraps_between_words() {
+    let measurer = MonospaceTextMeasurer::default();
+    let metrics = measurer.measure("hello world", &font(10.0), Some(40.0));
+    assert_eq!((metrics.width, metrics.height), (30.0, 24.0));
+
+    // Words longer than a line overflow it, line breaks are kept.
+    let metrics = measurer.measure("overflowing", &font(10.0), Some(40.0));
+    assert_eq!((metrics.width, metrics.height), (66.0, 12.0));
+    let metrics = measurer.measure("a\nb c", &font(10.0), None);
+    assert_eq!((metrics.width, metrics.height), (18.0, 24.0));
+}
+
+#[test]
+fn text_nodes_take_space_once_a_measurer_is_installed() {
+    let (mut tree, text_id) = tree();
+    assert_eq!(tree.client_rect(text_id).size.height, 0.0);
+
+    tree.set_text_measurer(MonospaceTextMeasurer::default(), font(10.0));
+    let changed = tree.layout_viewport();
+    assert!(changed.contains(&text_id));
+    let size = tree.client_rect(text_id).size;
+    assert_eq!((size.width, size.height), (30.0, 24.0));
+
+    tree.remove_text_measurer();
+    tree.layout_viewport();
+    assert_eq!(tree.client_rect(text_id).size.height, 0.0);
+}
@@ -0,0 +1,82 @@
+extern crate dom;
+extern crate jss;
+
+use dom::events::{raw_id, BasicEvent, EventManager};
+use dom::node::{DOMNode, DOMTagName};
+use dom::traits::TEvent;
+use dom::tree::DOMTree;
+use dom::types::{EventType, KnownElementName, Viewport};
+use jss::types::{Case, StyleBuilder};
+
+fn tree() -> DOMTree<BasicEvent> {
+    let style = StyleBuilder::default()
+        .case(Case::Ignore)
+        .parse_from_str(r#"{ "width": "50%", "height": "100px" }"#)
+        .unwrap();
+    let mut tree = DOMTree::default();
+    tree.root_mut()
+        .append(DOMNode::from((DOMTagName::from(KnownElementName::Div), style)));
+    tree.generate_layout_tree();
+    tree
+}
+
+#[test]
+fn resize_reflows_within_the_viewport() {
+    let mut tree = tree();
+    let document_id = tree.document().id();
+
+    tree.resize(400.0, 300.0);
+    assert_eq!(tree.client_rect(document_id).size.width, 200.0);
+
+    let changed = tree.resize(800.0, 300.0);
+    assert!(changed.contains(&document_id));
+    assert_eq!(tree.client_rect(document_id).size.width, 400.0);
+    assert_eq!(tree.client_rect(tree.root().id()).size.height, 300.0);
+
+    assert_eq!(tree.resize(800.0, 300.0), vec![]);
+}
+
+#[test]
+fn resize_fires_once_at_the_document() {
+    let mut tree = tree();
+    let mut manager = EventManager::default();
+    let document_id = tree.document().id();
+
+    tree.resize(400.0, 300.0);
+    tree.resize(500.0, 300.0);
+    let events = manager.broadcast_and_collect_events(&mut tree);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].ty(), EventType::Resize);
+    assert_eq!(events[0].target(), raw_id(document_id));
+
+    // Changing the device pixel ratio only isn't a resize.
+    let viewport = Viewport {
+        device_pixel_ratio: 2.0,
+        ..tree.viewport()
+    };
+    tree.set_viewport(viewport);
+    assert_eq!(tree.viewport().physical_size(), (1000, 600));
+    assert!(manager.broadcast_and_collect_events(&mut tree).is_empty());
+}
+
+#[test]
+fn viewport_units_resolve_against_the_viewport() {
+    let mut tree = tree();
+    let style = StyleBuilder::default()
+        .case(Case::Ignore)
+        .parse_from_str(r#"{ "width": "50vw", "height": "10px" }"#)
+        .unwrap();
+    let child_id = tree
+        .document_mut()
+        .append(DOMNode::from((DOMTagName::from(KnownElementName::Div), style)))
+        .id();
+    tree.generate_layout_tree();
+
+    // Half of the viewport rather than half of the parent, which is itself half of the viewport.
+    tree.resize(400.0, 300.0);
+    assert_eq!(tree.client_rect(child_id).size.width, 200.0);
+
+    let changed = tree.resize(600.0, 300.0);
+    assert!(changed.contains(&child_id));
+    assert_eq!(tree.client_rect(child_id).size.width, 300.0);
+}
@@ -0,0 +1,79 @@
+extern crate dom;
+extern crate jss;
+
+mod common;
+
+use common::{div, set_inline_style, style, Id, BOX, ROW};
+use dom::events::BasicEvent;
+use dom::node::DOMNode;
+use dom::tree::DOMTree;
+use dom::types::{Direction, Visibility};
+
+// A row holding two 50x50 boxes, each with some text.
+fn row() -> (DOMTree<BasicEvent>, Id, Id) {
+    let mut tree = DOMTree::default();
+    let row_id = tree.root_mut().append(div(ROW)).id();
+    let first_id = tree.get_mut(row_id).append(div(BOX)).id();
+    let second_id = tree.get_mut(row_id).append(div(BOX)).id();
+    tree.get_mut(first_id).append(DOMNode::from("first"));
+    tree.get_mut(second_id).append(DOMNode::from("second"));
+
+    tree.generate_layout_tree();
+    tree.relayout(200, 100, Direction::LTR);
+    (tree, first_id, second_id)
+}
+
+#[test]
+fn hidden_attribute_toggles_without_rebuilding() {
+    let (mut tree, first_id, second_id) = row();
+    let root_id = tree.root().id();
+    assert_eq!(tree.client_rect(second_id).left(), 50.0);
+
+    tree.get_mut(first_id).set_hidden(true);
+    tree.relayout(200, 100, Direction::LTR);
+    assert!(!tree.is_rendered(first_id));
+    assert_eq!(tree.client_rect(second_id).left(), 0.0);
+    assert_eq!(tree.hit_test((10, 10)), Some(second_id));
+    assert_eq!(tree.text_content(root_id), "second");
+
+    tree.get_mut(first_id).set_hidden(false);
+    tree.relayout(200, 100, Direction::LTR);
+    assert_eq!(tree.client_rect(second_id).left(), 50.0);
+    assert_eq!(tree.hit_test((10, 10)), Some(first_id));
+    assert_eq!(tree.text_content(root_id), "firstsecond");
+}
+
+#[test]
+fn display_none_style_takes_node_out_of_layout() {
+    let (mut tree, first_id, second_id) = row();
+    tree.get_mut(first_id)
+        .set_styles(style(r#"{ "width": "50px", "height": "50px", "display": "none" }"#));
+    tree.relayout(200, 100, Direction::LTR);
+    assert_eq!(tree.client_rect(second_id).left(), 0.0);
+    assert_eq!(tree.hit_test((10, 10)), Some(second_id));
+}
+
+#[test]
+fn visibility_hidden_keeps_layout_but_is_not_hit() {
+    let (mut tree, first_id, second_id) = row();
+    let root_id = tree.root().id();
+    let inner_id = tree.get_mut(first_id).append(div("{}")).id();
+    tree.get_mut(inner_id).append(DOMNode::from("inner"));
+    set_inline_style(&mut tree, first_id, r#"{ "visibility": "hidden" }"#);
+    tree.relayout(200, 100, Direction::LTR);
+
+    assert_eq!(tree.client_rect(second_id).left(), 50.0);
+    assert_eq!(tree.hit_test((10, 10)), tree.get(first_id).parent_id());
+    assert_eq!(tree.text_content(root_id), "second");
+
+    // Descendants inherit it, but can opt back in.
+    set_inline_style(&mut tree, inner_id, r#"{ "visibility": "visible" }"#);
+    tree.relayout(200, 100, Direction::LTR);
+    assert_eq!(tree.computed_visibility(first_id), Visibility::Hidden);
+    assert_eq!(tree.text_content(root_id), "innersecond");
+
+    set_inline_style(&mut tree, first_id, "{}");
+    tree.relayout(200, 100, Direction::LTR);
+    assert_eq!(tree.hit_test((10, 10)), Some(first_id));
+    assert_eq!(tree.text_content(root_id), "firstinnersecond");
+}